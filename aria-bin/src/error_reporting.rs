@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::{collections::HashMap, vec};
 
-use aria_compiler::do_compile::CompilationError;
+use aria_compiler::do_compile::{CompilationError, CompilationWarning};
 use aria_parser::ast::{ParserError, SourcePointer};
 use ariadne::{Color, Label, Report, ReportKind, Source};
 use haxby_vm::{
@@ -9,9 +9,11 @@ use haxby_vm::{
     vm::VirtualMachine,
 };
 
+// sources are keyed by label rather than by file name, so that each backtrace
+// frame gets its own header, even when several frames are in the same file
 #[derive(Default, Debug, Clone)]
 pub struct StringCache {
-    buffers: HashMap<String, Source>,
+    buffers: HashMap<String, (String, Source)>,
 }
 
 impl ariadne::Cache<String> for StringCache {
@@ -19,14 +21,14 @@ impl ariadne::Cache<String> for StringCache {
 
     fn fetch(
         &mut self,
-        path: &String,
+        key: &String,
     ) -> Result<&Source<<Self as ariadne::Cache<String>>::Storage>, impl std::fmt::Debug> {
-        Ok::<&Source, Source>(&self.buffers[path])
+        Ok::<&Source, Source>(&self.buffers[key].1)
     }
 
     #[allow(refining_impl_trait)]
-    fn display<'a>(&self, path: &'a String) -> Option<impl std::fmt::Display + 'a> {
-        Some(Box::new((*path).clone()))
+    fn display<'a>(&self, key: &'a String) -> Option<impl std::fmt::Display + 'a> {
+        Some(Box::new(self.buffers[key].0.clone()))
     }
 }
 
@@ -38,30 +40,36 @@ pub(crate) type PrintableReport<'a> = (
 fn build_report_from_msg_and_location<'a>(
     msg: &str,
     locations: Vec<SourcePointer>,
+) -> PrintableReport<'a> {
+    build_report_of_kind_from_msg_and_location(ReportKind::Error, Color::Magenta, msg, locations)
+}
+
+fn build_report_of_kind_from_msg_and_location<'a>(
+    kind: ReportKind<'a>,
+    color: Color,
+    msg: &str,
+    locations: Vec<SourcePointer>,
 ) -> PrintableReport<'a> {
     let config = ariadne::Config::default().with_index_type(ariadne::IndexType::Byte);
-    let magenta = Color::Magenta;
-    let mut report = Report::build(ReportKind::Error, ("unknown".to_owned(), 0..0))
+    let mut report = Report::build(kind, ("unknown".to_owned(), 0..0))
         .with_message(msg)
         .with_config(config);
     let mut cache = StringCache::default();
     for (idx, loc) in locations.iter().enumerate() {
-        let loc = loc.clone();
+        let key = format!("{idx}:{}", loc.buffer.name);
         report = report.with_label(
-            Label::new((
-                loc.buffer.name.clone(),
-                loc.location.start..loc.location.stop,
-            ))
-            .with_message("here")
-            .with_order(idx as i32)
-            .with_color(magenta),
+            Label::new((key.clone(), loc.location.start..loc.location.stop))
+                .with_message("here")
+                .with_order(idx as i32)
+                .with_color(color),
         );
-        if !cache.buffers.contains_key(&loc.buffer.name) {
-            cache.buffers.insert(
+        cache.buffers.insert(
+            key,
+            (
                 loc.buffer.name.clone(),
                 Source::from((*loc.buffer.content).clone()),
-            );
-        }
+            ),
+        );
     }
     (report.finish(), cache)
 }
@@ -76,6 +84,11 @@ pub(crate) fn print_report_from_compiler_error(err: &CompilationError) {
     report.eprint(cache).unwrap();
 }
 
+pub(crate) fn print_report_from_compiler_warning(warning: &CompilationWarning) {
+    let (report, cache) = build_report_from_compiler_warning(warning);
+    report.eprint(cache).unwrap();
+}
+
 pub(crate) fn print_report_from_parser_error(err: &ParserError) {
     let (report, cache) = build_report_from_parser_error(err);
     report.eprint(cache).unwrap();
@@ -118,6 +131,19 @@ pub(crate) fn build_report_from_compiler_error<'a>(
     build_report_from_msg_and_location(&msg, vec![loc.clone()])
 }
 
+pub(crate) fn build_report_from_compiler_warning<'a>(
+    warning: &'a CompilationWarning,
+) -> PrintableReport<'a> {
+    let msg = warning.reason.to_string();
+    let loc = &warning.loc;
+    build_report_of_kind_from_msg_and_location(
+        ReportKind::Warning,
+        Color::Yellow,
+        &msg,
+        vec![loc.clone()],
+    )
+}
+
 pub(crate) fn build_report_from_parser_error<'a>(err: &'a ParserError) -> PrintableReport<'a> {
     let msg = &err.msg;
     let loc = &err.loc;
@@ -1,5 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
-use aria_compiler::{CompilationOptions, compile_from_ast};
+use std::path::Path;
+
+use aria_compiler::{
+    CompilationOptions, compile_from_ast,
+    module::CompiledModule,
+    module_file::{MODULE_FILE_EXTENSION, read_module_file, write_module_file},
+};
 use aria_parser::ast::{
     SourceBuffer,
     prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
@@ -13,8 +19,8 @@ use haxby_vm::{
 use crate::{
     Args,
     error_reporting::{
-        print_report_from_compiler_error, print_report_from_parser_error,
-        print_report_from_vm_error, print_report_from_vm_exception,
+        print_report_from_compiler_error, print_report_from_compiler_warning,
+        print_report_from_parser_error, print_report_from_vm_error, print_report_from_vm_exception,
     },
 };
 
@@ -22,21 +28,19 @@ impl From<&Args> for CompilationOptions {
     fn from(value: &Args) -> Self {
         CompilationOptions {
             optimize: !value.disable_optimizer,
+            ..Default::default()
         }
     }
 }
 
 // To permit return Err(report_blah(x)) where report_blah(x) -> ()
 #[allow(clippy::unit_arg)]
-fn eval_buffer(
-    sb: SourceBuffer,
-    vm: &mut VirtualMachine,
-    args: &Args,
-) -> Result<RuntimeModule, ()> {
+fn compile_buffer(sb: SourceBuffer, args: &Args) -> Result<CompiledModule, ()> {
     let ast = match source_to_ast(&sb) {
         Ok(ast) => ast,
-        Err(err) => {
-            return Err(print_report_from_parser_error(&err));
+        Err(errs) => {
+            errs.iter().for_each(print_report_from_parser_error);
+            return Err(());
         }
     };
 
@@ -56,6 +60,21 @@ fn eval_buffer(
         }
     };
 
+    c_module
+        .warnings
+        .iter()
+        .for_each(print_report_from_compiler_warning);
+
+    Ok(c_module)
+}
+
+// To permit return Err(report_blah(x)) where report_blah(x) -> ()
+#[allow(clippy::unit_arg)]
+fn eval_module(
+    c_module: CompiledModule,
+    vm: &mut VirtualMachine,
+    args: &Args,
+) -> Result<RuntimeModule, ()> {
     if args.dump_mod {
         let mod_buffer = PrintoutAccumulator::default();
         let output = c_module.prettyprint(mod_buffer).value();
@@ -89,17 +108,66 @@ fn eval_buffer(
     }
 }
 
+fn is_module_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext == MODULE_FILE_EXTENSION)
+}
+
+fn load_module_file(path: &str) -> Result<CompiledModule, ()> {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(err) => {
+            println!("error reading compiled module: {err}");
+            return Err(());
+        }
+    };
+
+    read_module_file(&data).map_err(|err| println!("error loading {path}: {err}"))
+}
+
 pub(crate) fn file_eval(path: &str, args: &Args) -> i32 {
     let mut vm = VirtualMachine::with_options(VmOptions::from(args));
 
-    let buffer = SourceBuffer::file(path);
-    match buffer {
-        Ok(src) => match eval_buffer(src, &mut vm, args) {
-            Ok(_) => 0,
-            Err(_) => 1,
+    let c_module = if is_module_file(path) {
+        load_module_file(path)
+    } else {
+        match SourceBuffer::file(path) {
+            Ok(src) => compile_buffer(src, args),
+            Err(err) => {
+                println!("error reading source file: {err}");
+                return 1;
+            }
+        }
+    };
+
+    match c_module.and_then(|m| eval_module(m, &mut vm, args)) {
+        Ok(_) => 0,
+        Err(_) => 1,
+    }
+}
+
+pub(crate) fn file_compile(path: &str, args: &Args) -> i32 {
+    let c_module = match SourceBuffer::file(path) {
+        Ok(src) => match compile_buffer(src, args) {
+            Ok(m) => m,
+            Err(_) => return 1,
         },
         Err(err) => {
             println!("error reading source file: {err}");
+            return 1;
+        }
+    };
+
+    let output = match &args.output {
+        Some(output) => output.into(),
+        None => Path::new(path).with_extension(MODULE_FILE_EXTENSION),
+    };
+
+    match std::fs::write(&output, write_module_file(&c_module)) {
+        Ok(_) => 0,
+        Err(err) => {
+            println!("error writing {}: {err}", output.display());
             1
         }
     }
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{SourceBuffer, format_source};
+
+use crate::{FmtArgs, error_reporting::print_report_from_parser_error};
+
+// To permit return Err(report_blah(x)) where report_blah(x) -> ()
+#[allow(clippy::unit_arg)]
+fn format_file(path: &str, check: bool) -> Result<(), ()> {
+    let src = match SourceBuffer::file(path) {
+        Ok(src) => src,
+        Err(err) => return Err(println!("error reading source file: {err}")),
+    };
+
+    let formatted = match format_source(&src) {
+        Ok(formatted) => formatted,
+        Err(errs) => return Err(errs.iter().for_each(print_report_from_parser_error)),
+    };
+
+    if formatted == *src.content {
+        Ok(())
+    } else if check {
+        Err(println!("{path} is not formatted"))
+    } else {
+        std::fs::write(path, formatted).map_err(|err| println!("error writing {path}: {err}"))
+    }
+}
+
+pub(crate) fn file_fmt(args: &FmtArgs) -> i32 {
+    let mut exit_code = 0;
+    for path in &args.paths {
+        if format_file(path, args.check).is_err() {
+            exit_code = 1;
+        }
+    }
+    exit_code
+}
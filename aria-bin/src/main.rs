@@ -1,17 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 mod error_reporting;
 mod file_eval;
+mod file_fmt;
 mod repl_eval;
 
 #[cfg(test)]
 mod test;
 
-use clap::Parser;
-use haxby_vm::vm::{VirtualMachine, VmOptions};
+use clap::{Parser, Subcommand};
+use haxby_vm::{
+    module_cache::ModuleCache,
+    vm::{VirtualMachine, VmOptions},
+};
 
 #[derive(Default, Parser, Debug)]
-#[command(author, name = "aria", version = env!("CARGO_PKG_VERSION"), about, trailing_var_arg = true)]
+#[command(author, name = "aria", version = env!("CARGO_PKG_VERSION"), about, trailing_var_arg = true, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// The name of the program file to run
     path: Option<String>,
     /// Should the VM trace instruction execution
@@ -29,6 +35,15 @@ struct Args {
     /// Turn off compile-time optimizations
     #[arg(long("disable-optimizer"))]
     disable_optimizer: bool,
+    /// Always compile imported modules from source, bypassing the compiled module cache
+    #[arg(long("no-module-cache"))]
+    no_module_cache: bool,
+    /// Compile the program file to a .ariac module instead of running it
+    #[arg(long("compile"))]
+    compile: bool,
+    /// Where to write the compiled module (defaults to the program file with a .ariac extension)
+    #[arg(short('o'), long("output"), requires("compile"))]
+    output: Option<String>,
     #[arg(trailing_var_arg = true)]
     extra_args: Vec<String>,
     #[arg(long("print-lib-path"))]
@@ -38,6 +53,22 @@ struct Args {
     no_repl_preamble: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Rewrite source files in the canonical Aria formatting
+    Fmt(FmtArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct FmtArgs {
+    /// The source files to format
+    #[arg(required = true)]
+    paths: Vec<String>,
+    /// Do not write any file; list the ones that are not formatted, and fail if there are any
+    #[arg(long("check"))]
+    check: bool,
+}
+
 impl From<&Args> for VmOptions {
     fn from(value: &Args) -> Self {
         let mut options = VmOptions::default();
@@ -50,6 +81,10 @@ impl From<&Args> for VmOptions {
         }
 
         options.vm_args = value.extra_args.clone();
+        options.optimize_imports = !value.disable_optimizer;
+        if !value.no_module_cache {
+            options.module_cache = ModuleCache::default_dir();
+        }
 
         options
     }
@@ -65,13 +100,21 @@ fn print_lib_paths() {
 fn main_loop() -> i32 {
     let args = Args::parse();
 
+    if let Some(Command::Fmt(fmt_args)) = &args.command {
+        return file_fmt::file_fmt(fmt_args);
+    }
+
     if args.print_lib_path {
         print_lib_paths();
         return 0;
     }
 
     if let Some(path) = &args.path {
-        file_eval::file_eval(path, &args)
+        if args.compile {
+            file_eval::file_compile(path, &args)
+        } else {
+            file_eval::file_eval(path, &args)
+        }
     } else {
         repl_eval::repl_eval(&args)
     }
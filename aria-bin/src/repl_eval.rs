@@ -16,8 +16,8 @@ use reedline::{DefaultPrompt, Reedline, Validator};
 use crate::{
     Args,
     error_reporting::{
-        build_report_from_compiler_error, build_report_from_parser_error,
-        build_report_from_vm_error, build_report_from_vm_exception,
+        build_report_from_compiler_error, build_report_from_compiler_warning,
+        build_report_from_parser_error, build_report_from_vm_error, build_report_from_vm_exception,
         print_report_from_compiler_error, print_report_from_parser_error,
         print_report_from_vm_error, print_report_from_vm_exception,
     },
@@ -175,8 +175,9 @@ impl<'a> Repl<'a> {
         let sb = SourceBuffer::stdin_with_name(repl_module_preamble, "repl");
         let ast = match source_to_ast(&sb) {
             Ok(ast) => ast,
-            Err(err) => {
-                return Err(print_report_from_parser_error(&err));
+            Err(errs) => {
+                errs.iter().for_each(print_report_from_parser_error);
+                return Err(());
             }
         };
 
@@ -216,7 +217,7 @@ impl<'a> Repl<'a> {
     fn try_parse_source(
         &mut self,
         buffer: &SourceBuffer,
-    ) -> Result<aria_parser::ast::ParsedModule, aria_parser::ast::ParserError> {
+    ) -> Result<aria_parser::ast::ParsedModule, Vec<aria_parser::ast::ParserError>> {
         source_to_ast(buffer)
     }
 
@@ -240,8 +241,11 @@ impl<'a> Repl<'a> {
 
         let mut ast = match parsed_source {
             Ok(ast) => ast,
-            Err(err) => {
-                return Err(self.print_error_report(build_report_from_parser_error(&err)));
+            Err(errs) => {
+                for err in &errs {
+                    self.print_error_report(build_report_from_parser_error(err));
+                }
+                return Err(());
             }
         };
 
@@ -267,6 +271,11 @@ impl<'a> Repl<'a> {
             }
         };
 
+        c_module
+            .warnings
+            .iter()
+            .for_each(|w| self.print_error_report(build_report_from_compiler_warning(w)));
+
         if self.args.dump_mod {
             let mod_buffer = PrintoutAccumulator::default();
             let output = c_module.prettyprint(mod_buffer).value();
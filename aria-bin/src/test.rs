@@ -279,3 +279,175 @@ fn repl_test_invalid_literal() {
         &[],
     );
 }
+
+#[test]
+fn repl_reports_compiler_warnings() {
+    let cmdline_options = Args::default();
+    let mut repl = build_test_repl(&cmdline_options);
+
+    run_passing_repl_line(
+        &mut repl,
+        "func foo(x) { val y = 1; return x; }",
+        &["local variable 'y' is never read"],
+    );
+    run_passing_repl_line(
+        &mut repl,
+        "func bar(x) { if x { val x = 2; return x; println(x); } return 0; }",
+        &[
+            "declaration of 'x' shadows an existing binding",
+            "unreachable code",
+        ],
+    );
+    run_passing_repl_line(
+        &mut repl,
+        "import Map from aria.structures.map;",
+        &["imported name 'Map' is never used"],
+    );
+}
+
+fn check_format(input: &str, expected: &str) {
+    use aria_parser::ast::{
+        SourceBuffer, format_source,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+        source_to_ast,
+    };
+
+    let dump_ast = |src: &str| {
+        source_to_ast(&SourceBuffer::stdin(src))
+            .unwrap()
+            .prettyprint(PrintoutAccumulator::default())
+            .value()
+    };
+
+    let formatted = format_source(&SourceBuffer::stdin(input)).unwrap();
+    assert_eq!(formatted, expected);
+    assert_eq!(
+        format_source(&SourceBuffer::stdin(&formatted)).unwrap(),
+        formatted
+    );
+    assert_eq!(dump_ast(input), dump_ast(&formatted));
+}
+
+#[test]
+fn fmt_indents_and_spaces_code() {
+    check_format(
+        "func foo(x,y){if x>y{return x+1;}else{return [1,2,...y];}}\nval z=foo(1,2);",
+        r#"func foo(x, y) {
+    if x > y {
+        return x + 1;
+    } else {
+        return [1, 2, ...y];
+    }
+}
+val z = foo(1, 2);
+"#,
+    );
+}
+
+#[test]
+fn fmt_keeps_comments_and_blank_lines() {
+    check_format(
+        r#"# a module
+import Map from aria.structures.map;
+func main() {
+    val a = 1; # the first one
+    val b = 2;
+
+
+    # a group of its own
+    println(a+b);
+}
+# the end
+"#,
+        r#"# a module
+import Map from aria.structures.map;
+func main() {
+    val a = 1; # the first one
+    val b = 2;
+
+    # a group of its own
+    println(a + b);
+}
+# the end
+"#,
+    );
+}
+
+#[test]
+fn fmt_keeps_comments_inside_statements_in_place() {
+    check_format(
+        r#"func main() {
+  val a = 1 + # one
+      2;
+  println(a);
+}
+"#,
+        r#"func main() {
+    val a = 1 + # one
+      2;
+    println(a);
+}
+"#,
+    );
+}
+
+#[test]
+fn fmt_keeps_comments_after_opening_brace() {
+    check_format(
+        r#"func main() {
+    if a>1 { # cond
+        println(a);
+    }
+    while false { # empty
+    }
+}
+"#,
+        r#"func main() {
+    if a > 1 { # cond
+        println(a);
+    }
+    while false { # empty
+    }
+}
+"#,
+    );
+}
+
+#[test]
+fn fmt_wraps_long_lists_with_trailing_commas() {
+    check_format(
+        r#"val x = some_function_name("a rather long argument", "another long argument", "and one more argument");
+val y = ["short", 'quotes'];
+"#,
+        r#"val x = some_function_name(
+    "a rather long argument",
+    "another long argument",
+    "and one more argument",
+);
+val y = ["short", 'quotes'];
+"#,
+    );
+}
+
+#[test]
+fn prettyprint_escapes_string_text() {
+    use aria_parser::ast::{
+        SourceBuffer,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+        source_to_ast,
+    };
+
+    let dump_ast = |src: &str| {
+        source_to_ast(&SourceBuffer::stdin(src))
+            .unwrap()
+            .prettyprint(PrintoutAccumulator::default())
+            .value()
+    };
+
+    let printed = dump_ast(r#"val s = f"a\nb\t{x} \x22q\x22 'z' {{}} \\" + 'say "hi"';"#);
+    assert_eq!(
+        printed,
+        "val s = f\"a\\nb\\t{x} \\x22q\\x22 'z' {{}} \\\\\" + 'say \"hi\"';\n"
+    );
+    assert_eq!(dump_ast(&printed), printed);
+}
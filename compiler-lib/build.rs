@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// the sources that decide what bytecode the compiler emits for a given program;
+// compiled modules record a fingerprint of them, so that any change to the compiler,
+// the opcodes or the parser invalidates modules compiled before it, even between
+// two builds of the same Aria version
+const FINGERPRINTED_SOURCES: &[&str] = &["src", "../opcodes-lib/src", "../parser-lib/src"];
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+// FNV-1a, which unlike the hashers in std gives the same result on every toolchain
+fn hash_bytes(hash: u64, bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(hash, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn main() {
+    let mut files = vec![];
+    for dir in FINGERPRINTED_SOURCES {
+        println!("cargo:rerun-if-changed={dir}");
+        collect_files(Path::new(dir), &mut files);
+    }
+    files.sort();
+
+    let mut hash = 0xcbf29ce484222325;
+    for file in &files {
+        let name = file.to_string_lossy().replace('\\', "/");
+        let content = fs::read(file).unwrap_or_default();
+        hash = hash_bytes(hash, name.bytes().chain([0]));
+        // checkouts with Windows line endings build the same compiler
+        hash = hash_bytes(hash, content.into_iter().filter(|&b| b != b'\r').chain([0]));
+    }
+    println!("cargo:rustc-env=ARIA_COMPILER_FINGERPRINT={hash:016x}");
+}
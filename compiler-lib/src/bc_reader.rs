@@ -24,7 +24,7 @@ pub enum DecodeError {
 pub type DecodeResult<T> = Result<T, DecodeError>;
 
 impl BytecodeReader {
-    fn read_u8(&mut self) -> DecodeResult<u8> {
+    pub(crate) fn read_u8(&mut self) -> DecodeResult<u8> {
         if self.idx < self.data.len() {
             let val = self.data[self.idx];
             self.idx += 1;
@@ -34,11 +34,11 @@ impl BytecodeReader {
         }
     }
 
-    fn read_u16(&mut self) -> DecodeResult<u16> {
+    pub(crate) fn read_u16(&mut self) -> DecodeResult<u16> {
         Ok(u16::from_le_bytes([self.read_u8()?, self.read_u8()?]))
     }
 
-    fn read_u32(&mut self) -> DecodeResult<u32> {
+    pub(crate) fn read_u32(&mut self) -> DecodeResult<u32> {
         Ok(u32::from_le_bytes([
             self.read_u8()?,
             self.read_u8()?,
@@ -47,6 +47,32 @@ impl BytecodeReader {
         ]))
     }
 
+    pub(crate) fn read_u64(&mut self) -> DecodeResult<u64> {
+        let mut bytes = [0_u8; 8];
+        for b in &mut bytes {
+            *b = self.read_u8()?;
+        }
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn read_bytes(&mut self, n: usize) -> DecodeResult<&[u8]> {
+        if n <= self.data.len() - self.idx {
+            let val = &self.data[self.idx..self.idx + n];
+            self.idx += n;
+            Ok(val)
+        } else {
+            Err(DecodeError::EndOfStream)
+        }
+    }
+
+    fn read_const_index(&mut self, wide: bool) -> DecodeResult<u32> {
+        if wide {
+            self.read_u32()
+        } else {
+            Ok(self.read_u16()?.into())
+        }
+    }
+
     pub fn jump_to_index(&mut self, idx: usize) {
         self.idx = idx;
     }
@@ -70,10 +96,18 @@ impl BytecodeReader {
                 }
             },
         };
+        let (next, wide) = if next == haxby_opcodes::OPCODE_WIDE_CONST {
+            (
+                self.read_u8().map_err(|_| DecodeError::InsufficientData)?,
+                true,
+            )
+        } else {
+            (next, false)
+        };
         match next {
             haxby_opcodes::OPCODE_NOP => Ok(Opcode::Nop),
             haxby_opcodes::OPCODE_PUSH => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| Ok(Opcode::Push(b))),
             haxby_opcodes::OPCODE_PUSH_0 => Ok(Opcode::Push0),
             haxby_opcodes::OPCODE_PUSH_1 => Ok(Opcode::Push1),
@@ -108,6 +142,14 @@ impl BytecodeReader {
             haxby_opcodes::OPCODE_NEG => Ok(Opcode::Neg),
             haxby_opcodes::OPCODE_SHL => Ok(Opcode::ShiftLeft),
             haxby_opcodes::OPCODE_SHR => Ok(Opcode::ShiftRight),
+            haxby_opcodes::OPCODE_ADD_CHECKED => Ok(Opcode::AddChecked),
+            haxby_opcodes::OPCODE_SUB_CHECKED => Ok(Opcode::SubChecked),
+            haxby_opcodes::OPCODE_MUL_CHECKED => Ok(Opcode::MulChecked),
+            haxby_opcodes::OPCODE_DIV_CHECKED => Ok(Opcode::DivChecked),
+            haxby_opcodes::OPCODE_REM_CHECKED => Ok(Opcode::RemChecked),
+            haxby_opcodes::OPCODE_NEG_CHECKED => Ok(Opcode::NegChecked),
+            haxby_opcodes::OPCODE_SHL_CHECKED => Ok(Opcode::ShiftLeftChecked),
+            haxby_opcodes::OPCODE_SHR_CHECKED => Ok(Opcode::ShiftRightChecked),
             haxby_opcodes::OPCODE_NOT => Ok(Opcode::Not),
             haxby_opcodes::OPCODE_READ_LOCAL => self
                 .read_u8()
@@ -125,17 +167,17 @@ impl BytecodeReader {
                     Ok(Opcode::TypedefLocal(b))
                 }),
             haxby_opcodes::OPCODE_READ_NAMED => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::ReadNamed(b))
                 }),
             haxby_opcodes::OPCODE_WRITE_NAMED => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::WriteNamed(b))
                 }),
             haxby_opcodes::OPCODE_TYPEDEF_NAMED => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::TypedefNamed(b))
                 }),
@@ -150,12 +192,12 @@ impl BytecodeReader {
                     Ok(Opcode::WriteIndex(b))
                 }),
             haxby_opcodes::OPCODE_READ_ATTRIBUTE => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::ReadAttribute(b))
                 }),
             haxby_opcodes::OPCODE_WRITE_ATTRIBUTE => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::WriteAttribute(b))
                 }),
@@ -164,6 +206,31 @@ impl BytecodeReader {
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::ReadUplevel(b))
                 }),
+            haxby_opcodes::OPCODE_READ_LOCAL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::ReadLocalWide(b))
+                }),
+            haxby_opcodes::OPCODE_WRITE_LOCAL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::WriteLocalWide(b))
+                }),
+            haxby_opcodes::OPCODE_TYPEDEF_LOCAL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::TypedefLocalWide(b))
+                }),
+            haxby_opcodes::OPCODE_READ_UPLEVEL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::ReadUplevelWide(b))
+                }),
+            haxby_opcodes::OPCODE_STORE_UPLEVEL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::StoreUplevelWide(b))
+                }),
             haxby_opcodes::OPCODE_LOGICAL_AND => Ok(Opcode::LogicalAnd),
             haxby_opcodes::OPCODE_LOGICAL_OR => Ok(Opcode::LogicalOr),
             haxby_opcodes::OPCODE_XOR => Ok(Opcode::Xor),
@@ -197,10 +264,52 @@ impl BytecodeReader {
                 };
                 Ok(Opcode::JumpIfArgSupplied(arg0, arg1))
             }
+            haxby_opcodes::OPCODE_JUMP_IF_ARG_SUPPLIED_WIDE => {
+                let arg0 = match self.read_u16() {
+                    Ok(w) => w,
+                    Err(_) => {
+                        return Err(DecodeError::InsufficientData);
+                    }
+                };
+                let arg1 = match self.read_u16() {
+                    Ok(w) => w,
+                    Err(_) => {
+                        return Err(DecodeError::InsufficientData);
+                    }
+                };
+                Ok(Opcode::JumpIfArgSuppliedWide(arg0, arg1))
+            }
             haxby_opcodes::OPCODE_CALL => self
                 .read_u8()
                 .map_or(Err(DecodeError::InsufficientData), |b| Ok(Opcode::Call(b))),
+            haxby_opcodes::OPCODE_CALL_WIDE => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::CallWide(b))
+                }),
+            haxby_opcodes::OPCODE_CALL_NAMED => {
+                let arg0 = match self.read_u16() {
+                    Ok(w) => w,
+                    Err(_) => {
+                        return Err(DecodeError::InsufficientData);
+                    }
+                };
+                let arg1 = match self.read_u16() {
+                    Ok(w) => w,
+                    Err(_) => {
+                        return Err(DecodeError::InsufficientData);
+                    }
+                };
+                Ok(Opcode::CallNamed(arg0, arg1))
+            }
+            haxby_opcodes::OPCODE_CALL_SPREAD => self
+                .read_u16()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::CallSpread(b))
+                }),
             haxby_opcodes::OPCODE_RETURN => Ok(Opcode::Return),
+            haxby_opcodes::OPCODE_YIELD => Ok(Opcode::Yield),
+            haxby_opcodes::OPCODE_DEFER => Ok(Opcode::Defer),
             haxby_opcodes::OPCODE_TRY_ENTER => self
                 .read_u16()
                 .map_or(Err(DecodeError::InsufficientData), |b| {
@@ -213,6 +322,12 @@ impl BytecodeReader {
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::BuildList(b))
                 }),
+            haxby_opcodes::OPCODE_EXTEND_LIST => Ok(Opcode::ExtendList),
+            haxby_opcodes::OPCODE_BUILD_RANGE => self
+                .read_u8()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::BuildRange(b))
+                }),
             haxby_opcodes::OPCODE_BUILD_FUNCTION => self
                 .read_u8()
                 .map_or(Err(DecodeError::InsufficientData), |b| {
@@ -233,7 +348,7 @@ impl BytecodeReader {
                         return Err(DecodeError::InsufficientData);
                     }
                 };
-                let w1 = match self.read_u16() {
+                let w1 = match self.read_const_index(wide) {
                     Ok(w) => w,
                     Err(_) => {
                         return Err(DecodeError::InsufficientData);
@@ -248,7 +363,7 @@ impl BytecodeReader {
                         return Err(DecodeError::InsufficientData);
                     }
                 };
-                let w1 = match self.read_u16() {
+                let w1 = match self.read_const_index(wide) {
                     Ok(w) => w,
                     Err(_) => {
                         return Err(DecodeError::InsufficientData);
@@ -264,7 +379,7 @@ impl BytecodeReader {
                         return Err(DecodeError::InsufficientData);
                     }
                 };
-                let w1 = match self.read_u16() {
+                let w1 = match self.read_const_index(wide) {
                     Ok(w) => w,
                     Err(_) => {
                         return Err(DecodeError::InsufficientData);
@@ -273,7 +388,7 @@ impl BytecodeReader {
                 Ok(Opcode::NewEnumVal(b0, w1))
             }
             haxby_opcodes::OPCODE_ENUM_CHECK_IS_CASE => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::EnumCheckIsCase(b))
                 }),
@@ -283,20 +398,25 @@ impl BytecodeReader {
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::TryUnwrapProtocol(b))
                 }),
+            haxby_opcodes::OPCODE_BUILD_STRING => self
+                .read_u32()
+                .map_or(Err(DecodeError::InsufficientData), |b| {
+                    Ok(Opcode::BuildString(b))
+                }),
             haxby_opcodes::OPCODE_ISA => Ok(Opcode::Isa),
             haxby_opcodes::OPCODE_IMPORT => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::Import(b))
                 }),
             haxby_opcodes::OPCODE_LIFT_MODULE => Ok(Opcode::LiftModule),
             haxby_opcodes::OPCODE_LOAD_DYLIB => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::LoadDylib(b))
                 }),
             haxby_opcodes::OPCODE_ASSERT => self
-                .read_u16()
+                .read_const_index(wide)
                 .map_or(Err(DecodeError::InsufficientData), |b| {
                     Ok(Opcode::Assert(b))
                 }),
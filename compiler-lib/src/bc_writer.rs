@@ -7,17 +7,17 @@ pub(crate) struct BytecodeWriter {
 }
 
 impl BytecodeWriter {
-    fn write_u8(&mut self, val: u8) -> &mut Self {
+    pub(crate) fn write_u8(&mut self, val: u8) -> &mut Self {
         self.data.push(val);
         self
     }
 
-    fn write_u16(&mut self, val: u16) -> &mut Self {
+    pub(crate) fn write_u16(&mut self, val: u16) -> &mut Self {
         let bytes = val.to_le_bytes();
         self.write_u8(bytes[0]).write_u8(bytes[1])
     }
 
-    fn write_u32(&mut self, val: u32) -> &mut Self {
+    pub(crate) fn write_u32(&mut self, val: u32) -> &mut Self {
         let bytes = val.to_le_bytes();
         self.write_u8(bytes[0])
             .write_u8(bytes[1])
@@ -25,10 +25,40 @@ impl BytecodeWriter {
             .write_u8(bytes[3])
     }
 
+    pub(crate) fn write_u64(&mut self, val: u64) -> &mut Self {
+        for b in val.to_le_bytes() {
+            self.write_u8(b);
+        }
+        self
+    }
+
+    pub(crate) fn write_bytes(&mut self, val: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(val);
+        self
+    }
+
+    fn write_const_prefix(&mut self, idx: u32) -> &mut Self {
+        if idx > u16::MAX as u32 {
+            self.write_u8(haxby_opcodes::OPCODE_WIDE_CONST)
+        } else {
+            self
+        }
+    }
+
+    fn write_const_index(&mut self, idx: u32) -> &mut Self {
+        match u16::try_from(idx) {
+            Ok(idx) => self.write_u16(idx),
+            Err(_) => self.write_u32(idx),
+        }
+    }
+
     pub(crate) fn write_opcode(&mut self, op: &Opcode) -> &mut Self {
         match op {
             Opcode::Nop => self.write_u8(haxby_opcodes::OPCODE_NOP),
-            Opcode::Push(n) => self.write_u8(haxby_opcodes::OPCODE_PUSH).write_u16(*n),
+            Opcode::Push(n) => self
+                .write_const_prefix(*n)
+                .write_u8(haxby_opcodes::OPCODE_PUSH)
+                .write_const_index(*n),
             Opcode::Push0 => self.write_u8(haxby_opcodes::OPCODE_PUSH_0),
             Opcode::Push1 => self.write_u8(haxby_opcodes::OPCODE_PUSH_1),
             Opcode::PushTrue => self.write_u8(haxby_opcodes::OPCODE_PUSH_TRUE),
@@ -51,6 +81,14 @@ impl BytecodeWriter {
             Opcode::Neg => self.write_u8(haxby_opcodes::OPCODE_NEG),
             Opcode::ShiftLeft => self.write_u8(haxby_opcodes::OPCODE_SHL),
             Opcode::ShiftRight => self.write_u8(haxby_opcodes::OPCODE_SHR),
+            Opcode::AddChecked => self.write_u8(haxby_opcodes::OPCODE_ADD_CHECKED),
+            Opcode::SubChecked => self.write_u8(haxby_opcodes::OPCODE_SUB_CHECKED),
+            Opcode::MulChecked => self.write_u8(haxby_opcodes::OPCODE_MUL_CHECKED),
+            Opcode::DivChecked => self.write_u8(haxby_opcodes::OPCODE_DIV_CHECKED),
+            Opcode::RemChecked => self.write_u8(haxby_opcodes::OPCODE_REM_CHECKED),
+            Opcode::NegChecked => self.write_u8(haxby_opcodes::OPCODE_NEG_CHECKED),
+            Opcode::ShiftLeftChecked => self.write_u8(haxby_opcodes::OPCODE_SHL_CHECKED),
+            Opcode::ShiftRightChecked => self.write_u8(haxby_opcodes::OPCODE_SHR_CHECKED),
             Opcode::Not => self.write_u8(haxby_opcodes::OPCODE_NOT),
             Opcode::Equal => self.write_u8(haxby_opcodes::OPCODE_EQ),
             Opcode::ReadLocal(n) => self.write_u8(haxby_opcodes::OPCODE_READ_LOCAL).write_u8(*n),
@@ -61,27 +99,47 @@ impl BytecodeWriter {
                 .write_u8(haxby_opcodes::OPCODE_TYPEDEF_LOCAL)
                 .write_u8(*n),
             Opcode::ReadNamed(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_READ_NAMED)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::WriteNamed(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_WRITE_NAMED)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::TypedefNamed(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_TYPEDEF_NAMED)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::ReadIndex(n) => self.write_u8(haxby_opcodes::OPCODE_READ_INDEX).write_u8(*n),
             Opcode::WriteIndex(n) => self
                 .write_u8(haxby_opcodes::OPCODE_WRITE_INDEX)
                 .write_u8(*n),
             Opcode::ReadAttribute(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_READ_ATTRIBUTE)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::WriteAttribute(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_WRITE_ATTRIBUTE)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::ReadUplevel(n) => self
                 .write_u8(haxby_opcodes::OPCODE_READ_UPLEVEL)
                 .write_u8(*n),
+            Opcode::ReadLocalWide(n) => self
+                .write_u8(haxby_opcodes::OPCODE_READ_LOCAL_WIDE)
+                .write_u16(*n),
+            Opcode::WriteLocalWide(n) => self
+                .write_u8(haxby_opcodes::OPCODE_WRITE_LOCAL_WIDE)
+                .write_u16(*n),
+            Opcode::TypedefLocalWide(n) => self
+                .write_u8(haxby_opcodes::OPCODE_TYPEDEF_LOCAL_WIDE)
+                .write_u16(*n),
+            Opcode::ReadUplevelWide(n) => self
+                .write_u8(haxby_opcodes::OPCODE_READ_UPLEVEL_WIDE)
+                .write_u16(*n),
+            Opcode::StoreUplevelWide(n) => self
+                .write_u8(haxby_opcodes::OPCODE_STORE_UPLEVEL_WIDE)
+                .write_u16(*n),
             Opcode::LogicalAnd => self.write_u8(haxby_opcodes::OPCODE_LOGICAL_AND),
             Opcode::LogicalOr => self.write_u8(haxby_opcodes::OPCODE_LOGICAL_OR),
             Opcode::Xor => self.write_u8(haxby_opcodes::OPCODE_XOR),
@@ -100,14 +158,32 @@ impl BytecodeWriter {
                 .write_u8(haxby_opcodes::OPCODE_JUMP_IF_ARG_SUPPLIED)
                 .write_u8(*n)
                 .write_u16(*d),
+            Opcode::JumpIfArgSuppliedWide(n, d) => self
+                .write_u8(haxby_opcodes::OPCODE_JUMP_IF_ARG_SUPPLIED_WIDE)
+                .write_u16(*n)
+                .write_u16(*d),
             Opcode::Call(n) => self.write_u8(haxby_opcodes::OPCODE_CALL).write_u8(*n),
+            Opcode::CallWide(n) => self.write_u8(haxby_opcodes::OPCODE_CALL_WIDE).write_u16(*n),
+            Opcode::CallNamed(n, m) => self
+                .write_u8(haxby_opcodes::OPCODE_CALL_NAMED)
+                .write_u16(*n)
+                .write_u16(*m),
+            Opcode::CallSpread(n) => self
+                .write_u8(haxby_opcodes::OPCODE_CALL_SPREAD)
+                .write_u16(*n),
             Opcode::Return => self.write_u8(haxby_opcodes::OPCODE_RETURN),
+            Opcode::Yield => self.write_u8(haxby_opcodes::OPCODE_YIELD),
+            Opcode::Defer => self.write_u8(haxby_opcodes::OPCODE_DEFER),
             Opcode::TryEnter(n) => self.write_u8(haxby_opcodes::OPCODE_TRY_ENTER).write_u16(*n),
             Opcode::TryExit => self.write_u8(haxby_opcodes::OPCODE_TRY_EXIT),
             Opcode::Throw => self.write_u8(haxby_opcodes::OPCODE_THROW),
             Opcode::BuildList(n) => self
                 .write_u8(haxby_opcodes::OPCODE_BUILD_LIST)
                 .write_u32(*n),
+            Opcode::ExtendList => self.write_u8(haxby_opcodes::OPCODE_EXTEND_LIST),
+            Opcode::BuildRange(n) => self
+                .write_u8(haxby_opcodes::OPCODE_BUILD_RANGE)
+                .write_u8(*n),
             Opcode::BuildFunction(n) => self
                 .write_u8(haxby_opcodes::OPCODE_BUILD_FUNCTION)
                 .write_u8(*n),
@@ -118,34 +194,48 @@ impl BytecodeWriter {
             Opcode::BuildMixin => self.write_u8(haxby_opcodes::OPCODE_BUILD_MIXIN),
             Opcode::BuildEnum => self.write_u8(haxby_opcodes::OPCODE_BUILD_ENUM),
             Opcode::BindMethod(a, n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_BIND_METHOD)
                 .write_u8(*a)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::BindCase(a, n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_BIND_CASE)
                 .write_u8(*a)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::IncludeMixin => self.write_u8(haxby_opcodes::OPCODE_INCLUDE_MIXIN),
             Opcode::NewEnumVal(a, n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_NEW_ENUM_VAL)
                 .write_u8(*a)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::EnumCheckIsCase(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_ENUM_CHECK_IS_CASE)
-                .write_u16(*n),
+                .write_const_index(*n),
             Opcode::EnumTryExtractPayload => {
                 self.write_u8(haxby_opcodes::OPCODE_ENUM_TRY_EXTRACT_PAYLOAD)
             }
             Opcode::TryUnwrapProtocol(n) => self
                 .write_u8(haxby_opcodes::OPCODE_TRY_UNWRAP_PROTOCOL)
                 .write_u8(*n),
+            Opcode::BuildString(n) => self
+                .write_u8(haxby_opcodes::OPCODE_BUILD_STRING)
+                .write_u32(*n),
             Opcode::Isa => self.write_u8(haxby_opcodes::OPCODE_ISA),
-            Opcode::Import(n) => self.write_u8(haxby_opcodes::OPCODE_IMPORT).write_u16(*n),
+            Opcode::Import(n) => self
+                .write_const_prefix(*n)
+                .write_u8(haxby_opcodes::OPCODE_IMPORT)
+                .write_const_index(*n),
             Opcode::LiftModule => self.write_u8(haxby_opcodes::OPCODE_LIFT_MODULE),
             Opcode::LoadDylib(n) => self
+                .write_const_prefix(*n)
                 .write_u8(haxby_opcodes::OPCODE_LOAD_DYLIB)
-                .write_u16(*n),
-            Opcode::Assert(n) => self.write_u8(haxby_opcodes::OPCODE_ASSERT).write_u16(*n),
+                .write_const_index(*n),
+            Opcode::Assert(n) => self
+                .write_const_prefix(*n)
+                .write_u8(haxby_opcodes::OPCODE_ASSERT)
+                .write_const_index(*n),
             Opcode::Halt => self.write_u8(haxby_opcodes::OPCODE_HALT),
         }
     }
@@ -1,13 +1,25 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::rc::Rc;
 
-use haxby_opcodes::{Opcode as VmOpcode, enum_case_attribs::CASE_HAS_PAYLOAD};
+use haxby_opcodes::{Opcode as VmOpcode, const_index_size, enum_case_attribs::CASE_HAS_PAYLOAD};
 
-use crate::func_builder::{BasicBlock, FunctionBuilder};
+use crate::{
+    builder::constant_folding::FoldableValue,
+    func_builder::{BasicBlock, FunctionBuilder},
+};
+
+// operands that index locals or count arguments are encoded in a single byte
+// whenever they fit, and only fall back to the wide form of the opcode otherwise
+fn operand_size(n: u16) -> usize {
+    if n <= u8::MAX as u16 { 1 } else { 2 }
+}
 
 pub enum CompilerOpcode {
     Nop,
-    Push(u16),
+    Push(u32),
+    // a value computed by the optimizer, which only takes a slot in the constant
+    // table once optimization is done and the value has survived it
+    PushFolded(FoldableValue),
     Push0,
     Push1,
     PushTrue,
@@ -26,19 +38,27 @@ pub enum CompilerOpcode {
     Neg,
     ShiftLeft,
     ShiftRight,
+    AddChecked,
+    SubChecked,
+    MulChecked,
+    DivChecked,
+    RemChecked,
+    NegChecked,
+    ShiftLeftChecked,
+    ShiftRightChecked,
     Not,
     Equal,
-    ReadLocal(u8),
-    WriteLocal(u8),
-    TypedefLocal(u8),
-    ReadNamed(u16),
-    WriteNamed(u16),
-    TypedefNamed(u16),
+    ReadLocal(u16),
+    WriteLocal(u16),
+    TypedefLocal(u16),
+    ReadNamed(u32),
+    WriteNamed(u32),
+    TypedefNamed(u32),
     ReadIndex(u8),
     WriteIndex(u8),
-    ReadAttribute(u16),
-    WriteAttribute(u16),
-    ReadUplevel(u8),
+    ReadAttribute(u32),
+    WriteAttribute(u32),
+    ReadUplevel(u16),
     LogicalAnd,
     BitwiseAnd,
     LogicalOr,
@@ -51,38 +71,76 @@ pub enum CompilerOpcode {
     JumpTrue(Rc<BasicBlock>),
     JumpFalse(Rc<BasicBlock>),
     Jump(Rc<BasicBlock>),
-    JumpIfArgSupplied(u8, Rc<BasicBlock>),
-    Call(u8),
+    JumpIfArgSupplied(u16, Rc<BasicBlock>),
+    Call(u16),
+    CallNamed(u16, u16),
+    CallSpread(u16),
     Return,
+    Yield,
+    Defer,
     TryEnter(Rc<BasicBlock>),
     TryExit,
     Throw,
     BuildList(u32),
+    ExtendList,
+    BuildRange(u8),
     BuildFunction(u8),
-    StoreUplevel(u8),
+    StoreUplevel(u16),
     BuildStruct,
     BuildEnum,
     BuildMixin,
-    BindMethod(u8, u16),
-    BindCase(u8, u16),
+    BindMethod(u8, u32),
+    BindCase(u8, u32),
     IncludeMixin,
-    NewEnumVal(bool, u16),
-    EnumCheckIsCase(u16),
+    NewEnumVal(bool, u32),
+    EnumCheckIsCase(u32),
     EnumTryExtractPayload,
     TryUnwrapProtocol(u8),
+    BuildString(u32),
     Isa,
-    Import(u16),
+    Import(u32),
     LiftModule,
-    LoadDylib(u16),
-    Assert(u16),
+    LoadDylib(u32),
+    Assert(u32),
     Halt,
 }
 
 impl CompilerOpcode {
+    // the variant of an arithmetic opcode that throws on integer overflow
+    pub fn checked(self) -> Self {
+        match self {
+            Self::Add => Self::AddChecked,
+            Self::Sub => Self::SubChecked,
+            Self::Mul => Self::MulChecked,
+            Self::Div => Self::DivChecked,
+            Self::Rem => Self::RemChecked,
+            Self::Neg => Self::NegChecked,
+            Self::ShiftLeft => Self::ShiftLeftChecked,
+            Self::ShiftRight => Self::ShiftRightChecked,
+            other => other,
+        }
+    }
+
+    // the plain counterpart of a checked arithmetic opcode
+    pub fn unchecked(&self) -> Option<Self> {
+        match self {
+            Self::AddChecked => Some(Self::Add),
+            Self::SubChecked => Some(Self::Sub),
+            Self::MulChecked => Some(Self::Mul),
+            Self::DivChecked => Some(Self::Div),
+            Self::RemChecked => Some(Self::Rem),
+            Self::NegChecked => Some(Self::Neg),
+            Self::ShiftLeftChecked => Some(Self::ShiftLeft),
+            Self::ShiftRightChecked => Some(Self::ShiftRight),
+            _ => None,
+        }
+    }
+
     pub fn is_terminal(&self) -> bool {
         match self {
             Self::Nop => false,
             Self::Push(_) => false,
+            Self::PushFolded(_) => false,
             Self::Push0 => false,
             Self::Push1 => false,
             Self::PushTrue => false,
@@ -101,6 +159,14 @@ impl CompilerOpcode {
             Self::Neg => false,
             Self::ShiftLeft => false,
             Self::ShiftRight => false,
+            Self::AddChecked => false,
+            Self::SubChecked => false,
+            Self::MulChecked => false,
+            Self::DivChecked => false,
+            Self::RemChecked => false,
+            Self::NegChecked => false,
+            Self::ShiftLeftChecked => false,
+            Self::ShiftRightChecked => false,
             Self::Not => false,
             Self::Equal => false,
             Self::ReadLocal(_) => false,
@@ -128,11 +194,17 @@ impl CompilerOpcode {
             Self::Jump(_) => true,
             Self::JumpIfArgSupplied(..) => false,
             Self::Call(_) => false,
+            Self::CallNamed(..) => false,
+            Self::CallSpread(_) => false,
             Self::Return => true,
+            Self::Yield => false,
+            Self::Defer => false,
             Self::TryEnter(_) => false,
             Self::TryExit => false,
             Self::Throw => true,
             Self::BuildList(_) => false,
+            Self::ExtendList => false,
+            Self::BuildRange(_) => false,
             Self::BuildFunction(_) => false,
             Self::StoreUplevel(_) => false,
             Self::BuildStruct => false,
@@ -145,6 +217,7 @@ impl CompilerOpcode {
             Self::EnumCheckIsCase(_) => false,
             Self::EnumTryExtractPayload => false,
             Self::TryUnwrapProtocol(_) => false,
+            Self::BuildString(_) => false,
             Self::Isa => false,
             Self::Import(_) => false,
             Self::LiftModule => false,
@@ -157,7 +230,8 @@ impl CompilerOpcode {
     pub fn byte_size(&self) -> usize {
         match self {
             Self::Nop => 1,
-            Self::Push(_) => 3,
+            Self::Push(n) => 1 + const_index_size(*n),
+            Self::PushFolded(_) => unreachable!("folded values are pushed from the constant table"),
             Self::Push0 => 1,
             Self::Push1 => 1,
             Self::PushTrue => 1,
@@ -176,19 +250,27 @@ impl CompilerOpcode {
             Self::Neg => 1,
             Self::ShiftLeft => 1,
             Self::ShiftRight => 1,
+            Self::AddChecked => 1,
+            Self::SubChecked => 1,
+            Self::MulChecked => 1,
+            Self::DivChecked => 1,
+            Self::RemChecked => 1,
+            Self::NegChecked => 1,
+            Self::ShiftLeftChecked => 1,
+            Self::ShiftRightChecked => 1,
             Self::Not => 1,
             Self::Equal => 1,
-            Self::ReadLocal(_) => 2,
-            Self::WriteLocal(_) => 2,
-            Self::TypedefLocal(_) => 2,
-            Self::ReadNamed(_) => 3,
-            Self::WriteNamed(_) => 3,
-            Self::TypedefNamed(_) => 3,
+            Self::ReadLocal(n) => 1 + operand_size(*n),
+            Self::WriteLocal(n) => 1 + operand_size(*n),
+            Self::TypedefLocal(n) => 1 + operand_size(*n),
+            Self::ReadNamed(n) => 1 + const_index_size(*n),
+            Self::WriteNamed(n) => 1 + const_index_size(*n),
+            Self::TypedefNamed(n) => 1 + const_index_size(*n),
             Self::ReadIndex(_) => 2,
             Self::WriteIndex(_) => 2,
-            Self::ReadAttribute(_) => 3,
-            Self::WriteAttribute(_) => 3,
-            Self::ReadUplevel(_) => 2,
+            Self::ReadAttribute(n) => 1 + const_index_size(*n),
+            Self::WriteAttribute(n) => 1 + const_index_size(*n),
+            Self::ReadUplevel(n) => 1 + operand_size(*n),
             Self::LogicalAnd => 1,
             Self::LogicalOr => 1,
             Self::Xor => 1,
@@ -201,30 +283,37 @@ impl CompilerOpcode {
             Self::JumpTrue(_) => 3,
             Self::JumpFalse(_) => 3,
             Self::Jump(_) => 3,
-            Self::JumpIfArgSupplied(..) => 4,
-            Self::Call(_) => 2,
+            Self::JumpIfArgSupplied(n, _) => 3 + operand_size(*n),
+            Self::Call(n) => 1 + operand_size(*n),
+            Self::CallNamed(..) => 5,
+            Self::CallSpread(_) => 3,
             Self::Return => 1,
+            Self::Yield => 1,
+            Self::Defer => 1,
             Self::TryEnter(_) => 3,
             Self::TryExit => 1,
             Self::Throw => 1,
             Self::BuildList(_) => 5,
+            Self::ExtendList => 1,
+            Self::BuildRange(_) => 2,
             Self::BuildFunction(_) => 2,
-            Self::StoreUplevel(_) => 2,
+            Self::StoreUplevel(n) => 1 + operand_size(*n),
             Self::BuildStruct => 1,
             Self::BuildEnum => 1,
             Self::BuildMixin => 1,
-            Self::BindMethod(..) => 4,
-            Self::BindCase(..) => 4,
+            Self::BindMethod(_, n) => 2 + const_index_size(*n),
+            Self::BindCase(_, n) => 2 + const_index_size(*n),
             Self::IncludeMixin => 1,
-            Self::NewEnumVal(..) => 4,
-            Self::EnumCheckIsCase(_) => 3,
+            Self::NewEnumVal(_, n) => 2 + const_index_size(*n),
+            Self::EnumCheckIsCase(n) => 1 + const_index_size(*n),
             Self::EnumTryExtractPayload => 1,
             Self::TryUnwrapProtocol(_) => 2,
+            Self::BuildString(_) => 5,
             Self::Isa => 1,
-            Self::Import(_) => 3,
+            Self::Import(n) => 1 + const_index_size(*n),
             Self::LiftModule => 1,
-            Self::LoadDylib(_) => 3,
-            Self::Assert(_) => 3,
+            Self::LoadDylib(n) => 1 + const_index_size(*n),
+            Self::Assert(n) => 1 + const_index_size(*n),
             Self::Halt => 1,
         }
     }
@@ -244,6 +333,7 @@ impl CompilerOpcode {
         match self {
             Self::Nop => VmOpcode::Nop,
             Self::Push(v) => VmOpcode::Push(*v),
+            Self::PushFolded(_) => unreachable!("folded values are pushed from the constant table"),
             Self::Push0 => VmOpcode::Push0,
             Self::Push1 => VmOpcode::Push1,
             Self::PushTrue => VmOpcode::PushTrue,
@@ -262,11 +352,28 @@ impl CompilerOpcode {
             Self::Neg => VmOpcode::Neg,
             Self::ShiftLeft => VmOpcode::ShiftLeft,
             Self::ShiftRight => VmOpcode::ShiftRight,
+            Self::AddChecked => VmOpcode::AddChecked,
+            Self::SubChecked => VmOpcode::SubChecked,
+            Self::MulChecked => VmOpcode::MulChecked,
+            Self::DivChecked => VmOpcode::DivChecked,
+            Self::RemChecked => VmOpcode::RemChecked,
+            Self::NegChecked => VmOpcode::NegChecked,
+            Self::ShiftLeftChecked => VmOpcode::ShiftLeftChecked,
+            Self::ShiftRightChecked => VmOpcode::ShiftRightChecked,
             Self::Not => VmOpcode::Not,
             Self::Equal => VmOpcode::Equal,
-            Self::ReadLocal(n) => VmOpcode::ReadLocal(*n),
-            Self::WriteLocal(n) => VmOpcode::WriteLocal(*n),
-            Self::TypedefLocal(n) => VmOpcode::TypedefLocal(*n),
+            Self::ReadLocal(n) => match u8::try_from(*n) {
+                Ok(n) => VmOpcode::ReadLocal(n),
+                Err(_) => VmOpcode::ReadLocalWide(*n),
+            },
+            Self::WriteLocal(n) => match u8::try_from(*n) {
+                Ok(n) => VmOpcode::WriteLocal(n),
+                Err(_) => VmOpcode::WriteLocalWide(*n),
+            },
+            Self::TypedefLocal(n) => match u8::try_from(*n) {
+                Ok(n) => VmOpcode::TypedefLocal(n),
+                Err(_) => VmOpcode::TypedefLocalWide(*n),
+            },
             Self::ReadNamed(n) => VmOpcode::ReadNamed(*n),
             Self::WriteNamed(n) => VmOpcode::WriteNamed(*n),
             Self::TypedefNamed(n) => VmOpcode::TypedefNamed(*n),
@@ -274,7 +381,10 @@ impl CompilerOpcode {
             Self::WriteIndex(n) => VmOpcode::WriteIndex(*n),
             Self::ReadAttribute(n) => VmOpcode::ReadAttribute(*n),
             Self::WriteAttribute(n) => VmOpcode::WriteAttribute(*n),
-            Self::ReadUplevel(n) => VmOpcode::ReadUplevel(*n),
+            Self::ReadUplevel(n) => match u8::try_from(*n) {
+                Ok(n) => VmOpcode::ReadUplevel(n),
+                Err(_) => VmOpcode::ReadUplevelWide(*n),
+            },
             Self::LogicalAnd => VmOpcode::LogicalAnd,
             Self::LogicalOr => VmOpcode::LogicalOr,
             Self::Xor => VmOpcode::Xor,
@@ -298,10 +408,20 @@ impl CompilerOpcode {
             }
             Self::JumpIfArgSupplied(arg, dst) => {
                 let offset = parent.offset_of_block(dst).expect("invalid block") - 1;
-                VmOpcode::JumpIfArgSupplied(*arg, offset)
+                match u8::try_from(*arg) {
+                    Ok(arg) => VmOpcode::JumpIfArgSupplied(arg, offset),
+                    Err(_) => VmOpcode::JumpIfArgSuppliedWide(*arg, offset),
+                }
             }
-            Self::Call(n) => VmOpcode::Call(*n),
+            Self::Call(n) => match u8::try_from(*n) {
+                Ok(n) => VmOpcode::Call(n),
+                Err(_) => VmOpcode::CallWide(*n),
+            },
+            Self::CallNamed(n, m) => VmOpcode::CallNamed(*n, *m),
+            Self::CallSpread(n) => VmOpcode::CallSpread(*n),
             Self::Return => VmOpcode::Return,
+            Self::Yield => VmOpcode::Yield,
+            Self::Defer => VmOpcode::Defer,
             Self::TryEnter(dst) => {
                 let offset = parent.offset_of_block(dst).expect("invalid block") - 1;
                 VmOpcode::TryEnter(offset)
@@ -309,8 +429,13 @@ impl CompilerOpcode {
             Self::TryExit => VmOpcode::TryExit,
             Self::Throw => VmOpcode::Throw,
             Self::BuildList(v) => VmOpcode::BuildList(*v),
+            Self::ExtendList => VmOpcode::ExtendList,
+            Self::BuildRange(v) => VmOpcode::BuildRange(*v),
             Self::BuildFunction(a) => VmOpcode::BuildFunction(*a),
-            Self::StoreUplevel(a) => VmOpcode::StoreUplevel(*a),
+            Self::StoreUplevel(a) => match u8::try_from(*a) {
+                Ok(a) => VmOpcode::StoreUplevel(a),
+                Err(_) => VmOpcode::StoreUplevelWide(*a),
+            },
             Self::BuildStruct => VmOpcode::BuildStruct,
             Self::BuildEnum => VmOpcode::BuildEnum,
             Self::BuildMixin => VmOpcode::BuildMixin,
@@ -323,6 +448,7 @@ impl CompilerOpcode {
             Self::EnumCheckIsCase(v) => VmOpcode::EnumCheckIsCase(*v),
             Self::EnumTryExtractPayload => VmOpcode::EnumTryExtractPayload,
             Self::TryUnwrapProtocol(v) => VmOpcode::TryUnwrapProtocol(*v),
+            Self::BuildString(v) => VmOpcode::BuildString(*v),
             Self::Isa => VmOpcode::Isa,
             Self::Import(v) => VmOpcode::Import(*v),
             Self::LiftModule => VmOpcode::LiftModule,
@@ -0,0 +1,180 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    constant_value::{ConstantValue, ConstantValues, ConstantValuesError},
+};
+
+// a value that is statically known at compile time
+// folding must produce exactly what the VM would compute at runtime, so integer
+// arithmetic wraps, and anything that would raise (e.g. division by zero) is left alone
+#[derive(Clone, PartialEq)]
+pub enum FoldableValue {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+}
+
+impl FoldableValue {
+    pub(crate) fn from_opcode(op: &CompilerOpcode, cv: &ConstantValues) -> Option<Self> {
+        match op {
+            CompilerOpcode::Push0 => Some(Self::Integer(0)),
+            CompilerOpcode::Push1 => Some(Self::Integer(1)),
+            CompilerOpcode::PushTrue => Some(Self::Boolean(true)),
+            CompilerOpcode::PushFalse => Some(Self::Boolean(false)),
+            CompilerOpcode::PushFolded(value) => Some(value.clone()),
+            CompilerOpcode::Push(idx) => match cv.get(*idx as usize)? {
+                ConstantValue::Integer(n) => Some(Self::Integer(n)),
+                ConstantValue::Float(f) => Some(Self::Float(f.raw_value())),
+                ConstantValue::String(s) => Some(Self::String(s)),
+                ConstantValue::CompiledCodeObject(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    // intermediate results of folding never make it to the constant table,
+    // only the values that are still pushed once optimization is done do
+    pub(crate) fn to_opcode(&self) -> CompilerOpcode {
+        match self {
+            Self::Integer(0) => CompilerOpcode::Push0,
+            Self::Integer(1) => CompilerOpcode::Push1,
+            Self::Boolean(true) => CompilerOpcode::PushTrue,
+            Self::Boolean(false) => CompilerOpcode::PushFalse,
+            _ => CompilerOpcode::PushFolded(self.clone()),
+        }
+    }
+
+    // the opcode that pushes this value in the bytecode that is written out
+    pub(crate) fn to_final_opcode(
+        &self,
+        cv: &mut ConstantValues,
+    ) -> Result<CompilerOpcode, ConstantValuesError> {
+        let ct = match self {
+            Self::Integer(0 | 1) | Self::Boolean(_) => return Ok(self.to_opcode()),
+            Self::Integer(n) => ConstantValue::Integer(*n),
+            Self::Float(f) => ConstantValue::Float(From::from(*f)),
+            Self::String(s) => ConstantValue::String(s.clone()),
+        };
+
+        cv.insert(ct).map(CompilerOpcode::Push)
+    }
+}
+
+pub(crate) fn fold_unary_operation(
+    op: &CompilerOpcode,
+    val: &FoldableValue,
+) -> Option<FoldableValue> {
+    use FoldableValue::*;
+
+    match (op, val) {
+        (CompilerOpcode::Neg, Integer(n)) => Some(Integer(n.wrapping_neg())),
+        // an overflow is left for the VM to report at runtime
+        (CompilerOpcode::NegChecked, Integer(n)) => n.checked_neg().map(Integer),
+        (CompilerOpcode::NegChecked, Float(f)) => Some(Float(-f)),
+        (CompilerOpcode::Neg, Float(f)) => Some(Float(-f)),
+        (CompilerOpcode::Not, Boolean(b)) => Some(Boolean(!b)),
+        _ => None,
+    }
+}
+
+// lhs is the value that was pushed first
+pub(crate) fn fold_binary_operation(
+    op: &CompilerOpcode,
+    lhs: &FoldableValue,
+    rhs: &FoldableValue,
+) -> Option<FoldableValue> {
+    use FoldableValue::*;
+
+    // mixed integer/floating point arithmetic promotes the integer
+    let as_floats = match (lhs, rhs) {
+        (Float(a), Float(b)) => Some((*a, *b)),
+        (Integer(a), Float(b)) => Some((*a as f64, *b)),
+        (Float(a), Integer(b)) => Some((*a, *b as f64)),
+        _ => None,
+    };
+
+    // an overflow is left for the VM to report at runtime
+    if let (Integer(a), Integer(b)) = (lhs, rhs) {
+        let checked = match op {
+            CompilerOpcode::AddChecked => Some(a.checked_add(*b)),
+            CompilerOpcode::SubChecked => Some(a.checked_sub(*b)),
+            CompilerOpcode::MulChecked => Some(a.checked_mul(*b)),
+            CompilerOpcode::DivChecked if *b != 0 => Some(a.checked_div(*b)),
+            CompilerOpcode::RemChecked if *b != 0 => Some(Some(a.wrapping_rem(*b))),
+            CompilerOpcode::ShiftLeftChecked => {
+                // a shift that drops significant bits overflows too
+                Some(
+                    u32::try_from(*b)
+                        .ok()
+                        .and_then(|b| a.checked_shl(b).filter(|r| r >> b == *a)),
+                )
+            }
+            CompilerOpcode::ShiftRightChecked => {
+                Some(u32::try_from(*b).ok().and_then(|b| a.checked_shr(b)))
+            }
+            _ => None,
+        };
+        if let Some(result) = checked {
+            return result.map(Integer);
+        }
+    }
+
+    // otherwise, checked opcodes behave like their plain counterparts
+    let plain_op;
+    let op = match op.unchecked() {
+        Some(plain) => {
+            plain_op = plain;
+            &plain_op
+        }
+        None => op,
+    };
+
+    match (op, lhs, rhs) {
+        (CompilerOpcode::Add, Integer(a), Integer(b)) => Some(Integer(a.wrapping_add(*b))),
+        (CompilerOpcode::Sub, Integer(a), Integer(b)) => Some(Integer(a.wrapping_sub(*b))),
+        (CompilerOpcode::Mul, Integer(a), Integer(b)) => Some(Integer(a.wrapping_mul(*b))),
+        (CompilerOpcode::Div, Integer(a), Integer(b)) if *b != 0 => {
+            Some(Integer(a.wrapping_div(*b)))
+        }
+        (CompilerOpcode::Rem, Integer(a), Integer(b)) if *b != 0 => {
+            Some(Integer(a.wrapping_rem(*b)))
+        }
+        (CompilerOpcode::ShiftLeft, Integer(a), Integer(b)) if (0..64).contains(b) => {
+            Some(Integer(a << b))
+        }
+        (CompilerOpcode::ShiftRight, Integer(a), Integer(b)) if (0..64).contains(b) => {
+            Some(Integer(a >> b))
+        }
+        (CompilerOpcode::BitwiseAnd, Integer(a), Integer(b)) => Some(Integer(a & b)),
+        (CompilerOpcode::BitwiseOr, Integer(a), Integer(b)) => Some(Integer(a | b)),
+        (CompilerOpcode::Xor, Integer(a), Integer(b)) => Some(Integer(a ^ b)),
+        (CompilerOpcode::Xor, Boolean(a), Boolean(b)) => Some(Boolean(a ^ b)),
+        (CompilerOpcode::LogicalAnd, Boolean(a), Boolean(b)) => Some(Boolean(*a && *b)),
+        (CompilerOpcode::LogicalOr, Boolean(a), Boolean(b)) => Some(Boolean(*a || *b)),
+        (CompilerOpcode::Add, String(a), String(b)) => Some(String(format!("{a}{b}"))),
+        (CompilerOpcode::LessThan, Integer(a), Integer(b)) => Some(Boolean(a < b)),
+        (CompilerOpcode::LessThanEqual, Integer(a), Integer(b)) => Some(Boolean(a <= b)),
+        (CompilerOpcode::GreaterThan, Integer(a), Integer(b)) => Some(Boolean(a > b)),
+        (CompilerOpcode::GreaterThanEqual, Integer(a), Integer(b)) => Some(Boolean(a >= b)),
+        (CompilerOpcode::Equal, Integer(a), Integer(b)) => Some(Boolean(a == b)),
+        (CompilerOpcode::Equal, Float(a), Float(b)) => Some(Boolean(a == b)),
+        (CompilerOpcode::Equal, String(a), String(b)) => Some(Boolean(a == b)),
+        (CompilerOpcode::Equal, Boolean(a), Boolean(b)) => Some(Boolean(a == b)),
+        _ => {
+            let (a, b) = as_floats?;
+            match op {
+                CompilerOpcode::Add => Some(Float(a + b)),
+                CompilerOpcode::Sub => Some(Float(a - b)),
+                CompilerOpcode::Mul => Some(Float(a * b)),
+                CompilerOpcode::Div if b != 0.0 => Some(Float(a / b)),
+                CompilerOpcode::Rem if b != 0.0 => Some(Float(a % b)),
+                CompilerOpcode::LessThan => Some(Boolean(a < b)),
+                CompilerOpcode::LessThanEqual => Some(Boolean(a <= b)),
+                CompilerOpcode::GreaterThan => Some(Boolean(a > b)),
+                CompilerOpcode::GreaterThanEqual => Some(Boolean(a >= b)),
+                _ => None,
+            }
+        }
+    }
+}
@@ -10,11 +10,12 @@ use crate::line_table::LineTable;
 pub struct CompiledCodeObject {
     pub name: String,
     pub body: Vec<u8>,
-    pub required_argc: u8, // arguments that are required to call this function
-    pub default_argc: u8,  // additional arguments that this function can accept
+    pub required_argc: u16, // arguments that are required to call this function
+    pub default_argc: u16,  // additional arguments that this function can accept
+    pub arg_names: Vec<String>, // names of the declared arguments, in order, for named calls
     pub loc: SourcePointer,
     pub line_table: LineTable,
-    pub frame_size: u8,
+    pub frame_size: u16,
 }
 
 #[derive(Clone, Copy)]
@@ -82,18 +83,18 @@ pub enum ConstantValuesError {
 }
 
 impl ConstantValues {
-    pub fn insert(&mut self, v: ConstantValue) -> Result<u16, ConstantValuesError> {
+    pub fn insert(&mut self, v: ConstantValue) -> Result<u32, ConstantValuesError> {
         if let Some(idx) = self.uniq.get(&v) {
-            Ok(*idx as u16)
+            Ok(*idx as u32)
         } else {
-            if self.values.len() == (u16::MAX as usize) {
+            if self.values.len() == (u32::MAX as usize) {
                 return Err(ConstantValuesError::OutOfSpace);
             }
 
             let idx = self.values.len();
             self.uniq.insert(v.clone(), idx);
             self.values.push(v);
-            Ok(idx as u16)
+            Ok(idx as u32)
         }
     }
 
@@ -3,7 +3,7 @@ use std::{collections::HashSet, fmt::Display, path::PathBuf, rc::Rc};
 
 use aria_parser::ast::{
     ArgumentDecl, ArgumentList, AssertStatement, CodeBlock, DeclarationId, ElsePiece, EnumCaseDecl,
-    EnumDecl, EnumDeclEntry, Expression, FunctionBody, Identifier, MatchPattern,
+    EnumDecl, EnumDeclEntry, Expression, FunctionBody, Identifier, MatchPattern, MatchPatternBind,
     MatchPatternEnumCase, MatchRule, MatchStatement, MethodAccess, MethodDecl, MixinIncludeDecl,
     OperatorDecl, ParsedModule, ReturnStatement, SourceBuffer, SourcePointer, Statement,
     StringLiteral, StructDecl, StructEntry, ValDeclStatement, prettyprint::PrettyPrintable,
@@ -14,7 +14,7 @@ use thiserror::Error;
 
 use crate::{
     CompilationOptions,
-    builder::compiler_opcodes::CompilerOpcode,
+    builder::{compiler_opcodes::CompilerOpcode, constant_folding::FoldableValue},
     constant_value::{ConstantValue, ConstantValuesError},
     func_builder::{BasicBlock, FunctionBuilder},
     module::CompiledModule,
@@ -49,28 +49,34 @@ pub enum CompilationErrorReason {
     TooManyConstants,
     #[error("function accepts too many arguments")]
     TooManyArguments,
+    #[error("function declares too many local values")]
+    TooManyLocals,
     #[error("argument without a default value follows argument with default value")]
     DefaultArgsMustTrail,
     #[error("flow control statement not permitted in current context")]
     FlowControlNotAllowed,
+    #[error("control cannot leave a finally block")]
+    FlowControlInFinally,
     #[error("argument name '{0}' is already defined for this function")]
     DuplicateArgumentName(String),
+    #[error("argument '{0}' is named more than once in this call")]
+    DuplicateNamedArgument(String),
     #[error("struct members do not support type hints")]
     NoTypeHintOnStructMember,
-    #[error("nested closures are not supported")]
-    NestedClosureDisallowed,
     #[error("attempted to write to {0} values, but {1} were provided")]
     AssignmentArityMismatch(usize, usize),
+    #[error("if expression must have an else branch")]
+    IfExpressionWithoutElse,
 }
 
 impl From<&ScopeErrorReason> for CompilationErrorReason {
     fn from(value: &ScopeErrorReason) -> Self {
         match value {
             ScopeErrorReason::TooManyConstants => CompilationErrorReason::TooManyConstants,
+            ScopeErrorReason::TooManyLocals => CompilationErrorReason::TooManyLocals,
             ScopeErrorReason::NoSuchIdentifier(s) => {
                 CompilationErrorReason::NoSuchIdentifier(s.clone())
             }
-            ScopeErrorReason::OverlyDeepClosure => CompilationErrorReason::NestedClosureDisallowed,
         }
     }
 }
@@ -111,10 +117,83 @@ impl std::fmt::Debug for CompilationError {
 
 pub type CompilationResult<T = (), E = CompilationError> = Result<T, E>;
 
+#[derive(Debug, Error)]
+pub enum CompilationWarningReason {
+    #[error("local variable '{0}' is never read")]
+    UnusedLocal(String),
+    #[error("imported name '{0}' is never used")]
+    UnusedImport(String),
+    #[error("unreachable code")]
+    UnreachableCode,
+    #[error("declaration of '{0}' shadows an existing binding")]
+    ShadowedBinding(String),
+}
+
+pub struct CompilationWarning {
+    pub loc: SourcePointer,
+    pub reason: CompilationWarningReason,
+}
+
+impl std::fmt::Display for CompilationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}, warning: {}", self.loc, self.reason)
+    }
+}
+
+impl std::fmt::Debug for CompilationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}, warning: {}", self.loc, self.reason)
+    }
+}
+
+// what a jump out of a try block has to do on its way to a loop target or out of
+// the function: every try block drops its handler, and finally blocks also run
+#[derive(Clone)]
+enum UnwindEntry {
+    TryHandler,
+    Finally(CodeBlock, CompilationScope),
+}
+
 #[derive(Default)]
 struct ControlFlowTargets {
     break_dest: Option<Rc<BasicBlock>>,
     continue_dest: Option<Rc<BasicBlock>>,
+    // try blocks enclosing the current statement in this function, innermost last
+    unwind: Vec<UnwindEntry>,
+    // the part of the unwind stack that was already in place when the innermost loop began
+    loop_unwind_depth: usize,
+    // finally blocks cannot be left by break, continue or return
+    in_finally: bool,
+}
+
+impl ControlFlowTargets {
+    fn for_loop(&self, break_dest: Rc<BasicBlock>, continue_dest: Rc<BasicBlock>) -> Self {
+        Self {
+            break_dest: Some(break_dest),
+            continue_dest: Some(continue_dest),
+            unwind: self.unwind.clone(),
+            loop_unwind_depth: self.unwind.len(),
+            in_finally: self.in_finally,
+        }
+    }
+
+    fn with_unwind_entry(&self, entry: UnwindEntry) -> Self {
+        let mut unwind = self.unwind.clone();
+        unwind.push(entry);
+        Self {
+            break_dest: self.break_dest.clone(),
+            continue_dest: self.continue_dest.clone(),
+            unwind,
+            loop_unwind_depth: self.loop_unwind_depth,
+            in_finally: self.in_finally,
+        }
+    }
+
+    fn has_finally(&self) -> bool {
+        self.unwind
+            .iter()
+            .any(|entry| matches!(entry, UnwindEntry::Finally(..)))
+    }
 }
 
 struct CompileParams<'a> {
@@ -133,7 +212,7 @@ trait CompileNode<'a, T = (), E = CompilationError> {
         params: &mut CompileParams,
         ct: ConstantValue,
         loc: &SourcePointer,
-    ) -> CompilationResult<u16> {
+    ) -> CompilationResult<u32> {
         match params.module.constants.insert(ct) {
             Ok(idx) => Ok(idx),
             Err(_) => Err(CompilationError {
@@ -143,6 +222,30 @@ trait CompileNode<'a, T = (), E = CompilationError> {
         }
     }
 
+    // when optimizing, literals are pushed as folded values, so that those folded
+    // into a larger constant never take a slot in the constant table
+    fn push_literal_or_fail(
+        &self,
+        params: &mut CompileParams,
+        val: FoldableValue,
+        loc: &SourcePointer,
+    ) -> CompilationResult {
+        let op = if params.options.optimize {
+            val.to_opcode()
+        } else {
+            val.to_final_opcode(&mut params.module.constants)
+                .map_err(|_| CompilationError {
+                    loc: loc.clone(),
+                    reason: CompilationErrorReason::TooManyConstants,
+                })?
+        };
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(op, loc.clone());
+        Ok(())
+    }
+
     fn return_unit_value(
         &self,
         params: &mut CompileParams,
@@ -171,6 +274,56 @@ trait CompileNode<'a, T = (), E = CompilationError> {
 mod nodes;
 mod postfix;
 
+// a finally block is compiled once for every way out of its try block, always in
+// the scope of the try statement itself; only the first copy reports warnings
+fn emit_finally_block(
+    block: &CodeBlock,
+    scope: &CompilationScope,
+    unwind: &[UnwindEntry],
+    report_warnings: bool,
+    params: &mut CompileParams,
+) -> CompilationResult {
+    let cflow = ControlFlowTargets {
+        break_dest: None,
+        continue_dest: None,
+        unwind: unwind.to_vec(),
+        loop_unwind_depth: unwind.len(),
+        in_finally: true,
+    };
+    let mut f_params = CompileParams {
+        module: params.module,
+        scope,
+        writer: params.writer,
+        cflow: &cflow,
+        options: params.options,
+    };
+    let num_warnings = f_params.module.warnings.len();
+    block.do_compile(&mut f_params)?;
+    if !report_warnings {
+        f_params.module.warnings.truncate(num_warnings);
+    }
+    Ok(())
+}
+
+// leave every try block above the given depth of the unwind stack, innermost first
+fn emit_unwind_to_depth(
+    depth: usize,
+    params: &mut CompileParams,
+    loc: &SourcePointer,
+) -> CompilationResult {
+    let unwind = &params.cflow.unwind;
+    for (idx, entry) in unwind.iter().enumerate().skip(depth).rev() {
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::TryExit, loc.clone());
+        if let UnwindEntry::Finally(block, scope) = entry {
+            emit_finally_block(block, scope, &unwind[..idx], false, params)?;
+        }
+    }
+    Ok(())
+}
+
 fn ensure_arg_list_is_correct(args: &ArgumentList) -> CompilationResult {
     ensure_unique_arg_names(args)?;
     ensure_default_args_trailing(args)?;
@@ -211,7 +364,7 @@ fn ensure_default_args_trailing(args: &ArgumentList) -> CompilationResult {
 
 fn emit_arg_at_target(
     arg: &ArgumentDecl,
-    idx: u8,
+    idx: u16,
     params: &mut CompileParams,
 ) -> CompilationResult {
     if let Some(deft_expr) = arg.deft.as_ref() {
@@ -259,12 +412,34 @@ fn emit_arg_at_target(
     Ok(())
 }
 
+fn emit_unused_local_warnings(
+    scope: &CompilationScope,
+    writer: &mut FunctionBuilder,
+    module: &mut CompiledModule,
+) {
+    if let Some(fr) = scope.as_function_root() {
+        let unread_locals = writer.unread_locals();
+        // a declaration inside a finally block is compiled once per copy of the block
+        let mut reported = HashSet::new();
+        for (name, loc) in fr.declarations_of(&unread_locals) {
+            if !reported.insert((loc.location.start, loc.location.stop)) {
+                continue;
+            }
+            module.warnings.push(CompilationWarning {
+                loc,
+                reason: CompilationWarningReason::UnusedLocal(name),
+            });
+        }
+    }
+}
+
 #[allow(dead_code)]
 struct ArgumentCountInfo {
-    user_args: u8,
-    required_args: u8,
-    default_args: u8,
+    user_args: u16,
+    required_args: u16,
+    default_args: u16,
     varargs: bool,
+    names: Vec<String>,
 }
 
 fn emit_args_at_target(
@@ -276,7 +451,7 @@ fn emit_args_at_target(
     ensure_arg_list_is_correct(args)?;
 
     let total_args = prefix_args.len() + args.names.len() + suffix_args.len();
-    if total_args > u8::MAX.into() {
+    if total_args > u16::MAX.into() {
         return Err(CompilationError {
             loc: args.loc.clone(),
             reason: CompilationErrorReason::TooManyArguments,
@@ -284,22 +459,25 @@ fn emit_args_at_target(
     }
 
     let mut argc_info = ArgumentCountInfo {
-        user_args: args.len() as u8,
+        user_args: args.len() as u16,
         required_args: 0,
         default_args: 0,
         varargs: args.vararg,
+        names: vec![],
     };
 
-    let mut arg_idx: u8 = 0;
+    let mut arg_idx: u16 = 0;
 
     for arg in prefix_args {
         emit_arg_at_target(arg, arg_idx, params)?;
+        argc_info.names.push(arg.name().to_owned());
         argc_info.required_args += 1;
         arg_idx += 1;
     }
 
     for arg in &args.names {
         emit_arg_at_target(arg, arg_idx, params)?;
+        argc_info.names.push(arg.name().to_owned());
         if arg.deft.is_some() {
             argc_info.default_args += 1;
         } else {
@@ -310,6 +488,7 @@ fn emit_args_at_target(
 
     for arg in suffix_args {
         emit_arg_at_target(arg, arg_idx, params)?;
+        argc_info.names.push(arg.name().to_owned());
         argc_info.required_args += 1;
         arg_idx += 1;
     }
@@ -340,7 +519,7 @@ fn emit_type_mixin_include_decl_compile(
 
 // assume your parent struct is on the stack
 fn emit_method_decl_compile(md: &MethodDecl, params: &mut CompileParams) -> CompilationResult {
-    md.do_compile(params)?;
+    let body_attribs = md.do_compile(params)?;
 
     let name_idx = md.insert_const_or_fail(
         params,
@@ -357,6 +536,7 @@ fn emit_method_decl_compile(md: &MethodDecl, params: &mut CompileParams) -> Comp
                 } else {
                     0
                 } | FUNC_IS_METHOD
+                    | body_attribs
                     | if md.access == MethodAccess::Type {
                         METHOD_ATTRIBUTE_TYPE
                     } else {
@@ -817,6 +997,7 @@ fn generate_is_case_helper_for_enum(case: &EnumCaseDecl) -> MethodDecl {
     let match_rule = MatchRule {
         loc: case.loc.clone(),
         patterns: vec![MatchPattern::MatchPatternEnumCase(match_case_pattern)],
+        guard: None,
         then: CodeBlock::from(&Statement::ReturnStatement(return_true_stmt)),
     };
 
@@ -867,19 +1048,18 @@ fn generate_unwap_case_helper_for_enum(case: &EnumCaseDecl) -> MethodDecl {
     let match_case_pattern = MatchPatternEnumCase {
         loc: case.loc.clone(),
         case: case.name.clone(),
-        payload: Some(DeclarationId {
-            loc: case.loc.clone(),
-            name: Identifier {
+        payload: Some(Box::new(MatchPattern::MatchPatternBind(
+            MatchPatternBind::from(&DeclarationId::from(&Identifier {
                 loc: case.loc.clone(),
                 value: "__case_payload".to_owned(),
-            },
-            ty: None,
-        }),
+            })),
+        ))),
     };
 
     let match_rule = MatchRule {
         loc: case.loc.clone(),
         patterns: vec![MatchPattern::MatchPatternEnumCase(match_case_pattern)],
+        guard: None,
         then: CodeBlock::from(&Statement::ReturnStatement(return_true_stmt)),
     };
 
@@ -946,6 +1126,21 @@ pub(crate) fn compile_from_ast(
     let mut mod_init_bytecode = FunctionBuilder::default();
     let cflow = ControlFlowTargets::default();
 
+    let flag_options;
+    let options = if ast
+        .flags
+        .flags
+        .contains(&aria_parser::ast::ModuleFlag::CheckedArithmetic)
+    {
+        flag_options = CompilationOptions {
+            checked_arithmetic: true,
+            ..options.clone()
+        };
+        &flag_options
+    } else {
+        options
+    };
+
     let mut c_params = CompileParams {
         module: &mut dest,
         scope: &scope,
@@ -965,11 +1160,14 @@ pub(crate) fn compile_from_source(
 ) -> CompilationResult<CompiledModule, Vec<CompilationError>> {
     let ast = match source_to_ast(src) {
         Ok(ast) => ast,
-        Err(err) => {
-            return Err(vec![CompilationError {
-                loc: err.loc,
-                reason: CompilationErrorReason::ParserError(err.msg),
-            }]);
+        Err(errs) => {
+            return Err(errs
+                .into_iter()
+                .map(|err| CompilationError {
+                    loc: err.loc,
+                    reason: CompilationErrorReason::ParserError(err.msg),
+                })
+                .collect());
         }
     };
 
@@ -9,16 +9,19 @@ impl<'a> CompileNode<'a> for aria_parser::ast::AddOperation {
         self.left.do_compile(params)?;
         for right in &self.right {
             right.1.do_compile(params)?;
+            let opcode = match right.0 {
+                aria_parser::ast::AddSymbol::Plus => CompilerOpcode::Add,
+                aria_parser::ast::AddSymbol::Minus => CompilerOpcode::Sub,
+            };
+            let opcode = if params.options.checked_arithmetic {
+                opcode.checked()
+            } else {
+                opcode
+            };
             params
                 .writer
                 .get_current_block()
-                .write_opcode_and_source_info(
-                    match right.0 {
-                        aria_parser::ast::AddSymbol::Plus => CompilerOpcode::Add,
-                        aria_parser::ast::AddSymbol::Minus => CompilerOpcode::Sub,
-                    },
-                    self.loc.clone(),
-                );
+                .write_opcode_and_source_info(opcode, self.loc.up_to(&right.1.loc));
         }
         Ok(())
     }
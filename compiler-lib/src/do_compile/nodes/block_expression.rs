@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::do_compile::{CompilationResult, CompileNode, CompileParams};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::BlockExpression {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let c_scope = params.scope.child();
+        let mut c_params = CompileParams {
+            module: params.module,
+            scope: &c_scope,
+            writer: params.writer,
+            cflow: params.cflow,
+            options: params.options,
+        };
+
+        for entry in &self.body {
+            entry.do_compile(&mut c_params)?;
+        }
+        // the trailing expression is the value of the whole block
+        self.value.do_compile(&mut c_params)
+    }
+}
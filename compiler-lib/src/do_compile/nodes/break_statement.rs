@@ -3,12 +3,14 @@ use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     do_compile::{
         CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+        emit_unwind_to_depth,
     },
 };
 
 impl<'a> CompileNode<'a> for aria_parser::ast::BreakStatement {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
         if let Some(break_target) = &params.cflow.break_dest {
+            emit_unwind_to_depth(params.cflow.loop_unwind_depth, params, &self.loc)?;
             params
                 .writer
                 .get_current_block()
@@ -20,7 +22,11 @@ impl<'a> CompileNode<'a> for aria_parser::ast::BreakStatement {
         } else {
             Err(CompilationError {
                 loc: self.loc.clone(),
-                reason: CompilationErrorReason::FlowControlNotAllowed,
+                reason: if params.cflow.in_finally {
+                    CompilationErrorReason::FlowControlInFinally
+                } else {
+                    CompilationErrorReason::FlowControlNotAllowed
+                },
             })
         }
     }
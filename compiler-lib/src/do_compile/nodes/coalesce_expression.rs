@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: Apache-2.0
+use haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR;
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{
+        CompilationResult, CompileNode, CompileParams, postfix::emit_try_unwrap_protocol,
+    },
+};
+use aria_parser::ast::CoalesceExpression;
+
+impl<'a> CompileNode<'a> for CoalesceExpression {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        self.value.do_compile(params)?;
+        emit_try_unwrap_protocol(BRANCH_ON_ERROR, &self.loc, params)?;
+
+        let end_branch = params
+            .writer
+            .append_block_at_end(&format!("coalesce_end_{}", self.loc));
+
+        // the unwrapped value is the result, otherwise drop whatever
+        // failed to unwrap and evaluate the default instead
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::JumpTrue(end_branch.clone()),
+                self.loc.clone(),
+            )
+            .write_opcode_and_source_info(CompilerOpcode::Pop, self.loc.clone());
+
+        self.default.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(end_branch.clone()),
+                self.loc.clone(),
+            );
+
+        params.writer.set_current_block(end_branch);
+
+        Ok(())
+    }
+}
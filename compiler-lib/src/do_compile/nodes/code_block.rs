@@ -1,5 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
-use crate::do_compile::{CompilationResult, CompileNode, CompileParams};
+use aria_parser::ast::Statement;
+
+use crate::do_compile::{
+    CompilationResult, CompilationWarning, CompilationWarningReason, CompileNode, CompileParams,
+};
 
 impl<'a> CompileNode<'a> for aria_parser::ast::CodeBlock {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
@@ -12,8 +16,29 @@ impl<'a> CompileNode<'a> for aria_parser::ast::CodeBlock {
             options: params.options,
         };
 
-        for entry in &self.entries {
+        // only the first statement after a jump is reported, the rest of the
+        // block is still compiled as usual
+        let mut reported_unreachable = false;
+        for (idx, entry) in self.entries.iter().enumerate() {
             entry.do_compile(&mut c_params)?;
+            if !reported_unreachable
+                && matches!(
+                    entry,
+                    Statement::ReturnStatement(_)
+                        | Statement::ThrowStatement(_)
+                        | Statement::BreakStatement(_)
+                        | Statement::ContinueStatement(_)
+                )
+                && let Some(next) = self.entries[idx + 1..]
+                    .iter()
+                    .find(|e| !matches!(e, Statement::ExpressionStatement(es) if es.val.is_none()))
+            {
+                c_params.module.warnings.push(CompilationWarning {
+                    loc: next.loc().clone(),
+                    reason: CompilationWarningReason::UnreachableCode,
+                });
+                reported_unreachable = true;
+            }
         }
         Ok(())
     }
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{
+    CodeBlock, ComprehensionClause, DeclarationId, Expression, ExpressionStatement, ForStatement,
+    Identifier, IfCondPiece, IfPiece, IfStatement, PostfixExpression, Primary, SourcePointer,
+    Statement, ValDeclEntry, ValDeclStatement,
+};
+
+use crate::do_compile::{CompilationResult, CompileNode, CompileParams};
+
+// a comprehension is lowered to a loop that fills a hidden accumulator,
+// whose value is then left on the stack:
+//
+// {
+//     val acc = <empty list or map>;
+//     for x in <iterable> {
+//         if <condition> {
+//             acc.<method>(<args>);
+//         }
+//     }
+//     acc
+// }
+pub(super) fn compile_comprehension(
+    clause: &ComprehensionClause,
+    loc: &SourcePointer,
+    empty: Expression,
+    method: &str,
+    args: &[Expression],
+    params: &mut CompileParams,
+) -> CompilationResult {
+    let acc_ident = Identifier {
+        loc: loc.clone(),
+        value: format!("__comp__acc__{:?}", loc.location),
+    };
+
+    let decl_acc = Statement::ValDeclStatement(ValDeclStatement {
+        loc: loc.clone(),
+        decls: vec![ValDeclEntry {
+            loc: loc.clone(),
+            id: DeclarationId::from(&acc_ident),
+            val: empty,
+        }],
+    });
+
+    let add_to_acc = Statement::ExpressionStatement(ExpressionStatement {
+        loc: loc.clone(),
+        val: Some(Expression::from(&PostfixExpression::method_call(
+            &Primary::Identifier(acc_ident.clone()),
+            method,
+            args,
+        ))),
+    });
+
+    let body = match &clause.cond {
+        Some(cond) => Statement::IfStatement(IfStatement {
+            loc: clause.loc.clone(),
+            iff: IfPiece {
+                content: IfCondPiece {
+                    loc: cond.loc().clone(),
+                    expression: cond.clone(),
+                    then: CodeBlock {
+                        loc: loc.clone(),
+                        entries: vec![add_to_acc],
+                    },
+                },
+            },
+            elsif: vec![],
+            els: None,
+        }),
+        None => add_to_acc,
+    };
+
+    let for_stmt = Statement::ForStatement(ForStatement {
+        loc: clause.loc.clone(),
+        id: clause.id.clone(),
+        expr: clause.expr.as_ref().clone(),
+        then: CodeBlock {
+            loc: loc.clone(),
+            entries: vec![body],
+        },
+        els: None,
+    });
+
+    // the accumulator and the loop variables must not leak into the enclosing scope
+    let c_scope = params.scope.child();
+    let mut c_params = CompileParams {
+        module: params.module,
+        scope: &c_scope,
+        writer: params.writer,
+        cflow: params.cflow,
+        options: params.options,
+    };
+    decl_acc.do_compile(&mut c_params)?;
+    for_stmt.do_compile(&mut c_params)?;
+    Expression::from(&acc_ident).do_compile(&mut c_params)
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{ArgumentList, LambdaBody, LambdaFunction};
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{
+        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+    },
+    scope::CompilationScope,
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::DeferStatement {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        if matches!(
+            params.scope,
+            CompilationScope::ModuleRoot(_) | CompilationScope::ModuleChild(_)
+        ) {
+            return Err(CompilationError {
+                loc: self.loc.clone(),
+                reason: CompilationErrorReason::FlowControlNotAllowed,
+            });
+        }
+
+        // the expression becomes the body of a closure, which the VM calls
+        // when the function exits, most recently deferred first
+        let deferred = LambdaFunction {
+            loc: self.loc.clone(),
+            args: ArgumentList::empty(self.loc.clone()),
+            body: Box::new(LambdaBody::Expression(self.val.clone())),
+        };
+        deferred.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Defer, self.loc.clone());
+        Ok(())
+    }
+}
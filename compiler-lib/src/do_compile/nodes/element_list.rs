@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{ListElement, SourcePointer};
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{
+        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+    },
+};
+
+// collects the values on top of the stack into a list, and appends that to
+// the list below it if there is one
+fn emit_build_list(
+    count: usize,
+    extend: bool,
+    loc: &SourcePointer,
+    params: &mut CompileParams,
+) -> CompilationResult {
+    let count = u32::try_from(count).map_err(|_| CompilationError {
+        loc: loc.clone(),
+        reason: CompilationErrorReason::ListTooLarge,
+    })?;
+    params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::BuildList(count), loc.clone());
+    if extend {
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::ExtendList, loc.clone());
+    }
+    Ok(())
+}
+
+// leaves a single list with all the elements on the stack; runs of plain
+// elements become a BUILD_LIST, and each spread list is appended to the
+// result with EXTEND_LIST
+impl<'a> CompileNode<'a> for aria_parser::ast::ElementList {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let mut pending = 0;
+        let mut started = false;
+
+        for element in &self.elements {
+            match element {
+                ListElement::Expression(expr) => {
+                    expr.do_compile(params)?;
+                    pending += 1;
+                }
+                ListElement::SpreadExpression(spread) => {
+                    if pending > 0 || !started {
+                        emit_build_list(pending, started, &self.loc, params)?;
+                        pending = 0;
+                        started = true;
+                    }
+                    spread.val.do_compile(params)?;
+                    params
+                        .writer
+                        .get_current_block()
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::ExtendList,
+                            spread.loc.clone(),
+                        );
+                }
+            }
+        }
+        if pending > 0 || !started {
+            emit_build_list(pending, started, &self.loc, params)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    builder::compiler_opcodes::CompilerOpcode,
-    constant_value::ConstantValue,
+    builder::constant_folding::FoldableValue,
     do_compile::{
         CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
     },
@@ -18,12 +17,6 @@ impl<'a> CompileNode<'a> for aria_parser::ast::FloatLiteral {
                 loc: self.loc.clone(),
                 reason: CompilationErrorReason::InvalidLiteral(self.val.clone()),
             })?;
-        let const_idx =
-            self.insert_const_or_fail(params, ConstantValue::Float(fp_val.into()), &self.loc)?;
-        params
-            .writer
-            .get_current_block()
-            .write_opcode_and_source_info(CompilerOpcode::Push(const_idx), self.loc.clone());
-        Ok(())
+        self.push_literal_or_fail(params, FoldableValue::Float(fp_val), &self.loc)
     }
 }
@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 use aria_parser::ast::{
-    AssignStatement, BreakStatement, CodeBlock, DeclarationId, ElsePiece, Expression, Identifier,
-    IfCondPiece, IfPiece, IfStatement, MatchRule, MatchStatement, ParenExpression,
+    AssignStatement, BreakStatement, CodeBlock, DeclarationId, ElsePiece, Expression, ForBinding,
+    Identifier, IfCondPiece, IfPiece, IfStatement, MatchRule, MatchStatement, ParenExpression,
     PostfixExpression, PostfixRvalue, PostfixTerm, PostfixTermEnumCase, Primary, Statement,
-    ThrowStatement, UnaryOperation, ValDeclEntry, ValDeclStatement, WhileStatement,
+    ThrowStatement, UnaryOperation, ValDeclEntry, ValDeclStatement, ValDestructureStatement,
+    WhileStatement,
 };
 
 use crate::do_compile::{CompilationResult, CompileNode, CompileParams};
@@ -38,6 +39,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ForStatement {
         //             case Some(x) => {
         //                 any_hit = true;
         //                 val x = next.value;
+        //                 val [a, b] = x; # only if the loop destructures its items
         //                 <body of the loop>
         //             }
         //             case None => {
@@ -50,22 +52,40 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ForStatement {
         //     }
         // }
 
+        // a destructuring loop binds each item to a hidden name, and then
+        // unpacks it at the top of the body
+        let (item_ident, destructure_item) = match &self.id {
+            ForBinding::Identifier(id) => (id.clone(), None),
+            ForBinding::DestructurePattern(pattern) => {
+                let item_ident = Identifier {
+                    loc: self.loc.clone(),
+                    value: format!("__for__item__{:?}", self.loc.location),
+                };
+                let destructure = Statement::ValDestructureStatement(ValDestructureStatement {
+                    loc: pattern.loc().clone(),
+                    pattern: pattern.clone(),
+                    val: Expression::from(&item_ident),
+                });
+                (item_ident, Some(destructure))
+            }
+        };
+
         // this is the iterator
         let iter_name_ident = Identifier {
             loc: self.loc.clone(),
-            value: format!("__for__iter__{}", self.id.value),
+            value: format!("__for__iter__{}", item_ident.value),
         };
 
         // this is the next value from the iterator (the Maybe, not the actual object)
         let val_next_ident = Identifier {
             loc: self.loc.clone(),
-            value: format!("__for__next__{}", self.id.value),
+            value: format!("__for__next__{}", item_ident.value),
         };
 
         // this becomes true when the for {} body is executed at least once
         let any_hit_ident = Identifier {
             loc: self.loc.clone(),
-            value: format!("__for__any_hit__{}", self.id.value),
+            value: format!("__for__any_hit__{}", item_ident.value),
         };
 
         let fetch_iter_val = val_decl_statement!(
@@ -150,10 +170,13 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ForStatement {
             self.loc.clone(),
             "Maybe",
             "Some",
-            Some(self.id.clone()),
+            Some(item_ident.clone()),
             CodeBlock {
                 loc: self.loc.clone(),
-                entries: vec![assign_to_any_hit, Statement::CodeBlock(self.then.clone())],
+                entries: std::iter::once(assign_to_any_hit)
+                    .chain(destructure_item)
+                    .chain(std::iter::once(Statement::CodeBlock(self.then.clone())))
+                    .collect(),
             },
         );
 
@@ -1,12 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
-use haxby_opcodes::function_attribs::FUNC_ACCEPTS_VARARG;
+use haxby_opcodes::function_attribs::{FUNC_ACCEPTS_VARARG, FUNC_IS_GENERATOR};
 
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     constant_value::{CompiledCodeObject, ConstantValue},
     do_compile::{
         CompilationError, CompilationResult, CompileNode, CompileParams, ControlFlowTargets,
-        emit_args_at_target,
+        emit_args_at_target, emit_unused_local_warnings,
     },
     func_builder::FunctionBuilder,
 };
@@ -27,8 +27,30 @@ impl<'a> CompileNode<'a> for aria_parser::ast::FunctionDecl {
 
         self.body.do_compile(&mut c_params)?;
         self.return_unit_value(&mut c_params, &self.loc)?;
+        emit_unused_local_warnings(params.scope, c_params.writer, c_params.module);
 
-        let co = match writer.write(&params.module.constants, params.options) {
+        let entry = writer.get_block("entry");
+        for uplv in params
+            .scope
+            .as_function_root()
+            .unwrap()
+            .uplevels
+            .borrow()
+            .iter()
+            .rev()
+        {
+            entry
+                .prepend_opcode_and_source_info(
+                    CompilerOpcode::WriteLocal(uplv.idx_in_local),
+                    self.loc.clone(),
+                )
+                .prepend_opcode_and_source_info(
+                    CompilerOpcode::ReadUplevel(uplv.idx_in_uplevel),
+                    self.loc.clone(),
+                );
+        }
+
+        let co = match writer.write(&mut params.module.constants, params.options) {
             Ok(c) => c,
             Err(er) => {
                 return Err(CompilationError {
@@ -44,6 +66,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::FunctionDecl {
             body: co,
             required_argc: argc.required_args,
             default_argc: argc.default_args,
+            arg_names: argc.names,
             loc: self.loc.clone(),
             line_table,
             frame_size,
@@ -54,6 +77,10 @@ impl<'a> CompileNode<'a> for aria_parser::ast::FunctionDecl {
             FUNC_ACCEPTS_VARARG
         } else {
             0_u8
+        } | if writer.is_generator() {
+            FUNC_IS_GENERATOR
+        } else {
+            0_u8
         };
         params
             .writer
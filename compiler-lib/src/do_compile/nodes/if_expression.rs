@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{
+        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+    },
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::IfExpression {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // every path must produce a value, so the else branch is mandatory
+        let Some(els) = &self.els else {
+            return Err(CompilationError {
+                loc: self.loc.clone(),
+                reason: CompilationErrorReason::IfExpressionWithoutElse,
+            });
+        };
+
+        let if_expr_end = params
+            .writer
+            .append_block_at_end(&format!("if_expr_end_{}", self.loc));
+
+        // compiled like a chain of ternaries, each branch leaving its value on the stack
+        for piece in std::iter::once(&self.iff).chain(self.elsif.iter()) {
+            piece.expression.do_compile(params)?;
+            let if_expr_next = params.writer.insert_block_after(
+                &format!("if_expr_next_{}", piece.loc),
+                &params.writer.get_current_block(),
+            );
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::JumpFalse(if_expr_next.clone()),
+                    piece.loc.clone(),
+                );
+            piece.then.do_compile(params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::Jump(if_expr_end.clone()),
+                    piece.loc.clone(),
+                );
+            params.writer.set_current_block(if_expr_next);
+        }
+
+        els.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(if_expr_end.clone()),
+                self.loc.clone(),
+            );
+        params.writer.set_current_block(if_expr_end);
+
+        Ok(())
+    }
+}
@@ -40,6 +40,9 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ImportFromStatement {
                         params.writer.get_current_block(),
                         self.loc.clone(),
                     )?;
+                    if let Some(root) = params.scope.as_module_root() {
+                        root.note_import(&identifier.value, identifier.loc.clone());
+                    }
                 }
             }
             aria_parser::ast::ImportTarget::All => {
@@ -1,7 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    builder::compiler_opcodes::CompilerOpcode,
-    constant_value::ConstantValue,
+    builder::constant_folding::FoldableValue,
     do_compile::{
         CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
     },
@@ -37,24 +36,6 @@ impl<'a> CompileNode<'a> for aria_parser::ast::IntLiteral {
             })?
         };
 
-        if val == 0 {
-            params
-                .writer
-                .get_current_block()
-                .write_opcode_and_source_info(CompilerOpcode::Push0, self.loc.clone());
-        } else if val == 1 {
-            params
-                .writer
-                .get_current_block()
-                .write_opcode_and_source_info(CompilerOpcode::Push1, self.loc.clone());
-        } else {
-            let const_idx =
-                self.insert_const_or_fail(params, ConstantValue::Integer(val), &self.loc)?;
-            params
-                .writer
-                .get_current_block()
-                .write_opcode_and_source_info(CompilerOpcode::Push(const_idx), self.loc.clone());
-        }
-        Ok(())
+        self.push_literal_or_fail(params, FoldableValue::Integer(val), &self.loc)
     }
 }
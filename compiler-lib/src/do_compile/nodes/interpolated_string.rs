@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::InterpolatedStringPiece;
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    constant_value::ConstantValue,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::InterpolatedString {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        for piece in &self.pieces {
+            match piece {
+                InterpolatedStringPiece::Text(text) => {
+                    let const_idx = self.insert_const_or_fail(
+                        params,
+                        ConstantValue::String(text.clone()),
+                        &self.loc,
+                    )?;
+                    params
+                        .writer
+                        .get_current_block()
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::Push(const_idx),
+                            self.loc.clone(),
+                        );
+                }
+                InterpolatedStringPiece::Expression(expr) => {
+                    expr.do_compile(params)?;
+                }
+            }
+        }
+
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::BuildString(self.pieces.len() as u32),
+                self.loc.clone(),
+            );
+        Ok(())
+    }
+}
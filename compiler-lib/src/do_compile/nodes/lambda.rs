@@ -17,7 +17,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::LambdaFunction {
             body,
         };
 
-        let f_body_scope = params.scope.closure(params.writer.get_current_block());
+        let f_body_scope = params.scope.closure();
         let mut f_body_params = CompileParams {
             module: params.module,
             scope: &f_body_scope,
@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{ElementList, Expression, ListLiteral, Primary};
+
+use crate::do_compile::{
+    CompilationResult, CompileNode, CompileParams,
+    nodes::comprehension_clause::compile_comprehension,
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::ListComprehension {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let empty = Expression::from(&Primary::ListLiteral(ListLiteral {
+            loc: self.loc.clone(),
+            items: ElementList::empty(self.loc.clone()),
+        }));
+        compile_comprehension(
+            &self.clause,
+            &self.loc,
+            empty,
+            "append",
+            std::slice::from_ref(self.value.as_ref()),
+            params,
+        )
+    }
+}
@@ -15,21 +15,30 @@ impl<'a> CompileNode<'a> for aria_parser::ast::LogOperation {
                     params
                         .writer
                         .get_current_block()
-                        .write_opcode_and_source_info(CompilerOpcode::BitwiseAnd, self.loc.clone());
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::BitwiseAnd,
+                            self.loc.up_to(&right.1.loc),
+                        );
                 }
                 aria_parser::ast::LogSymbol::Pipe => {
                     right.1.do_compile(params)?;
                     params
                         .writer
                         .get_current_block()
-                        .write_opcode_and_source_info(CompilerOpcode::BitwiseOr, self.loc.clone());
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::BitwiseOr,
+                            self.loc.up_to(&right.1.loc),
+                        );
                 }
                 aria_parser::ast::LogSymbol::Caret => {
                     right.1.do_compile(params)?;
                     params
                         .writer
                         .get_current_block()
-                        .write_opcode_and_source_info(CompilerOpcode::Xor, self.loc.clone());
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::Xor,
+                            self.loc.up_to(&right.1.loc),
+                        );
                 }
                 aria_parser::ast::LogSymbol::DoubleAmpersand => {
                     let bb_and_true = params
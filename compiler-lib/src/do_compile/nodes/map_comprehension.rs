@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{Expression, MapLiteral, Primary};
+
+use crate::do_compile::{
+    CompilationResult, CompileNode, CompileParams,
+    nodes::comprehension_clause::compile_comprehension,
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MapComprehension {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let empty = Expression::from(&Primary::MapLiteral(MapLiteral {
+            loc: self.loc.clone(),
+            entries: vec![],
+        }));
+        compile_comprehension(
+            &self.clause,
+            &self.loc,
+            empty,
+            "set",
+            &[self.key.as_ref().clone(), self.value.as_ref().clone()],
+            params,
+        )
+    }
+}
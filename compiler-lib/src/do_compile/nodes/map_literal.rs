@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    constant_value::ConstantValue,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MapLiteral {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let path_idx = self.insert_const_or_fail(
+            params,
+            ConstantValue::String("aria.structures.map".to_owned()),
+            &self.loc,
+        )?;
+        let map_idx =
+            self.insert_const_or_fail(params, ConstantValue::String("Map".to_owned()), &self.loc)?;
+        let new_idx =
+            self.insert_const_or_fail(params, ConstantValue::String("new".to_owned()), &self.loc)?;
+
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Import(path_idx), self.loc.clone())
+            .write_opcode_and_source_info(CompilerOpcode::ReadAttribute(map_idx), self.loc.clone())
+            .write_opcode_and_source_info(CompilerOpcode::ReadAttribute(new_idx), self.loc.clone())
+            .write_opcode_and_source_info(CompilerOpcode::Call(0), self.loc.clone());
+
+        for entry in &self.entries {
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::Dup, entry.loc.clone());
+            entry.key.do_compile(params)?;
+            entry.value.do_compile(params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::WriteIndex(1), entry.loc.clone());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{
+    Expression, Identifier, PostfixExpression, PostfixTerm, PostfixTermEnumCase, Primary,
+    ThrowStatement,
+};
+
+use crate::do_compile::{
+    CompilationResult, CompileNode, CompileParams,
+    nodes::match_statement::{MatchArm, compile_match},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchExpression {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let arms = self
+            .rules
+            .iter()
+            .map(|rule| MatchArm {
+                loc: &rule.loc,
+                patterns: &rule.patterns,
+                guard: rule.guard.as_ref(),
+                then: &rule.then,
+            })
+            .collect::<Vec<_>>();
+
+        compile_match(
+            &self.loc,
+            &self.expr,
+            &arms,
+            |params| match &self.els {
+                Some(els) => els.do_compile(params),
+                None => {
+                    // there is no value to produce, so
+                    // throw RuntimeError::NoMatchingRule
+                    let no_matching_rule = Expression::from(&PostfixExpression {
+                        loc: self.loc.clone(),
+                        base: Primary::Identifier(Identifier {
+                            loc: self.loc.clone(),
+                            value: "RuntimeError".to_owned(),
+                        }),
+                        terms: vec![PostfixTerm::PostfixTermEnumCase(PostfixTermEnumCase {
+                            loc: self.loc.clone(),
+                            id: Identifier {
+                                loc: self.loc.clone(),
+                                value: "NoMatchingRule".to_owned(),
+                            },
+                            payload: None,
+                        })],
+                    });
+                    ThrowStatement {
+                        loc: self.loc.clone(),
+                        val: no_matching_rule,
+                    }
+                    .do_compile(params)
+                }
+            },
+            params,
+        )
+    }
+}
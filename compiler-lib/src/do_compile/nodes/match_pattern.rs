@@ -7,6 +7,10 @@ impl<'a> CompileNode<'a> for aria_parser::ast::MatchPattern {
             Self::MatchPatternComp(e) => e.do_compile(params),
             Self::MatchPatternRel(e) => e.do_compile(params),
             Self::MatchPatternEnumCase(e) => e.do_compile(params),
+            Self::MatchPatternBind(b) => b.do_compile(params),
+            Self::MatchPatternIsaBind(i) => i.do_compile(params),
+            Self::MatchPatternList(l) => l.do_compile(params),
+            Self::MatchPatternAlternatives(a) => a.do_compile(params),
         }
     }
 }
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchPatternAlternatives {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // each alternative but the last gets a copy of the value; the first one
+        // to match jumps out, with the original value still on the stack
+        let alternative_hit = params
+            .writer
+            .append_block_at_end(&format!("alternative_hit{}", self.loc));
+        let alternatives_aftermath = params
+            .writer
+            .append_block_at_end(&format!("alternatives_aftermath{}", self.loc));
+
+        let (last, rest) = self
+            .alternatives
+            .split_last()
+            .expect("alternatives cannot be empty");
+        for alternative in rest {
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::Dup, alternative.loc().clone());
+            alternative.do_compile(params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::JumpTrue(alternative_hit.clone()),
+                    alternative.loc().clone(),
+                );
+        }
+        last.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(alternatives_aftermath.clone()),
+                self.loc.clone(),
+            );
+
+        params.writer.set_current_block(alternative_hit);
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Pop, self.loc.clone())
+            .write_opcode_and_source_info(CompilerOpcode::PushTrue, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(alternatives_aftermath.clone()),
+                self.loc.clone(),
+            );
+        params.writer.set_current_block(alternatives_aftermath);
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchPatternBind {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let ty = match &self.id.ty {
+            Some(ty) => ty,
+            None => {
+                // an untyped binding always matches
+                params.scope.emit_untyped_define(
+                    &self.id.name.value,
+                    &mut params.module.constants,
+                    params.writer.get_current_block(),
+                    self.loc.clone(),
+                )?;
+                params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(CompilerOpcode::PushTrue, self.loc.clone());
+                return Ok(());
+            }
+        };
+
+        // jump here when the type check fails, with the value still on the stack
+        let type_check_failed = params
+            .writer
+            .append_block_at_end(&format!("bind_type_chck_failed{}", self.loc));
+        let type_check_aftermath = params
+            .writer
+            .append_block_at_end(&format!("bind_type_chck_aftermath{}", self.loc));
+
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Dup, self.loc.clone());
+        ty.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Isa, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::JumpFalse(type_check_failed.clone()),
+                self.loc.clone(),
+            );
+        params.scope.emit_untyped_define(
+            &self.id.name.value,
+            &mut params.module.constants,
+            params.writer.get_current_block(),
+            self.loc.clone(),
+        )?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::PushTrue, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(type_check_aftermath.clone()),
+                self.loc.clone(),
+            );
+
+        params.writer.set_current_block(type_check_failed);
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Pop, self.loc.clone())
+            .write_opcode_and_source_info(CompilerOpcode::PushFalse, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(type_check_aftermath.clone()),
+                self.loc.clone(),
+            );
+        params.writer.set_current_block(type_check_aftermath);
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{DeclarationId, MatchPatternBind};
+
+use crate::do_compile::{CompilationResult, CompileNode, CompileParams};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchPatternIsaBind {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // isa T as x is the same as binding x: T
+        MatchPatternBind::from(&DeclarationId {
+            loc: self.loc.clone(),
+            name: self.name.clone(),
+            ty: Some(self.ty.clone()),
+        })
+        .do_compile(params)
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{
+    ComprehensionClause, Expression, ForBinding, Identifier, IntLiteral, ListComprehension,
+    PostfixExpression, Primary, RangeExpression,
+};
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchPatternList {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // [a, b, ...rest] matches as
+        // val temp = <value>;
+        // temp isa List && temp.len() >= 2 && <a matches temp[0]> && <b matches temp[1]>
+        // and then binds rest = [temp[i] for i in 2..temp.len()];
+        let temp_list = Identifier {
+            loc: self.loc.clone(),
+            value: format!("__match_list{:?}", self.loc.location),
+        };
+        let temp_primary = Primary::Identifier(temp_list.clone());
+        let int_expr = |n: usize| {
+            Expression::from(&Primary::IntLiteral(IntLiteral {
+                loc: self.loc.clone(),
+                base: aria_parser::ast::IntLiteralBase::Decimal,
+                val: n.to_string(),
+            }))
+        };
+        let temp_len = Expression::from(&PostfixExpression::method_call(&temp_primary, "len", &[]));
+
+        let list_check_failed = params
+            .writer
+            .append_block_at_end(&format!("list_chck_failed{}", self.loc));
+        let list_check_aftermath = params
+            .writer
+            .append_block_at_end(&format!("list_chck_aftermath{}", self.loc));
+
+        params.scope.emit_untyped_define(
+            &temp_list.value,
+            &mut params.module.constants,
+            params.writer.get_current_block(),
+            self.loc.clone(),
+        )?;
+
+        // temp isa List
+        Expression::from(&temp_list).do_compile(params)?;
+        Expression::from(&Identifier {
+            loc: self.loc.clone(),
+            value: "List".to_owned(),
+        })
+        .do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Isa, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::JumpFalse(list_check_failed.clone()),
+                self.loc.clone(),
+            );
+
+        // temp.len() == n, or temp.len() >= n if there is a rest binding
+        temp_len.do_compile(params)?;
+        int_expr(self.items.len()).do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                if self.rest.is_some() {
+                    CompilerOpcode::GreaterThanEqual
+                } else {
+                    CompilerOpcode::Equal
+                },
+                self.loc.clone(),
+            )
+            .write_opcode_and_source_info(
+                CompilerOpcode::JumpFalse(list_check_failed.clone()),
+                self.loc.clone(),
+            );
+
+        for (idx, item) in self.items.iter().enumerate() {
+            Expression::from(&PostfixExpression::index_read(
+                &temp_primary,
+                &int_expr(idx),
+            ))
+            .do_compile(params)?;
+            item.do_compile(params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::JumpFalse(list_check_failed.clone()),
+                    item.loc().clone(),
+                );
+        }
+
+        if let Some(rest) = &self.rest {
+            let rest_index = Identifier {
+                loc: rest.loc.clone(),
+                value: format!("__match_list_rest{:?}", self.loc.location),
+            };
+            let log_operation = |e: Expression| match e {
+                Expression::LogOperation(l) => Box::new(l),
+                _ => unreachable!("a postfix expression is a log operation"),
+            };
+            let rest_indices = RangeExpression {
+                loc: rest.loc.clone(),
+                from: log_operation(int_expr(self.items.len())),
+                to: log_operation(temp_len),
+                inclusive: false,
+                step: None,
+            };
+            ListComprehension {
+                loc: rest.loc.clone(),
+                value: Box::new(Expression::from(&PostfixExpression::index_read(
+                    &temp_primary,
+                    &Expression::from(&rest_index),
+                ))),
+                clause: ComprehensionClause {
+                    loc: rest.loc.clone(),
+                    id: ForBinding::Identifier(rest_index),
+                    expr: Box::new(Expression::RangeExpression(rest_indices)),
+                    cond: None,
+                },
+            }
+            .do_compile(params)?;
+            params.scope.emit_untyped_define(
+                &rest.value,
+                &mut params.module.constants,
+                params.writer.get_current_block(),
+                rest.loc.clone(),
+            )?;
+        }
+
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::PushTrue, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(list_check_aftermath.clone()),
+                self.loc.clone(),
+            );
+
+        params.writer.set_current_block(list_check_failed);
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::PushFalse, self.loc.clone())
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(list_check_aftermath.clone()),
+                self.loc.clone(),
+            );
+        params.writer.set_current_block(list_check_aftermath);
+        Ok(())
+    }
+}
@@ -1,101 +1,152 @@
 // SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{Expression, MatchPattern, SourcePointer};
+
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     do_compile::{CompilationResult, CompileNode, CompileParams},
 };
 
-impl<'a> CompileNode<'a> for aria_parser::ast::MatchStatement {
-    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
-        let c_scope = params.scope.child();
-        let mut match_param = CompileParams {
-            module: params.module,
-            scope: &c_scope,
-            writer: params.writer,
-            cflow: params.cflow,
-            options: params.options,
-        };
+// the parts of a rule that are common to match statements and match expressions
+pub(super) struct MatchArm<'r, B> {
+    pub(super) loc: &'r SourcePointer,
+    pub(super) patterns: &'r [MatchPattern],
+    pub(super) guard: Option<&'r Expression>,
+    pub(super) then: &'r B,
+}
 
-        self.expr.do_compile(&mut match_param)?;
+// runs the first arm whose patterns (and guard) match the control expression;
+// if none of them do, `els` is compiled instead
+pub(super) fn compile_match<B>(
+    loc: &SourcePointer,
+    expr: &Expression,
+    arms: &[MatchArm<'_, B>],
+    els: impl FnOnce(&mut CompileParams) -> CompilationResult,
+    params: &mut CompileParams,
+) -> CompilationResult
+where
+    B: for<'a> CompileNode<'a>,
+{
+    let c_scope = params.scope.child();
+    let mut match_param = CompileParams {
+        module: params.module,
+        scope: &c_scope,
+        writer: params.writer,
+        cflow: params.cflow,
+        options: params.options,
+    };
 
-        // store the control expression here so it can be used
-        match_param.scope.emit_untyped_define(
-            "__match_control_expr",
-            &mut match_param.module.constants,
-            match_param.writer.get_current_block(),
-            self.loc.clone(),
-        )?;
+    expr.do_compile(&mut match_param)?;
 
-        let match_after = match_param
-            .writer
-            .append_block_at_end(&format!("match_after_{}", self.loc));
+    // store the control expression here so it can be used
+    match_param.scope.emit_untyped_define(
+        "__match_control_expr",
+        &mut match_param.module.constants,
+        match_param.writer.get_current_block(),
+        loc.clone(),
+    )?;
 
-        for rule in &self.rules {
-            let r_scope = match_param.scope.child();
-            let mut rule_param = CompileParams {
-                module: match_param.module,
-                scope: &r_scope,
-                writer: match_param.writer,
-                cflow: match_param.cflow,
-                options: match_param.options,
-            };
+    let match_after = match_param
+        .writer
+        .append_block_at_end(&format!("match_after_{loc}"));
 
-            let match_hit = rule_param.writer.insert_block_after(
-                &format!("match_hit_{}", rule.loc),
-                &rule_param.writer.get_current_block(),
-            );
-            let match_miss = rule_param
-                .writer
-                .insert_block_after(&format!("match_miss_{}", rule.loc), &match_hit);
+    for rule in arms {
+        let r_scope = match_param.scope.child();
+        let mut rule_param = CompileParams {
+            module: match_param.module,
+            scope: &r_scope,
+            writer: match_param.writer,
+            cflow: match_param.cflow,
+            options: match_param.options,
+        };
 
-            for pattern in &rule.patterns {
-                rule_param.scope.emit_read(
-                    "__match_control_expr",
-                    &mut rule_param.module.constants,
-                    rule_param.writer.get_current_block(),
-                    pattern.loc().clone(),
-                )?;
-                // pattern is going to leave true (hit) or false (miss)
-                // and may add local variables to the scope
-                pattern.do_compile(&mut rule_param)?;
-                rule_param
-                    .writer
-                    .get_current_block()
-                    .write_opcode_and_source_info(
-                        CompilerOpcode::JumpFalse(match_miss.clone()),
-                        pattern.loc().clone(),
-                    );
-            }
+        let match_hit = rule_param.writer.insert_block_after(
+            &format!("match_hit_{}", rule.loc),
+            &rule_param.writer.get_current_block(),
+        );
+        let match_miss = rule_param
+            .writer
+            .insert_block_after(&format!("match_miss_{}", rule.loc), &match_hit);
+
+        for pattern in rule.patterns {
+            rule_param.scope.emit_read(
+                "__match_control_expr",
+                &mut rule_param.module.constants,
+                rule_param.writer.get_current_block(),
+                pattern.loc().clone(),
+            )?;
+            // pattern is going to leave true (hit) or false (miss)
+            // and may add local variables to the scope
+            pattern.do_compile(&mut rule_param)?;
             rule_param
                 .writer
                 .get_current_block()
                 .write_opcode_and_source_info(
-                    CompilerOpcode::Jump(match_hit.clone()),
-                    rule.loc.clone(),
+                    CompilerOpcode::JumpFalse(match_miss.clone()),
+                    pattern.loc().clone(),
                 );
-            rule_param.writer.set_current_block(match_hit);
-            rule.then.do_compile(&mut rule_param)?;
+        }
+        // the guard runs once all patterns have matched, and can see their bindings
+        if let Some(guard) = rule.guard {
+            guard.do_compile(&mut rule_param)?;
             rule_param
                 .writer
                 .get_current_block()
                 .write_opcode_and_source_info(
-                    CompilerOpcode::Jump(match_after.clone()),
-                    rule.loc.clone(),
+                    CompilerOpcode::JumpFalse(match_miss.clone()),
+                    guard.loc().clone(),
                 );
-            rule_param.writer.set_current_block(match_miss);
         }
-
-        if let Some(els) = &self.els {
-            els.then.do_compile(&mut match_param)?;
-        }
-        match_param
+        rule_param
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::Jump(match_hit.clone()),
+                rule.loc.clone(),
+            );
+        rule_param.writer.set_current_block(match_hit);
+        rule.then.do_compile(&mut rule_param)?;
+        rule_param
             .writer
             .get_current_block()
             .write_opcode_and_source_info(
                 CompilerOpcode::Jump(match_after.clone()),
-                self.loc.clone(),
+                rule.loc.clone(),
             );
-        match_param.writer.set_current_block(match_after);
+        rule_param.writer.set_current_block(match_miss);
+    }
+
+    els(&mut match_param)?;
+    match_param
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::Jump(match_after.clone()), loc.clone());
+    match_param.writer.set_current_block(match_after);
+
+    Ok(())
+}
+
+impl<'a> CompileNode<'a> for aria_parser::ast::MatchStatement {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let arms = self
+            .rules
+            .iter()
+            .map(|rule| MatchArm {
+                loc: &rule.loc,
+                patterns: &rule.patterns,
+                guard: rule.guard.as_ref(),
+                then: &rule.then,
+            })
+            .collect::<Vec<_>>();
 
-        Ok(())
+        compile_match(
+            &self.loc,
+            &self.expr,
+            &arms,
+            |params| match &self.els {
+                Some(els) => els.then.do_compile(params),
+                None => Ok(()),
+            },
+            params,
+        )
     }
 }
@@ -1,20 +1,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aria_parser::ast::{DeclarationId, Identifier};
+use haxby_opcodes::function_attribs::FUNC_IS_GENERATOR;
 
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     constant_value::{CompiledCodeObject, ConstantValue},
     do_compile::{
         CompilationError, CompilationResult, CompileNode, CompileParams, ControlFlowTargets,
-        emit_args_at_target,
+        emit_args_at_target, emit_unused_local_warnings,
     },
     func_builder::FunctionBuilder,
     scope::CompilationScope,
 };
 
-impl<'a> CompileNode<'a> for aria_parser::ast::MethodDecl {
-    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+// returns any function attributes implied by the method body (e.g. being a generator)
+impl<'a> CompileNode<'a, u8> for aria_parser::ast::MethodDecl {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult<u8> {
         let f_scope = CompilationScope::function(params.scope);
         let cflow = ControlFlowTargets::default();
         let mut writer = FunctionBuilder::default();
@@ -42,10 +44,11 @@ impl<'a> CompileNode<'a> for aria_parser::ast::MethodDecl {
 
         self.body.do_compile(&mut c_params)?;
         self.return_unit_value(&mut c_params, &self.loc)?;
+        emit_unused_local_warnings(&f_scope, c_params.writer, c_params.module);
 
         let frame_size = c_params.scope.as_function_root().unwrap().num_locals();
 
-        let co = match writer.write(&params.module.constants, params.options) {
+        let co = match writer.write(&mut params.module.constants, params.options) {
             Ok(c) => c,
             Err(er) => {
                 return Err(CompilationError {
@@ -60,6 +63,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::MethodDecl {
             body: co,
             required_argc: argc.required_args,
             default_argc: argc.default_args,
+            arg_names: argc.names,
             loc: self.loc.clone(),
             line_table,
             frame_size,
@@ -71,6 +75,10 @@ impl<'a> CompileNode<'a> for aria_parser::ast::MethodDecl {
             .get_current_block()
             .write_opcode_and_source_info(CompilerOpcode::Push(cco_idx), self.loc.clone());
 
-        Ok(())
+        Ok(if writer.is_generator() {
+            FUNC_IS_GENERATOR
+        } else {
+            0
+        })
     }
 }
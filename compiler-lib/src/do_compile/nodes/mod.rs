@@ -2,10 +2,15 @@
 mod add_operation;
 mod assert_statement;
 mod assign_statement;
+mod block_expression;
 mod break_statement;
+mod coalesce_expression;
 mod code_block;
 mod comp_operation;
+mod comprehension_clause;
 mod continue_statement;
+mod defer_statement;
+mod element_list;
 mod enum_case_decl;
 mod enum_decl;
 mod expression;
@@ -17,16 +22,26 @@ mod for_statement;
 mod function_body;
 mod function_decl;
 mod identifier;
+mod if_expression;
 mod if_statement;
 mod import_from_statement;
 mod import_statement;
 mod int_literal;
+mod interpolated_string;
 mod lambda;
+mod list_comprehension;
 mod list_literal;
 mod logical_operation;
+mod map_comprehension;
+mod map_literal;
+mod match_expression;
 mod match_pattern;
+mod match_pattern_alternatives;
+mod match_pattern_bind;
 mod match_pattern_comp;
 mod match_pattern_enum_case;
+mod match_pattern_isa_bind;
+mod match_pattern_list;
 mod match_pattern_rel;
 mod match_statement;
 mod method_decl;
@@ -36,6 +51,7 @@ mod paren_expression;
 mod parsed_module;
 mod postfix_rvalue;
 mod primary;
+mod range_expression;
 mod rel_operation;
 mod return_statement;
 mod shift_operation;
@@ -48,5 +64,7 @@ mod try_block;
 mod unary_operation;
 mod val_decl_entry;
 mod val_decl_statement;
+mod val_destructure_statement;
 mod while_statement;
 mod write_opeq_statement;
+mod yield_statement;
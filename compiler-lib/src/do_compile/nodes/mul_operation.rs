@@ -9,17 +9,20 @@ impl<'a> CompileNode<'a> for aria_parser::ast::MulOperation {
         self.left.do_compile(params)?;
         for right in &self.right {
             right.1.do_compile(params)?;
+            let opcode = match right.0 {
+                aria_parser::ast::MulSymbol::Star => CompilerOpcode::Mul,
+                aria_parser::ast::MulSymbol::Slash => CompilerOpcode::Div,
+                aria_parser::ast::MulSymbol::Percent => CompilerOpcode::Rem,
+            };
+            let opcode = if params.options.checked_arithmetic {
+                opcode.checked()
+            } else {
+                opcode
+            };
             params
                 .writer
                 .get_current_block()
-                .write_opcode_and_source_info(
-                    match right.0 {
-                        aria_parser::ast::MulSymbol::Star => CompilerOpcode::Mul,
-                        aria_parser::ast::MulSymbol::Slash => CompilerOpcode::Div,
-                        aria_parser::ast::MulSymbol::Percent => CompilerOpcode::Rem,
-                    },
-                    self.loc.clone(),
-                );
+                .write_opcode_and_source_info(opcode, self.loc.up_to(&right.1.loc));
         }
         Ok(())
     }
@@ -5,7 +5,10 @@ use haxby_opcodes::runtime_value_ids::RUNTIME_VALUE_THIS_MODULE;
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     constant_value::{CompiledCodeObject, ConstantValue},
-    do_compile::{CompilationError, CompilationResult, CompileNode, CompileParams},
+    do_compile::{
+        CompilationError, CompilationResult, CompilationWarning, CompilationWarningReason,
+        CompileNode, CompileParams,
+    },
 };
 
 macro_rules! collate_error_if_any {
@@ -41,6 +44,9 @@ impl<'a> CompileNode<'a, (), Vec<CompilationError>> for ParsedModule {
                 aria_parser::ast::TopLevelEntry::ValDeclStatement(v) => {
                     collate_error_if_any!(v.do_compile(params), errors)
                 }
+                aria_parser::ast::TopLevelEntry::ValDestructureStatement(d) => {
+                    collate_error_if_any!(d.do_compile(params), errors)
+                }
                 aria_parser::ast::TopLevelEntry::WriteOpEqStatement(w) => {
                     collate_error_if_any!(w.do_compile(params), errors)
                 }
@@ -103,6 +109,15 @@ impl<'a> CompileNode<'a, (), Vec<CompilationError>> for ParsedModule {
             }
         }
 
+        if let Some(root) = params.scope.as_module_root() {
+            for (name, loc) in root.unused_imports() {
+                params.module.warnings.push(CompilationWarning {
+                    loc,
+                    reason: CompilationWarningReason::UnusedImport(name),
+                });
+            }
+        }
+
         for flag in &self.flags.flags {
             if let aria_parser::ast::ModuleFlag::UsesDylib(lib) = flag {
                 let cidx = match self.insert_const_or_fail(
@@ -133,7 +148,7 @@ impl<'a> CompileNode<'a, (), Vec<CompilationError>> for ParsedModule {
 
         let co = match params
             .writer
-            .write(&params.module.constants, params.options)
+            .write(&mut params.module.constants, params.options)
         {
             Ok(c) => c,
             Err(e) => {
@@ -145,13 +160,17 @@ impl<'a> CompileNode<'a, (), Vec<CompilationError>> for ParsedModule {
             }
         };
 
-        let frame_size = 0;
+        let frame_size = params
+            .scope
+            .as_module_root()
+            .map_or(0, |root| root.num_temporaries());
         let line_table = params.writer.write_line_table().clone();
         let __entry_cco = CompiledCodeObject {
             name: "__entry".to_owned(),
             body: co,
             required_argc: 0,
             default_argc: 0,
+            arg_names: vec![],
             loc: self.loc.clone(),
             line_table,
             frame_size,
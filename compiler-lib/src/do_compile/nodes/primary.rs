@@ -8,8 +8,14 @@ impl<'a> CompileNode<'a> for aria_parser::ast::Primary {
             Self::FloatLiteral(fp) => fp.do_compile(params),
             Self::Identifier(id) => id.do_compile(params),
             Self::ListLiteral(ll) => ll.do_compile(params),
+            Self::ListComprehension(lc) => lc.do_compile(params),
+            Self::MapLiteral(ml) => ml.do_compile(params),
+            Self::MapComprehension(mc) => mc.do_compile(params),
             Self::StringLiteral(sl) => sl.do_compile(params),
+            Self::InterpolatedString(is) => is.do_compile(params),
             Self::ParenExpression(pe) => pe.do_compile(params),
+            Self::IfExpression(ie) => ie.do_compile(params),
+            Self::MatchExpression(me) => me.do_compile(params),
         }
     }
 }
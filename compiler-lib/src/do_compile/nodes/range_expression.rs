@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+use haxby_opcodes::range_flags::{RANGE_HAS_STEP, RANGE_INCLUSIVE};
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+use aria_parser::ast::RangeExpression;
+
+impl<'a> CompileNode<'a> for RangeExpression {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        let mut flags = 0;
+        if self.inclusive {
+            flags |= RANGE_INCLUSIVE;
+        }
+        if let Some(step) = &self.step {
+            step.do_compile(params)?;
+            flags |= RANGE_HAS_STEP;
+        }
+        self.to.do_compile(params)?;
+        self.from.do_compile(params)?;
+
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::BuildRange(flags), self.loc.clone());
+
+        Ok(())
+    }
+}
@@ -1,18 +1,31 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
-    do_compile::{CompilationResult, CompileNode, CompileParams},
+    do_compile::{
+        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+        emit_unwind_to_depth,
+    },
 };
 
 impl<'a> CompileNode<'a> for aria_parser::ast::ReturnStatement {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        if params.cflow.in_finally {
+            return Err(CompilationError {
+                loc: self.loc.clone(),
+                reason: CompilationErrorReason::FlowControlInFinally,
+            });
+        }
+
         if let Some(val) = &self.val {
             val.do_compile(params)?;
+            // the return value stays on the stack while finally blocks run
+            emit_unwind_to_depth(0, params, &self.loc)?;
             params
                 .writer
                 .get_current_block()
                 .write_opcode_and_source_info(CompilerOpcode::Return, self.loc.clone());
         } else {
+            emit_unwind_to_depth(0, params, &self.loc)?;
             self.return_unit_value(params, &self.loc)?;
         }
         Ok(())
@@ -9,16 +9,19 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ShiftOperation {
         self.left.do_compile(params)?;
         if let Some(rhs) = &self.right {
             rhs.1.do_compile(params)?;
+            let opcode = match rhs.0 {
+                aria_parser::ast::ShiftSymbol::Leftward => CompilerOpcode::ShiftLeft,
+                aria_parser::ast::ShiftSymbol::Rightward => CompilerOpcode::ShiftRight,
+            };
+            let opcode = if params.options.checked_arithmetic {
+                opcode.checked()
+            } else {
+                opcode
+            };
             params
                 .writer
                 .get_current_block()
-                .write_opcode_and_source_info(
-                    match rhs.0 {
-                        aria_parser::ast::ShiftSymbol::Leftward => CompilerOpcode::ShiftLeft,
-                        aria_parser::ast::ShiftSymbol::Rightward => CompilerOpcode::ShiftRight,
-                    },
-                    self.loc.clone(),
-                );
+                .write_opcode_and_source_info(opcode, self.loc.clone());
         };
         Ok(())
     }
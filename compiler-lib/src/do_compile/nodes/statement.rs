@@ -5,6 +5,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::Statement {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
         match self {
             Self::ValDeclStatement(l) => l.do_compile(params),
+            Self::ValDestructureStatement(d) => d.do_compile(params),
             Self::AssignStatement(a) => a.do_compile(params),
             Self::WriteOpEqStatement(w) => w.do_compile(params),
             Self::IfStatement(i) => i.do_compile(params),
@@ -13,6 +14,8 @@ impl<'a> CompileNode<'a> for aria_parser::ast::Statement {
             Self::ForStatement(f) => f.do_compile(params),
             Self::ReturnStatement(r) => r.do_compile(params),
             Self::ThrowStatement(t) => t.do_compile(params),
+            Self::YieldStatement(y) => y.do_compile(params),
+            Self::DeferStatement(d) => d.do_compile(params),
             Self::TryBlock(t) => t.do_compile(params),
             Self::AssertStatement(a) => a.do_compile(params),
             Self::CodeBlock(c) => c.do_compile(params),
@@ -22,7 +25,7 @@ impl<'a> CompileNode<'a> for aria_parser::ast::Statement {
             Self::StructDecl(s) => s.do_compile(params),
             Self::EnumDecl(e) => e.do_compile(params),
             Self::FunctionDecl(f) => {
-                let f_scope = params.scope.closure(params.writer.get_current_block());
+                let f_scope = params.scope.closure();
                 let mut f_params = CompileParams {
                     module: params.module,
                     scope: &f_scope,
@@ -1,21 +1,46 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
-    do_compile::{CompilationResult, CompileNode, CompileParams},
+    do_compile::{
+        CompilationResult, CompilationWarning, CompilationWarningReason, CompileNode,
+        CompileParams, UnwindEntry, emit_finally_block,
+    },
 };
 
 impl<'a> CompileNode<'a> for aria_parser::ast::TryBlock {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // a finally clause installs a second handler around the try/catch: the normal
+        // path leaves it and runs the finally code inline, while an exception lands in
+        // the handler, which runs the same code and then throws the exception again;
+        // break, continue and return run the finally code on their way out too
         let try_block = params.writer.insert_block_after(
             &format!("try_{}", &self.body.loc),
             &params.writer.get_current_block(),
         );
-        let catch_block = params
-            .writer
-            .insert_block_after(&format!("catch_{}", &self.catch.loc), &try_block);
-        let after_block = params
-            .writer
-            .insert_block_after(&format!("try_after_catch_{}", &self.body.loc), &catch_block);
+        let catch_block = self.catches.first().map(|catch| {
+            params
+                .writer
+                .insert_block_after(&format!("catch_{}", &catch.loc), &try_block)
+        });
+        let after_block = params.writer.insert_block_after(
+            &format!("try_after_catch_{}", &self.body.loc),
+            catch_block.as_ref().unwrap_or(&try_block),
+        );
+        let finally_block = self.finally.as_ref().map(|finally| {
+            params
+                .writer
+                .insert_block_after(&format!("finally_{}", &finally.loc), &after_block)
+        });
+
+        let finally_cflow = self.finally.as_ref().map(|finally| {
+            params
+                .cflow
+                .with_unwind_entry(UnwindEntry::Finally(finally.clone(), params.scope.clone()))
+        });
+        let catch_cflow = finally_cflow.as_ref().unwrap_or(params.cflow);
+        let body_cflow = catch_block
+            .as_ref()
+            .map(|_| catch_cflow.with_unwind_entry(UnwindEntry::TryHandler));
 
         params
             .writer
@@ -25,51 +50,160 @@ impl<'a> CompileNode<'a> for aria_parser::ast::TryBlock {
                 self.loc.clone(),
             );
         params.writer.set_current_block(try_block);
-        params
-            .writer
-            .get_current_block()
-            .write_opcode_and_source_info(
-                CompilerOpcode::TryEnter(catch_block.clone()),
-                self.loc.clone(),
-            );
-        self.body.do_compile(params)?;
-        params
-            .writer
-            .get_current_block()
-            .write_opcode_and_source_info(CompilerOpcode::TryExit, self.loc.clone());
-        params
-            .writer
-            .get_current_block()
-            .write_opcode_and_source_info(
-                CompilerOpcode::Jump(after_block.clone()),
-                self.loc.clone(),
-            );
-        params.writer.set_current_block(catch_block);
+        if let Some(finally_block) = &finally_block {
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::TryEnter(finally_block.clone()),
+                    self.loc.clone(),
+                );
+        }
+        if let Some(catch_block) = &catch_block {
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::TryEnter(catch_block.clone()),
+                    self.loc.clone(),
+                );
+        }
 
-        let catch_scope = params.scope.child();
-        let mut catch_params = CompileParams {
+        let mut body_params = CompileParams {
             module: params.module,
-            scope: &catch_scope,
+            scope: params.scope,
             writer: params.writer,
-            cflow: params.cflow,
+            cflow: body_cflow.as_ref().unwrap_or(catch_cflow),
             options: params.options,
         };
-        catch_params.scope.emit_untyped_define(
-            &self.id.value,
-            &mut catch_params.module.constants,
-            catch_params.writer.get_current_block(),
-            self.id.loc.clone(),
-        )?;
-
-        self.catch.do_compile(&mut catch_params)?;
-        catch_params
+        self.body.do_compile(&mut body_params)?;
+        if catch_block.is_some() {
+            body_params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::TryExit, self.loc.clone());
+        }
+        body_params
             .writer
             .get_current_block()
             .write_opcode_and_source_info(
                 CompilerOpcode::Jump(after_block.clone()),
                 self.loc.clone(),
             );
-        catch_params.writer.set_current_block(after_block);
+
+        if let Some(catch_block) = catch_block {
+            params.writer.set_current_block(catch_block);
+
+            // the exception is on the stack: each typed arm checks it with isa and
+            // moves on to the next arm if it does not match, and if no arm matches
+            // the exception is thrown again
+            let mut catches_all = false;
+            for catch in &self.catches {
+                if catches_all {
+                    params.module.warnings.push(CompilationWarning {
+                        loc: catch.loc.clone(),
+                        reason: CompilationWarningReason::UnreachableCode,
+                    });
+                    break;
+                }
+
+                let mut catch_params = CompileParams {
+                    module: params.module,
+                    scope: params.scope,
+                    writer: params.writer,
+                    cflow: catch_cflow,
+                    options: params.options,
+                };
+                let next_arm = match &catch.id.ty {
+                    Some(ty) => {
+                        let next_arm = catch_params.writer.insert_block_after(
+                            &format!("catch_next_{}", &catch.loc),
+                            &catch_params.writer.get_current_block(),
+                        );
+                        catch_params
+                            .writer
+                            .get_current_block()
+                            .write_opcode_and_source_info(CompilerOpcode::Dup, catch.loc.clone());
+                        ty.do_compile(&mut catch_params)?;
+                        catch_params
+                            .writer
+                            .get_current_block()
+                            .write_opcode_and_source_info(CompilerOpcode::Isa, catch.loc.clone())
+                            .write_opcode_and_source_info(
+                                CompilerOpcode::JumpFalse(next_arm.clone()),
+                                catch.loc.clone(),
+                            );
+                        Some(next_arm)
+                    }
+                    None => {
+                        catches_all = true;
+                        None
+                    }
+                };
+
+                let catch_scope = params.scope.child();
+                let mut catch_params = CompileParams {
+                    module: params.module,
+                    scope: &catch_scope,
+                    writer: params.writer,
+                    cflow: catch_cflow,
+                    options: params.options,
+                };
+                catch_params.scope.emit_untyped_define(
+                    &catch.id.name.value,
+                    &mut catch_params.module.constants,
+                    catch_params.writer.get_current_block(),
+                    catch.id.name.loc.clone(),
+                )?;
+
+                catch.body.do_compile(&mut catch_params)?;
+                catch_params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(
+                        CompilerOpcode::Jump(after_block.clone()),
+                        self.loc.clone(),
+                    );
+                if let Some(next_arm) = next_arm {
+                    catch_params.writer.set_current_block(next_arm);
+                }
+            }
+            if !catches_all {
+                params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(CompilerOpcode::Throw, self.loc.clone());
+            }
+        }
+        params.writer.set_current_block(after_block);
+
+        if let (Some(finally), Some(finally_block)) = (&self.finally, finally_block) {
+            let resume_block = params
+                .writer
+                .insert_block_after(&format!("finally_after_{}", &finally.loc), &finally_block);
+
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::TryExit, self.loc.clone());
+            emit_finally_block(finally, params.scope, &params.cflow.unwind, true, params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(
+                    CompilerOpcode::Jump(resume_block.clone()),
+                    self.loc.clone(),
+                );
+
+            // the exception is on the stack when the handler is entered
+            params.writer.set_current_block(finally_block);
+            emit_finally_block(finally, params.scope, &params.cflow.unwind, false, params)?;
+            params
+                .writer
+                .get_current_block()
+                .write_opcode_and_source_info(CompilerOpcode::Throw, finally.loc.clone());
+            params.writer.set_current_block(resume_block);
+        }
 
         Ok(())
     }
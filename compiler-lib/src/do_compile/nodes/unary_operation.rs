@@ -8,16 +8,19 @@ impl<'a> CompileNode<'a> for aria_parser::ast::UnaryOperation {
     fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
         self.postfix.do_compile(params)?;
         if let Some(op) = self.operand {
+            let opcode = match op {
+                aria_parser::ast::UnarySymbol::Exclamation => CompilerOpcode::Not,
+                aria_parser::ast::UnarySymbol::Minus => CompilerOpcode::Neg,
+            };
+            let opcode = if params.options.checked_arithmetic {
+                opcode.checked()
+            } else {
+                opcode
+            };
             params
                 .writer
                 .get_current_block()
-                .write_opcode_and_source_info(
-                    match op {
-                        aria_parser::ast::UnarySymbol::Exclamation => CompilerOpcode::Not,
-                        aria_parser::ast::UnarySymbol::Minus => CompilerOpcode::Neg,
-                    },
-                    self.loc.clone(),
-                );
+                .write_opcode_and_source_info(opcode, self.loc.clone());
         }
         Ok(())
     }
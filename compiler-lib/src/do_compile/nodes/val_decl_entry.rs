@@ -4,7 +4,8 @@ use haxby_opcodes::builtin_type_ids::BUILTIN_TYPE_ANY;
 use crate::{
     builder::compiler_opcodes::CompilerOpcode,
     do_compile::{
-        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+        CompilationError, CompilationErrorReason, CompilationResult, CompilationWarning,
+        CompilationWarningReason, CompileNode, CompileParams,
     },
 };
 
@@ -21,6 +22,17 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ValDeclEntry {
         };
 
         self.val.do_compile(params)?;
+
+        // names starting with _ are exempt from warnings, which also covers
+        // the temporaries the compiler introduces while desugaring
+        let warn = !self.id.name.value.starts_with('_');
+        if warn && params.scope.shadows_outer_local(&self.id.name.value) {
+            params.module.warnings.push(CompilationWarning {
+                loc: self.loc.clone(),
+                reason: CompilationWarningReason::ShadowedBinding(self.id.name.value.clone()),
+            });
+        }
+
         if let Some(ty) = &self.id.ty {
             ty.do_compile(params)?;
         } else {
@@ -44,6 +56,11 @@ impl<'a> CompileNode<'a> for aria_parser::ast::ValDeclEntry {
             params.writer.get_current_block(),
             self.loc.clone(),
         )?;
+        if warn {
+            params
+                .scope
+                .note_local_declaration(&self.id.name.value, self.loc.clone());
+        }
         Ok(())
     }
 }
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+use aria_parser::ast::{
+    DeclarationId, DestructurePattern, Expression, Identifier, IntLiteral, ParenExpression,
+    PostfixExpression, PostfixTerm, PostfixTermEnumCase, Primary, ThrowStatement, ValDeclEntry,
+    ValDeclStatement,
+};
+
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{CompilationResult, CompileNode, CompileParams},
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::ValDestructureStatement {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        // val [a, b] = expr; compiles as
+        // {
+        //     val temp = expr; # a hidden local, even at module level
+        //     if temp.len() != 2 {
+        //         throw RuntimeError::AssignmentArityMismatch(RuntimeError.ArgcMismatch.new(2, temp.len()));
+        //     }
+        //     val a = temp[0], b = temp[1];
+        // }
+        // whereas val {x, y} = obj; reads the attributes instead
+        // val x = temp.x, y = temp.y;
+        let temp_store = Identifier {
+            loc: self.loc.clone(),
+            value: format!("__destructure_temp_store{:?}", self.loc.location),
+        };
+        let temp_primary = Primary::Identifier(temp_store.clone());
+
+        self.val.do_compile(params)?;
+        params.scope.emit_untyped_temporary_define(
+            &temp_store.value,
+            &mut params.module.constants,
+            params.writer.get_current_block(),
+            self.loc.clone(),
+        )?;
+
+        let decls = match &self.pattern {
+            DestructurePattern::ListDestructure(l) => {
+                let names = &l.names.identifiers;
+                let int_expr = |n: usize| {
+                    Expression::from(&Primary::IntLiteral(IntLiteral {
+                        loc: l.loc.clone(),
+                        base: aria_parser::ast::IntLiteralBase::Decimal,
+                        val: n.to_string(),
+                    }))
+                };
+                let temp_len =
+                    Expression::from(&PostfixExpression::method_call(&temp_primary, "len", &[]));
+
+                let arity_ok = params.writer.insert_block_after(
+                    &format!("destructure_ok_{}", self.loc),
+                    &params.writer.get_current_block(),
+                );
+                temp_len.do_compile(params)?;
+                int_expr(names.len()).do_compile(params)?;
+                params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(CompilerOpcode::Equal, l.loc.clone());
+                params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(
+                        CompilerOpcode::JumpTrue(arity_ok.clone()),
+                        l.loc.clone(),
+                    );
+
+                // RuntimeError::AssignmentArityMismatch(RuntimeError.ArgcMismatch.new(n, temp.len()))
+                let runtime_error = Primary::Identifier(Identifier {
+                    loc: l.loc.clone(),
+                    value: "RuntimeError".to_owned(),
+                });
+                let argc_mismatch =
+                    Primary::ParenExpression(ParenExpression::from(&Expression::from(
+                        &PostfixExpression::attrib_read(&runtime_error, "ArgcMismatch"),
+                    )));
+                let arity_mismatch = Expression::from(&PostfixExpression {
+                    loc: l.loc.clone(),
+                    base: runtime_error,
+                    terms: vec![PostfixTerm::PostfixTermEnumCase(PostfixTermEnumCase {
+                        loc: l.loc.clone(),
+                        id: Identifier {
+                            loc: l.loc.clone(),
+                            value: "AssignmentArityMismatch".to_owned(),
+                        },
+                        payload: Some(Expression::from(&PostfixExpression::method_call(
+                            &argc_mismatch,
+                            "new",
+                            &[int_expr(names.len()), temp_len],
+                        ))),
+                    })],
+                });
+                ThrowStatement {
+                    loc: l.loc.clone(),
+                    val: arity_mismatch,
+                }
+                .do_compile(params)?;
+                params.writer.set_current_block(arity_ok);
+
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| ValDeclEntry {
+                        loc: name.loc.clone(),
+                        id: DeclarationId::from(name),
+                        val: Expression::from(&PostfixExpression::index_read(
+                            &temp_primary,
+                            &int_expr(i),
+                        )),
+                    })
+                    .collect::<Vec<_>>()
+            }
+            DestructurePattern::ObjectDestructure(o) => o
+                .names
+                .identifiers
+                .iter()
+                .map(|name| ValDeclEntry {
+                    loc: name.loc.clone(),
+                    id: DeclarationId::from(name),
+                    val: Expression::from(&PostfixExpression::attrib_read(
+                        &temp_primary,
+                        &name.value,
+                    )),
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        ValDeclStatement {
+            loc: self.loc.clone(),
+            decls,
+        }
+        .do_compile(params)
+    }
+}
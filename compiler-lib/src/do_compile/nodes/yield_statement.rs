@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    builder::compiler_opcodes::CompilerOpcode,
+    do_compile::{
+        CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+    },
+    scope::CompilationScope,
+};
+
+impl<'a> CompileNode<'a> for aria_parser::ast::YieldStatement {
+    fn do_compile(&self, params: &'a mut CompileParams) -> CompilationResult {
+        if matches!(
+            params.scope,
+            CompilationScope::ModuleRoot(_) | CompilationScope::ModuleChild(_)
+        ) {
+            return Err(CompilationError {
+                loc: self.loc.clone(),
+                reason: CompilationErrorReason::FlowControlNotAllowed,
+            });
+        }
+
+        self.val.do_compile(params)?;
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Yield, self.loc.clone());
+        params.writer.mark_as_generator();
+        Ok(())
+    }
+}
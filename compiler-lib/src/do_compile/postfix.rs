@@ -1,11 +1,17 @@
 // SPDX-License-Identifier: Apache-2.0
-use aria_parser::ast::{Expression, ExpressionList, Identifier, SourcePointer};
-use haxby_opcodes::builtin_type_ids::BUILTIN_TYPE_RESULT;
+use std::collections::HashSet;
+
+use aria_parser::ast::{
+    ElementList, Expression, ExpressionList, Identifier, ListElement, NamedArgument,
+    PostfixExpression, PostfixTerm, PostfixTermAttribute, Primary, SourcePointer,
+};
+use haxby_opcodes::builtin_type_ids::{BUILTIN_TYPE_MAYBE, BUILTIN_TYPE_RESULT};
 
 use crate::{builder::compiler_opcodes::CompilerOpcode, constant_value::ConstantValue};
 
 use super::{
     CompilationError, CompilationErrorReason, CompilationResult, CompileNode, CompileParams,
+    emit_unwind_to_depth,
 };
 
 #[derive(Debug)]
@@ -36,37 +42,303 @@ impl ObjWrite {
     }
 }
 
+// the SourcePointer carried by attribute, call, case and index values spans the
+// chain from its base up to and including that term, e.g. all of `a.b.c(d)[e]`
+// for the index, so that runtime errors point at the exact failing subexpression
 #[allow(clippy::large_enum_variant)]
 pub(super) enum PostfixValue {
     Primary(Box<aria_parser::ast::Primary>),
-    Attribute(Box<PostfixValue>, Box<Identifier>),
-    Call(Box<PostfixValue>, Box<ExpressionList>, SourcePointer),
-    Case(Box<PostfixValue>, Box<Identifier>, Option<Expression>),
-    Index(Box<PostfixValue>, Box<aria_parser::ast::ExpressionList>),
+    Attribute(Box<PostfixValue>, Box<Identifier>, SourcePointer),
+    Call(
+        Box<PostfixValue>,
+        Box<ElementList>,
+        Vec<NamedArgument>,
+        SourcePointer,
+    ),
+    Case(
+        Box<PostfixValue>,
+        Box<Identifier>,
+        Option<Expression>,
+        SourcePointer,
+    ),
+    Index(
+        Box<PostfixValue>,
+        Box<aria_parser::ast::ExpressionList>,
+        SourcePointer,
+    ),
     ObjWrite(Box<PostfixValue>, Vec<ObjWrite>),
     TryProtocol(
         Box<PostfixValue>,
         Box<aria_parser::ast::PostfixTermTryProtocol>,
     ),
+    OptionalChain(Box<PostfixExpression>),
+}
+
+// runs the value on top of the stack through Result.try_unwrap_protocol, and
+// then lets TRY_UNWRAP_PROTOCOL deal with the outcome according to mode
+pub(super) fn emit_try_unwrap_protocol(
+    mode: u8,
+    loc: &SourcePointer,
+    params: &mut CompileParams,
+) -> CompilationResult {
+    let try_unwrap_protocol_idx = params
+        .module
+        .constants
+        .insert(ConstantValue::String("try_unwrap_protocol".to_string()))
+        .map_err(|_| CompilationError {
+            loc: loc.clone(),
+            reason: CompilationErrorReason::TooManyConstants,
+        })?;
+    params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(
+            CompilerOpcode::PushBuiltinTy(BUILTIN_TYPE_RESULT),
+            loc.clone(),
+        )
+        .write_opcode_and_source_info(
+            CompilerOpcode::ReadAttribute(try_unwrap_protocol_idx),
+            loc.clone(),
+        )
+        .write_opcode_and_source_info(CompilerOpcode::Call(1), loc.clone())
+        .write_opcode_and_source_info(CompilerOpcode::TryUnwrapProtocol(mode), loc.clone());
+    Ok(())
+}
+
+fn emit_new_maybe(
+    case: &str,
+    has_payload: bool,
+    loc: &SourcePointer,
+    params: &mut CompileParams,
+) -> CompilationResult {
+    let case_idx = params
+        .module
+        .constants
+        .insert(ConstantValue::String(case.to_owned()))
+        .map_err(|_| CompilationError {
+            loc: loc.clone(),
+            reason: CompilationErrorReason::TooManyConstants,
+        })?;
+    params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(
+            CompilerOpcode::PushBuiltinTy(BUILTIN_TYPE_MAYBE),
+            loc.clone(),
+        )
+        .write_opcode_and_source_info(
+            CompilerOpcode::NewEnumVal(has_payload, case_idx),
+            loc.clone(),
+        );
+    Ok(())
+}
+
+// a?.b.c?.d() is compiled as
+// {
+//     val t0 = a ?? <Maybe::None>;
+//     val t1 = t0.b.c ?? <Maybe::None>;
+//     Maybe::Some(t1.d())
+// }
+// where each link that fails to unwrap jumps out of the whole chain; storing each
+// unwrapped link in a temporary means that nothing else is on the stack when it does
+// with a spread, the positional arguments are collected in a list, which the
+// VM unpacks once its length is known; they are still evaluated right to left
+// like those of any other call, and each one (as a single-element list, or the
+// spread list itself) is then appended to a new list, first to last
+fn emit_spread_arguments(args: &ElementList, params: &mut CompileParams) -> CompilationResult {
+    for arg in args.elements.iter().rev() {
+        match arg {
+            ListElement::Expression(expr) => {
+                expr.do_compile(params)?;
+                params
+                    .writer
+                    .get_current_block()
+                    .write_opcode_and_source_info(CompilerOpcode::BuildList(1), args.loc.clone());
+            }
+            ListElement::SpreadExpression(spread) => spread.val.do_compile(params)?,
+        }
+    }
+    params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::BuildList(0), args.loc.clone());
+    for _ in &args.elements {
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::Swap, args.loc.clone());
+        params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(CompilerOpcode::ExtendList, args.loc.clone());
+    }
+    Ok(())
+}
+
+fn emit_optional_chain(expr: &PostfixExpression, params: &mut CompileParams) -> CompilationResult {
+    let none_blk = params
+        .writer
+        .append_block_at_end(&format!("optional_chain_none_{:?}", expr.loc.location));
+    let end_blk = params
+        .writer
+        .append_block_at_end(&format!("optional_chain_end_{:?}", expr.loc.location));
+
+    let c_scope = params.scope.child();
+    let mut c_params = CompileParams {
+        module: params.module,
+        scope: &c_scope,
+        writer: params.writer,
+        cflow: params.cflow,
+        options: params.options,
+    };
+
+    let mut segment = PostfixExpression {
+        loc: expr.loc.clone(),
+        base: expr.base.clone(),
+        terms: vec![],
+    };
+    let mut link_idx = 0;
+    for term in &expr.terms {
+        let PostfixTerm::PostfixTermOptionalAttribute(link) = term else {
+            segment.terms.push(term.clone());
+            continue;
+        };
+
+        PostfixValue::from(&segment).emit_read(&mut c_params)?;
+        emit_try_unwrap_protocol(
+            haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR,
+            &link.loc,
+            &mut c_params,
+        )?;
+        c_params
+            .writer
+            .get_current_block()
+            .write_opcode_and_source_info(
+                CompilerOpcode::JumpFalse(none_blk.clone()),
+                link.loc.clone(),
+            );
+
+        let link_value = Identifier {
+            loc: link.loc.clone(),
+            value: format!("__optional_chain_{link_idx}_{:?}", expr.loc.location),
+        };
+        c_scope.emit_untyped_define(
+            &link_value.value,
+            &mut c_params.module.constants,
+            c_params.writer.get_current_block(),
+            link.loc.clone(),
+        )?;
+        link_idx += 1;
+
+        segment = PostfixExpression {
+            loc: link.loc.clone(),
+            base: Primary::Identifier(link_value),
+            terms: vec![PostfixTerm::PostfixTermAttribute(PostfixTermAttribute {
+                loc: link.loc.clone(),
+                id: link.id.clone(),
+            })],
+        };
+    }
+    PostfixValue::from(&segment).emit_read(&mut c_params)?;
+    emit_new_maybe("Some", true, &expr.loc, &mut c_params)?;
+    c_params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::Jump(end_blk.clone()), expr.loc.clone());
+
+    // the value that failed to unwrap is on the stack
+    c_params.writer.set_current_block(none_blk);
+    c_params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::Pop, expr.loc.clone());
+    emit_new_maybe("None", false, &expr.loc, &mut c_params)?;
+    c_params
+        .writer
+        .get_current_block()
+        .write_opcode_and_source_info(CompilerOpcode::Jump(end_blk.clone()), expr.loc.clone());
+
+    c_params.writer.set_current_block(end_blk);
+    Ok(())
 }
 
 impl<'a> PostfixValue {
     pub(super) fn emit_read(&self, params: &'a mut CompileParams) -> CompilationResult {
         match self {
             PostfixValue::Primary(primary) => primary.do_compile(params),
-            PostfixValue::Call(base, args, loc) => {
-                for expr in args.expressions.iter().rev() {
-                    expr.do_compile(params)?;
+            PostfixValue::Call(base, args, named, loc) => {
+                let mut seen = HashSet::new();
+                for arg in named {
+                    if !seen.insert(arg.name.value.as_str()) {
+                        return Err(CompilationError {
+                            loc: arg.loc.clone(),
+                            reason: CompilationErrorReason::DuplicateNamedArgument(
+                                arg.name.value.clone(),
+                            ),
+                        });
+                    }
+                }
+
+                for arg in named.iter().rev() {
+                    arg.value.do_compile(params)?;
+                }
+                let spread = args.has_spread();
+                if spread {
+                    emit_spread_arguments(args, params)?;
+                } else {
+                    for arg in args.elements.iter().rev() {
+                        if let ListElement::Expression(expr) = arg {
+                            expr.do_compile(params)?;
+                        }
+                    }
+                }
+                let (argc, named_argc) = match (
+                    u16::try_from(args.elements.len()),
+                    u16::try_from(named.len()),
+                ) {
+                    (Ok(argc), Ok(named_argc)) => (argc, named_argc),
+                    _ => {
+                        return Err(CompilationError {
+                            loc: loc.clone(),
+                            reason: CompilationErrorReason::TooManyArguments,
+                        });
+                    }
+                };
+
+                // the names go on top of the values, first name topmost, and the
+                // VM matches them against the argument names of the callee
+                for arg in named.iter().rev() {
+                    let name_idx = params
+                        .module
+                        .constants
+                        .insert(ConstantValue::String(arg.name.value.clone()))
+                        .map_err(|_| CompilationError {
+                            loc: arg.name.loc.clone(),
+                            reason: CompilationErrorReason::TooManyConstants,
+                        })?;
+                    params
+                        .writer
+                        .get_current_block()
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::Push(name_idx),
+                            arg.name.loc.clone(),
+                        );
                 }
-                let argc = args.expressions.len();
                 base.emit_read(params)?;
+                let call = if spread {
+                    CompilerOpcode::CallSpread(named_argc)
+                } else if named.is_empty() {
+                    CompilerOpcode::Call(argc)
+                } else {
+                    CompilerOpcode::CallNamed(argc, named_argc)
+                };
                 params
                     .writer
                     .get_current_block()
-                    .write_opcode_and_source_info(CompilerOpcode::Call(argc as u8), loc.clone());
+                    .write_opcode_and_source_info(call, loc.clone());
                 Ok(())
             }
-            PostfixValue::Case(base, case, payload) => {
+            PostfixValue::Case(base, case, payload, loc) => {
                 if let Some(p) = payload {
                     p.do_compile(params)?;
                 }
@@ -89,11 +361,11 @@ impl<'a> PostfixValue {
                     .get_current_block()
                     .write_opcode_and_source_info(
                         CompilerOpcode::NewEnumVal(payload.is_some(), identifier_idx),
-                        case.loc.clone(),
+                        loc.clone(),
                     );
                 Ok(())
             }
-            PostfixValue::Index(base, index) => {
+            PostfixValue::Index(base, index, loc) => {
                 base.emit_read(params)?;
                 index.do_compile(params)?;
                 params
@@ -101,11 +373,11 @@ impl<'a> PostfixValue {
                     .get_current_block()
                     .write_opcode_and_source_info(
                         CompilerOpcode::ReadIndex(index.expressions.len() as u8),
-                        index.loc.clone(),
+                        loc.clone(),
                     );
                 Ok(())
             }
-            PostfixValue::Attribute(base, identifier) => {
+            PostfixValue::Attribute(base, identifier, loc) => {
                 let identifier_idx = match params
                     .module
                     .constants
@@ -125,7 +397,7 @@ impl<'a> PostfixValue {
                     .get_current_block()
                     .write_opcode_and_source_info(
                         CompilerOpcode::ReadAttribute(identifier_idx),
-                        identifier.loc.clone(),
+                        loc.clone(),
                     );
                 Ok(())
             }
@@ -171,8 +443,19 @@ impl<'a> PostfixValue {
                 }
                 Ok(())
             }
+            PostfixValue::OptionalChain(expr) => emit_optional_chain(expr, params),
             PostfixValue::TryProtocol(base, tp) => {
                 let mode = match tp.mode {
+                    aria_parser::ast::TryProtocolMode::Return if params.cflow.in_finally => {
+                        return Err(CompilationError {
+                            loc: tp.loc.clone(),
+                            reason: CompilationErrorReason::FlowControlInFinally,
+                        });
+                    }
+                    // returning has to run the enclosing finally blocks first
+                    aria_parser::ast::TryProtocolMode::Return if params.cflow.has_finally() => {
+                        haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR
+                    }
                     aria_parser::ast::TryProtocolMode::Return => {
                         haxby_opcodes::try_unwrap_protocol_mode::PROPAGATE_ERROR
                     }
@@ -182,31 +465,27 @@ impl<'a> PostfixValue {
                 };
 
                 base.emit_read(params)?;
+                emit_try_unwrap_protocol(mode, &tp.loc, params)?;
 
-                let try_unwrap_protocol_idx = params
-                    .module
-                    .constants
-                    .insert(ConstantValue::String("try_unwrap_protocol".to_string()))
-                    .map_err(|_| CompilationError {
-                        loc: tp.loc.clone(),
-                        reason: CompilationErrorReason::TooManyConstants,
-                    })?;
-                params
-                    .writer
-                    .get_current_block()
-                    .write_opcode_and_source_info(
-                        CompilerOpcode::PushBuiltinTy(BUILTIN_TYPE_RESULT),
-                        tp.loc.clone(),
-                    )
-                    .write_opcode_and_source_info(
-                        CompilerOpcode::ReadAttribute(try_unwrap_protocol_idx),
-                        tp.loc.clone(),
-                    )
-                    .write_opcode_and_source_info(CompilerOpcode::Call(1), tp.loc.clone())
-                    .write_opcode_and_source_info(
-                        CompilerOpcode::TryUnwrapProtocol(mode),
-                        tp.loc.clone(),
+                if mode == haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR {
+                    let unwrapped = params.writer.insert_block_after(
+                        &format!("try_unwrapped_{}", tp.loc),
+                        &params.writer.get_current_block(),
                     );
+                    params
+                        .writer
+                        .get_current_block()
+                        .write_opcode_and_source_info(
+                            CompilerOpcode::JumpTrue(unwrapped.clone()),
+                            tp.loc.clone(),
+                        );
+                    emit_unwind_to_depth(0, params, &tp.loc)?;
+                    params
+                        .writer
+                        .get_current_block()
+                        .write_opcode_and_source_info(CompilerOpcode::Return, tp.loc.clone());
+                    params.writer.set_current_block(unwrapped);
+                }
                 Ok(())
             }
         }
@@ -238,11 +517,11 @@ impl<'a> PostfixValue {
                 loc: loc.clone(),
                 reason: CompilationErrorReason::ReadOnlyValue,
             }),
-            PostfixValue::Case(_, case, _) => Err(CompilationError {
+            PostfixValue::Case(_, case, ..) => Err(CompilationError {
                 loc: case.loc.clone(),
                 reason: CompilationErrorReason::ReadOnlyValue,
             }),
-            PostfixValue::Index(base, index) => {
+            PostfixValue::Index(base, index, loc) => {
                 base.emit_read(params)?;
                 index.do_compile(params)?;
                 val.do_compile(params)?;
@@ -251,11 +530,11 @@ impl<'a> PostfixValue {
                     .get_current_block()
                     .write_opcode_and_source_info(
                         CompilerOpcode::WriteIndex(index.expressions.len() as u8),
-                        index.loc.clone(),
+                        loc.clone(),
                     );
                 Ok(())
             }
-            PostfixValue::Attribute(base, identifier) => {
+            PostfixValue::Attribute(base, identifier, loc) => {
                 let identifier_idx = match params
                     .module
                     .constants
@@ -276,7 +555,7 @@ impl<'a> PostfixValue {
                     .get_current_block()
                     .write_opcode_and_source_info(
                         CompilerOpcode::WriteAttribute(identifier_idx),
-                        identifier.loc.clone(),
+                        loc.clone(),
                     );
                 Ok(())
             }
@@ -291,26 +570,47 @@ impl<'a> PostfixValue {
                 loc: tp.loc.clone(),
                 reason: CompilationErrorReason::ReadOnlyValue,
             }),
+            PostfixValue::OptionalChain(expr) => Err(CompilationError {
+                loc: expr.loc.clone(),
+                reason: CompilationErrorReason::ReadOnlyValue,
+            }),
         }
     }
 }
 
 impl From<&aria_parser::ast::PostfixExpression> for PostfixValue {
     fn from(value: &aria_parser::ast::PostfixExpression) -> Self {
+        if value
+            .terms
+            .iter()
+            .any(|t| matches!(t, PostfixTerm::PostfixTermOptionalAttribute(_)))
+        {
+            return PostfixValue::OptionalChain(Box::new(value.clone()));
+        }
+
         let mut current = PostfixValue::Primary(Box::new(value.base.clone()));
         for term in &value.terms {
             match term {
                 aria_parser::ast::PostfixTerm::PostfixTermAttribute(attr) => {
-                    current = PostfixValue::Attribute(Box::new(current), Box::new(attr.id.clone()))
+                    current = PostfixValue::Attribute(
+                        Box::new(current),
+                        Box::new(attr.id.clone()),
+                        value.loc.up_to(&attr.loc),
+                    )
                 }
                 aria_parser::ast::PostfixTerm::PostfixTermIndex(index) => {
-                    current = PostfixValue::Index(Box::new(current), Box::new(index.index.clone()))
+                    current = PostfixValue::Index(
+                        Box::new(current),
+                        Box::new(index.index.clone()),
+                        value.loc.up_to(&index.loc),
+                    )
                 }
                 aria_parser::ast::PostfixTerm::PostfixTermCall(call) => {
                     current = PostfixValue::Call(
                         Box::new(current),
                         Box::new(call.args.clone()),
-                        call.loc.clone(),
+                        call.named.clone(),
+                        value.loc.up_to(&call.loc),
                     )
                 }
                 aria_parser::ast::PostfixTerm::PostfixTermEnumCase(case) => {
@@ -318,6 +618,7 @@ impl From<&aria_parser::ast::PostfixExpression> for PostfixValue {
                         Box::new(current),
                         Box::new(case.id.clone()),
                         case.payload.clone(),
+                        value.loc.up_to(&case.loc),
                     )
                 }
                 aria_parser::ast::PostfixTerm::PostfixTermObjectWrite(wrt) => {
@@ -352,6 +653,9 @@ impl From<&aria_parser::ast::PostfixExpression> for PostfixValue {
                 aria_parser::ast::PostfixTerm::PostfixTermTryProtocol(tp) => {
                     current = PostfixValue::TryProtocol(Box::new(current), Box::new(tp.clone()))
                 }
+                aria_parser::ast::PostfixTerm::PostfixTermOptionalAttribute(_) => {
+                    unreachable!("optional chains are handled as a whole")
+                }
             }
         }
 
@@ -18,6 +18,7 @@ fn builtin_type_id_to_str(id: u8) -> &'static str {
         BUILTIN_TYPE_UNIT => "Unit",
         BUILTIN_TYPE_RESULT => "Result",
         BUILTIN_TYPE_TYPE => "Type",
+        BUILTIN_TYPE_BIGINT => "BigInt",
         _ => "Unknown",
     }
 }
@@ -29,7 +30,7 @@ fn runtime_val_id_to_str(id: u8) -> &'static str {
     }
 }
 
-fn const_best_repr(module: &CompiledModule, idx: u16) -> String {
+fn const_best_repr(module: &CompiledModule, idx: u32) -> String {
     match module.load_indexed_const(idx) {
         Some(s) => s.to_string(),
         None => format!("invalid const @{idx}"),
@@ -40,6 +41,7 @@ fn try_protocol_mode_to_str(id: u8) -> &'static str {
     match id {
         haxby_opcodes::try_unwrap_protocol_mode::PROPAGATE_ERROR => "RETURN",
         haxby_opcodes::try_unwrap_protocol_mode::ASSERT_ERROR => "ASSERT",
+        haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR => "BRANCH",
         _ => "Unknown",
     }
 }
@@ -141,6 +143,14 @@ pub fn opcode_prettyprint(
         | Opcode::Neg
         | Opcode::ShiftLeft
         | Opcode::ShiftRight
+        | Opcode::AddChecked
+        | Opcode::SubChecked
+        | Opcode::MulChecked
+        | Opcode::DivChecked
+        | Opcode::RemChecked
+        | Opcode::NegChecked
+        | Opcode::ShiftLeftChecked
+        | Opcode::ShiftRightChecked
         | Opcode::Not
         | Opcode::Equal
         | Opcode::ReadLocal(_)
@@ -149,6 +159,11 @@ pub fn opcode_prettyprint(
         | Opcode::ReadIndex(_)
         | Opcode::WriteIndex(_)
         | Opcode::ReadUplevel(_)
+        | Opcode::ReadLocalWide(_)
+        | Opcode::WriteLocalWide(_)
+        | Opcode::TypedefLocalWide(_)
+        | Opcode::ReadUplevelWide(_)
+        | Opcode::StoreUplevelWide(_)
         | Opcode::LogicalAnd
         | Opcode::LogicalOr
         | Opcode::Xor
@@ -162,12 +177,21 @@ pub fn opcode_prettyprint(
         | Opcode::JumpFalse(_)
         | Opcode::Jump(_)
         | Opcode::JumpIfArgSupplied(..)
+        | Opcode::JumpIfArgSuppliedWide(..)
         | Opcode::Call(_)
+        | Opcode::CallWide(_)
+        | Opcode::CallNamed(..)
+        | Opcode::CallSpread(_)
         | Opcode::Return
+        | Opcode::Yield
+        | Opcode::Defer
         | Opcode::TryEnter(_)
         | Opcode::TryExit
         | Opcode::Throw
         | Opcode::BuildList(_)
+        | Opcode::ExtendList
+        | Opcode::BuildRange(_)
+        | Opcode::BuildString(_)
         | Opcode::BuildFunction(_)
         | Opcode::StoreUplevel(_)
         | Opcode::BuildStruct
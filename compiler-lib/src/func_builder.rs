@@ -1,12 +1,22 @@
 // SPDX-License-Identifier: Apache-2.0
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use aria_parser::ast::SourcePointer;
-use haxby_opcodes::Opcode;
+use haxby_opcodes::{Opcode, builtin_type_ids::BUILTIN_TYPE_ANY};
 
 use crate::{
-    CompilationOptions, bc_writer::BytecodeWriter, builder::compiler_opcodes::CompilerOpcode,
-    constant_value::ConstantValues, line_table::LineTable,
+    CompilationOptions,
+    bc_writer::BytecodeWriter,
+    builder::{
+        compiler_opcodes::CompilerOpcode,
+        constant_folding::{FoldableValue, fold_binary_operation, fold_unary_operation},
+    },
+    constant_value::{ConstantValues, ConstantValuesError},
+    line_table::LineTable,
 };
 
 struct BasicBlockEntry {
@@ -37,12 +47,21 @@ pub struct BasicBlock {
 }
 
 struct LocalValuesAccess {
-    reads: HashSet<u8>,
-    writes: HashSet<u8>,
+    reads: HashSet<u16>,
+    writes: HashSet<u16>,
+    typechecks: HashSet<u16>,
 }
 
 impl LocalValuesAccess {
-    fn calculate_unused_locals(&self) -> HashSet<u8> {
+    // a local that is never read can still not be dropped if writing it checks its type
+    fn calculate_unused_locals(&self) -> HashSet<u16> {
+        self.calculate_unread_locals()
+            .difference(&self.typechecks)
+            .cloned()
+            .collect()
+    }
+
+    fn calculate_unread_locals(&self) -> HashSet<u16> {
         self.writes.difference(&self.reads).cloned().collect()
     }
 }
@@ -68,6 +87,12 @@ impl BasicBlock {
         self
     }
 
+    pub fn prepend_opcode_and_source_info(&self, op: CompilerOpcode, src: SourcePointer) -> &Self {
+        let bbe = BasicBlockEntry { op, src: Some(src) };
+        self.writer.borrow_mut().insert(0, bbe);
+        self
+    }
+
     pub fn len(&self) -> usize {
         self.writer.borrow().len()
     }
@@ -134,6 +159,128 @@ impl BasicBlock {
         }
     }
 
+    fn fold_constant_operations(&self, cv: &ConstantValues) {
+        let mut br = self.writer.borrow_mut();
+        let mut i = 0;
+        while i < br.len() {
+            if i >= 2
+                && let Some(lhs) = FoldableValue::from_opcode(&br[i - 2].op, cv)
+                && let Some(rhs) = FoldableValue::from_opcode(&br[i - 1].op, cv)
+                && let Some(result) = fold_binary_operation(&br[i].op, &lhs, &rhs)
+            {
+                let src = br[i].src.clone();
+                br[i - 2] = BasicBlockEntry {
+                    op: result.to_opcode(),
+                    src,
+                };
+                br.drain(i - 1..=i);
+                i -= 1;
+                continue;
+            }
+
+            if i >= 1
+                && let Some(val) = FoldableValue::from_opcode(&br[i - 1].op, cv)
+                && let Some(result) = fold_unary_operation(&br[i].op, &val)
+            {
+                let src = br[i].src.clone();
+                br[i - 1] = BasicBlockEntry {
+                    op: result.to_opcode(),
+                    src,
+                };
+                br.remove(i);
+                continue;
+            }
+
+            i += 1;
+        }
+    }
+
+    // finds the locals declared as "val x = <constant>;", along with how many
+    // times each local is declared or written anywhere in this block
+    fn collect_constant_declarations(
+        &self,
+        cv: &ConstantValues,
+        declarations: &mut HashMap<u16, FoldableValue>,
+        writes: &mut HashMap<u16, usize>,
+    ) {
+        let br = self.writer.borrow();
+        for i in 0..br.len() {
+            match br[i].op {
+                CompilerOpcode::WriteLocal(x) => {
+                    *writes.entry(x).or_default() += 1;
+                    if i >= 3
+                        && matches!(br[i - 1].op, CompilerOpcode::TypedefLocal(y) if y == x)
+                        && matches!(br[i - 2].op, CompilerOpcode::PushBuiltinTy(ty) if ty == BUILTIN_TYPE_ANY)
+                        && let Some(val) = FoldableValue::from_opcode(&br[i - 3].op, cv)
+                    {
+                        declarations.insert(x, val);
+                    }
+                }
+                CompilerOpcode::TypedefLocal(x) => {
+                    *writes.entry(x).or_default() += 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // a read is only replaced when the operation consuming it folds away, as each
+    // push makes a new value, and other uses may depend on the identity of the
+    // one stored in the local (e.g. to read attributes written to it)
+    fn replace_constant_local_reads(
+        &self,
+        cv: &ConstantValues,
+        constants: &HashMap<u16, FoldableValue>,
+    ) -> bool {
+        let value_of = |op: &CompilerOpcode| match op {
+            CompilerOpcode::ReadLocal(x) => constants.get(x).cloned(),
+            _ => FoldableValue::from_opcode(op, cv),
+        };
+
+        let mut any = false;
+
+        let mut br = self.writer.borrow_mut();
+        for i in 0..br.len() {
+            let operands = if i >= 2
+                && let Some(lhs) = value_of(&br[i - 2].op)
+                && let Some(rhs) = value_of(&br[i - 1].op)
+                && fold_binary_operation(&br[i].op, &lhs, &rhs).is_some()
+            {
+                i - 2..i
+            } else if i >= 1
+                && let Some(val) = value_of(&br[i - 1].op)
+                && fold_unary_operation(&br[i].op, &val).is_some()
+            {
+                i - 1..i
+            } else {
+                continue;
+            };
+
+            for entry in &mut br[operands] {
+                if let CompilerOpcode::ReadLocal(x) = entry.op {
+                    entry.op = constants[&x].to_opcode();
+                    any = true;
+                }
+            }
+        }
+
+        any
+    }
+
+    fn materialize_folded_values(
+        &self,
+        cv: &mut ConstantValues,
+    ) -> Result<(), ConstantValuesError> {
+        let mut br = self.writer.borrow_mut();
+        for entry in br.iter_mut() {
+            if let CompilerOpcode::PushFolded(val) = &entry.op {
+                entry.op = val.to_final_opcode(cv)?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn optimize_redundant_conditional_jumps(&self) {
         let mut br = self.writer.borrow_mut();
         let mut i = 0;
@@ -248,10 +395,12 @@ impl BasicBlock {
         br.retain(|x| !matches!(x.op, CompilerOpcode::Nop));
     }
 
-    fn remove_push_pop_pairs(&self) {
+    fn remove_push_pop_pairs(&self) -> bool {
+        let mut any = false;
+
         let mut br = self.writer.borrow_mut();
         if br.len() < 2 {
-            return;
+            return false;
         }
 
         for i in 0..br.len() - 1 {
@@ -261,6 +410,7 @@ impl BasicBlock {
                 | CompilerOpcode::PushFalse
                 | CompilerOpcode::PushTrue
                 | CompilerOpcode::Push(_)
+                | CompilerOpcode::PushFolded(_)
                 | CompilerOpcode::PushBuiltinTy(_)
                 | CompilerOpcode::Dup,
                 CompilerOpcode::Pop,
@@ -268,24 +418,31 @@ impl BasicBlock {
             {
                 br[i].op = CompilerOpcode::Nop;
                 br[i + 1].op = CompilerOpcode::Nop;
+                any = true;
             }
         }
+
+        any
     }
 
     fn run_optimize_passes(&self, cv: &ConstantValues) {
         self.optimize_true_false(cv);
+        self.fold_constant_operations(cv);
         self.optimize_redundant_conditional_jumps();
         self.remove_redundant_local_reads();
         self.remove_redundant_named_reads();
         self.remove_store_load_sequence();
         self.remove_instructions_after_terminal();
         self.remove_nop_instructions();
-        self.remove_push_pop_pairs();
-        self.remove_nop_instructions();
+        // removing a pair can make another one adjacent, e.g. the value and
+        // the type of a local that was dropped
+        while self.remove_push_pop_pairs() {
+            self.remove_nop_instructions();
+        }
         while self.replace_double_jump() {}
     }
 
-    fn drop_unused_locals(&self, values: &HashSet<u8>) {
+    fn drop_unused_locals(&self, values: &HashSet<u16>) {
         let mut br = self.writer.borrow_mut();
 
         for i in 0..br.len() {
@@ -293,15 +450,10 @@ impl BasicBlock {
                 CompilerOpcode::ReadLocal(x) => {
                     assert!(!values.contains(&x));
                 }
-                CompilerOpcode::TypedefLocal(x) => {
-                    if values.contains(&x) {
-                        br[i].op = CompilerOpcode::Pop;
-                    }
-                }
-                CompilerOpcode::WriteLocal(x) => {
-                    if values.contains(&x) {
-                        br[i].op = CompilerOpcode::Pop;
-                    }
+                CompilerOpcode::TypedefLocal(x) | CompilerOpcode::WriteLocal(x)
+                    if values.contains(&x) =>
+                {
+                    br[i].op = CompilerOpcode::Pop;
                 }
                 _ => {}
             }
@@ -320,12 +472,12 @@ impl BasicBlock {
                 }
                 CompilerOpcode::TypedefLocal(x) => {
                     if i > 0 {
-                        if let CompilerOpcode::PushBuiltinTy(x) = br[i - 1].op
-                            && x == 1
+                        if let CompilerOpcode::PushBuiltinTy(ty) = br[i - 1].op
+                            && ty == BUILTIN_TYPE_ANY
                         {
                             dest.writes.insert(x);
                         } else {
-                            dest.reads.insert(x);
+                            dest.typechecks.insert(x);
                             dest.writes.insert(x);
                         }
                     } else {
@@ -365,6 +517,7 @@ pub struct FunctionBuilder {
     current: Rc<BasicBlock>,
     bb_id: usize,
     line_table: LineTable,
+    is_generator: bool,
 }
 
 impl Default for FunctionBuilder {
@@ -375,6 +528,7 @@ impl Default for FunctionBuilder {
             current: Rc::new(BasicBlock::new("entry", 0)),
             bb_id: 1,
             line_table: Default::default(),
+            is_generator: false,
         };
         this.blocks.push(this.current.clone());
         this.names.insert(this.current.name.clone());
@@ -383,6 +537,14 @@ impl Default for FunctionBuilder {
 }
 
 impl FunctionBuilder {
+    pub fn mark_as_generator(&mut self) {
+        self.is_generator = true;
+    }
+
+    pub fn is_generator(&self) -> bool {
+        self.is_generator
+    }
+
     pub fn try_get_block(&self, name: &str) -> Option<Rc<BasicBlock>> {
         for blk in &self.blocks {
             if blk.name == name {
@@ -499,11 +661,32 @@ impl FunctionBuilder {
         false
     }
 
-    fn run_optimize_passes(&mut self, cv: &ConstantValues) {
-        let orphans = self.find_orphaned_blocks();
-        for orphan_id in &orphans {
-            assert!(self.remove_block_with_id(*orphan_id));
+    fn remove_orphaned_blocks(&mut self) {
+        loop {
+            let orphans = self.find_orphaned_blocks();
+            if orphans.is_empty() {
+                break;
+            }
+            for orphan_id in &orphans {
+                assert!(self.remove_block_with_id(*orphan_id));
+            }
+        }
+    }
+
+    // the locals that are written but never read by reachable code; this runs before
+    // optimizing, as constant propagation removes reads that the source does perform
+    pub fn unread_locals(&mut self) -> HashSet<u16> {
+        for blk in &self.blocks {
+            blk.remove_instructions_after_terminal();
         }
+        self.remove_orphaned_blocks();
+
+        self.calculate_locals_access().calculate_unread_locals()
+    }
+
+    fn run_optimize_passes(&mut self, cv: &mut ConstantValues) -> Result<(), ConstantValuesError> {
+        self.remove_orphaned_blocks();
+        self.propagate_constant_locals(cv);
 
         let locals_access = self.calculate_locals_access();
         let unused_locals = locals_access.calculate_unused_locals();
@@ -514,12 +697,47 @@ impl FunctionBuilder {
             }
             blk.run_optimize_passes(cv);
         }
+
+        // folded conditions may have turned branches into unconditional jumps
+        self.remove_orphaned_blocks();
+
+        for blk in &self.blocks {
+            blk.materialize_folded_values(cv)?;
+        }
+        Ok(())
+    }
+
+    // a local that is declared with a constant value, and never written again,
+    // can be replaced by that value in the operations that read it; as reads are
+    // replaced, more expressions fold, which can turn more declarations into constants
+    fn propagate_constant_locals(&self, cv: &ConstantValues) {
+        loop {
+            let mut declarations = HashMap::new();
+            let mut writes = HashMap::new();
+            for blk in &self.blocks {
+                blk.optimize_true_false(cv);
+                blk.fold_constant_operations(cv);
+                blk.collect_constant_declarations(cv, &mut declarations, &mut writes);
+            }
+
+            // a declaration writes its local twice, once to give it a type
+            declarations.retain(|x, _| writes.get(x) == Some(&2));
+
+            let mut any = false;
+            for blk in &self.blocks {
+                any |= blk.replace_constant_local_reads(cv, &declarations);
+            }
+            if !any {
+                break;
+            }
+        }
     }
 
     fn calculate_locals_access(&self) -> LocalValuesAccess {
         let mut dest = LocalValuesAccess {
             reads: HashSet::new(),
             writes: HashSet::new(),
+            typechecks: HashSet::new(),
         };
 
         for blk in &self.blocks {
@@ -531,11 +749,12 @@ impl FunctionBuilder {
 
     pub fn write(
         &mut self,
-        cv: &ConstantValues,
+        cv: &mut ConstantValues,
         options: &CompilationOptions,
     ) -> Result<Vec<u8>, crate::do_compile::CompilationErrorReason> {
         if options.optimize {
-            self.run_optimize_passes(cv);
+            self.run_optimize_passes(cv)
+                .map_err(|err| crate::do_compile::CompilationErrorReason::from(&err))?;
         }
 
         let mut dest = BytecodeWriter::default();
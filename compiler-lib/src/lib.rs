@@ -12,15 +12,23 @@ pub mod dump;
 pub mod func_builder;
 pub mod line_table;
 pub mod module;
+pub mod module_file;
 pub mod scope;
 
+#[derive(Clone)]
 pub struct CompilationOptions {
     pub optimize: bool,
+    // integer arithmetic throws on overflow instead of wrapping around;
+    // also turned on for a single module by flag: checked_arithmetic
+    pub checked_arithmetic: bool,
 }
 
 impl Default for CompilationOptions {
     fn default() -> Self {
-        Self { optimize: true }
+        Self {
+            optimize: true,
+            checked_arithmetic: false,
+        }
     }
 }
 
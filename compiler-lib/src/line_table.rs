@@ -21,6 +21,19 @@ impl LineTable {
     pub fn get(&self, idx: u16) -> Option<SourcePointer> {
         self.imp.map.borrow().get(&idx).cloned()
     }
+
+    // sorted by offset, so that serialized modules are deterministic
+    pub fn entries(&self) -> Vec<(u16, SourcePointer)> {
+        let mut entries = self
+            .imp
+            .map
+            .borrow()
+            .iter()
+            .map(|(idx, ptr)| (*idx, ptr.clone()))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(idx, _)| *idx);
+        entries
+    }
 }
 
 impl PartialEq for LineTable {
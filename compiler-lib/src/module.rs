@@ -1,16 +1,20 @@
 use std::path::PathBuf;
 
 // SPDX-License-Identifier: Apache-2.0
-use crate::constant_value::{CompiledCodeObject, ConstantValue, ConstantValues};
+use crate::{
+    constant_value::{CompiledCodeObject, ConstantValue, ConstantValues},
+    do_compile::CompilationWarning,
+};
 
 #[derive(Default)]
 pub struct CompiledModule {
     pub constants: ConstantValues,
     pub widget_root_path: Option<PathBuf>,
+    pub warnings: Vec<CompilationWarning>,
 }
 
 impl CompiledModule {
-    pub fn load_indexed_const(&self, idx: u16) -> Option<ConstantValue> {
+    pub fn load_indexed_const(&self, idx: u32) -> Option<ConstantValue> {
         self.constants.get(idx as usize)
     }
 
@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::{collections::HashMap, path::PathBuf, rc::Rc};
+
+use aria_parser::ast::{Location, SourceBuffer, SourcePointer};
+use thiserror::Error;
+
+use crate::{
+    bc_reader::{BytecodeReader, DecodeError},
+    bc_writer::BytecodeWriter,
+    constant_value::{CompiledCodeObject, ConstantValue},
+    line_table::LineTable,
+    module::CompiledModule,
+};
+
+// a compiled module file starts with this magic, followed by the build of the
+// compiler that wrote it; modules are only ever loaded by that same build
+pub const MODULE_FILE_MAGIC: &[u8; 6] = b"ARIAC\0";
+pub const MODULE_FILE_EXTENSION: &str = "ariac";
+
+const CONST_TAG_INTEGER: u8 = 1;
+const CONST_TAG_STRING: u8 = 2;
+const CONST_TAG_FLOAT: u8 = 3;
+const CONST_TAG_CODE_OBJECT: u8 = 4;
+
+#[derive(Debug, Error)]
+pub enum ModuleFileError {
+    #[error("not a compiled Aria module")]
+    NotAModuleFile,
+    #[error("module was compiled by Aria {0}, but this is Aria {1}")]
+    VersionMismatch(String, String),
+    #[error("compiled module is truncated")]
+    Truncated,
+    #[error("compiled module is corrupt: {0}")]
+    Corrupt(&'static str),
+    #[error("invalid bytecode in function '{0}'")]
+    InvalidBytecode(String),
+}
+
+impl From<DecodeError> for ModuleFileError {
+    fn from(_: DecodeError) -> Self {
+        Self::Truncated
+    }
+}
+
+pub type ModuleFileResult<T> = Result<T, ModuleFileError>;
+
+// the Aria version, plus a fingerprint of the compiler sources (see build.rs) that
+// tells apart builds of the same version whose bytecode may differ
+pub const COMPILER_BUILD: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "+",
+    env!("ARIA_COMPILER_FINGERPRINT")
+);
+
+// source buffers are stored once, and source pointers refer to them by index,
+// so that errors raised by a compiled module still show the original source
+#[derive(Default)]
+struct SourceTable {
+    buffers: Vec<SourceBuffer>,
+    index: HashMap<*const String, u32>,
+}
+
+impl SourceTable {
+    fn index_of(&mut self, sb: &SourceBuffer) -> u32 {
+        *self
+            .index
+            .entry(Rc::as_ptr(&sb.content))
+            .or_insert_with(|| {
+                self.buffers.push(sb.clone());
+                (self.buffers.len() - 1) as u32
+            })
+    }
+
+    fn collect_pointer(&mut self, ptr: &SourcePointer) {
+        self.index_of(&ptr.buffer);
+    }
+
+    fn collect_module(&mut self, module: &CompiledModule) {
+        for cv in module.constants.values() {
+            if let ConstantValue::CompiledCodeObject(cco) = cv {
+                self.collect_pointer(&cco.loc);
+                for (_, ptr) in cco.line_table.entries() {
+                    self.collect_pointer(&ptr);
+                }
+            }
+        }
+    }
+}
+
+struct ModuleFileWriter {
+    dest: BytecodeWriter,
+    sources: SourceTable,
+}
+
+impl ModuleFileWriter {
+    fn write_len(&mut self, len: usize) {
+        self.dest.write_u32(len as u32);
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        self.dest.write_bytes(s.as_bytes());
+    }
+
+    fn write_pointer(&mut self, ptr: &SourcePointer) {
+        let idx = self.sources.index_of(&ptr.buffer);
+        self.dest
+            .write_u32(idx)
+            .write_u64(ptr.location.start as u64)
+            .write_u64(ptr.location.stop as u64);
+    }
+
+    fn write_code_object(&mut self, cco: &CompiledCodeObject) {
+        self.write_str(&cco.name);
+        self.write_len(cco.body.len());
+        self.dest
+            .write_bytes(&cco.body)
+            .write_u16(cco.required_argc)
+            .write_u16(cco.default_argc)
+            .write_u16(cco.frame_size);
+        self.write_len(cco.arg_names.len());
+        for name in &cco.arg_names {
+            self.write_str(name);
+        }
+        self.write_pointer(&cco.loc);
+        let entries = cco.line_table.entries();
+        self.write_len(entries.len());
+        for (idx, ptr) in &entries {
+            self.dest.write_u16(*idx);
+            self.write_pointer(ptr);
+        }
+    }
+
+    fn write_constant(&mut self, cv: &ConstantValue) {
+        match cv {
+            ConstantValue::Integer(n) => {
+                self.dest.write_u8(CONST_TAG_INTEGER).write_u64(*n as u64);
+            }
+            ConstantValue::String(s) => {
+                self.dest.write_u8(CONST_TAG_STRING);
+                self.write_str(s);
+            }
+            ConstantValue::Float(f) => {
+                self.dest
+                    .write_u8(CONST_TAG_FLOAT)
+                    .write_u64(f.raw_value().to_bits());
+            }
+            ConstantValue::CompiledCodeObject(cco) => {
+                self.dest.write_u8(CONST_TAG_CODE_OBJECT);
+                self.write_code_object(cco);
+            }
+        }
+    }
+
+    fn write_module(mut self, module: &CompiledModule) -> Vec<u8> {
+        self.dest.write_bytes(MODULE_FILE_MAGIC);
+        self.write_str(COMPILER_BUILD);
+
+        match module.widget_root_path.as_ref().and_then(|p| p.to_str()) {
+            Some(path) => {
+                self.dest.write_u8(1);
+                self.write_str(path);
+            }
+            None => {
+                self.dest.write_u8(0);
+            }
+        }
+
+        self.sources.collect_module(module);
+        let buffers = self.sources.buffers.clone();
+        self.write_len(buffers.len());
+        for sb in &buffers {
+            self.write_str(&sb.name);
+            self.write_str(&sb.content);
+        }
+
+        self.write_len(module.constants.len());
+        for cv in module.constants.values() {
+            self.write_constant(cv);
+        }
+
+        self.dest.get_data()
+    }
+}
+
+pub fn write_module_file(module: &CompiledModule) -> Vec<u8> {
+    ModuleFileWriter {
+        dest: BytecodeWriter::default(),
+        sources: SourceTable::default(),
+    }
+    .write_module(module)
+}
+
+struct ModuleFileReader {
+    src: BytecodeReader,
+    buffers: Vec<SourceBuffer>,
+}
+
+impl ModuleFileReader {
+    fn read_len(&mut self) -> ModuleFileResult<usize> {
+        Ok(self.src.read_u32()? as usize)
+    }
+
+    fn read_str(&mut self) -> ModuleFileResult<String> {
+        let len = self.read_len()?;
+        let bytes = self.src.read_bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| ModuleFileError::Corrupt("invalid string"))
+    }
+
+    fn read_pointer(&mut self) -> ModuleFileResult<SourcePointer> {
+        let idx = self.src.read_u32()? as usize;
+        let start = self.src.read_u64()? as usize;
+        let stop = self.src.read_u64()? as usize;
+        let buffer = self
+            .buffers
+            .get(idx)
+            .ok_or(ModuleFileError::Corrupt("invalid source index"))?;
+        let content = buffer.content.as_str();
+        if start > stop || !content.is_char_boundary(start) || !content.is_char_boundary(stop) {
+            return Err(ModuleFileError::Corrupt("invalid source location"));
+        }
+        Ok(buffer.pointer(Location { start, stop }))
+    }
+
+    fn read_code_object(&mut self) -> ModuleFileResult<CompiledCodeObject> {
+        let name = self.read_str()?;
+        let len = self.read_len()?;
+        let body = self.src.read_bytes(len)?.to_vec();
+
+        let mut bcr = BytecodeReader::from(body.as_slice());
+        loop {
+            match bcr.read_opcode() {
+                Ok(_) => {}
+                Err(DecodeError::EndOfStream) => break,
+                Err(_) => return Err(ModuleFileError::InvalidBytecode(name)),
+            }
+        }
+
+        let required_argc = self.src.read_u16()?;
+        let default_argc = self.src.read_u16()?;
+        let frame_size = self.src.read_u16()?;
+        let arg_names = (0..self.read_len()?)
+            .map(|_| self.read_str())
+            .collect::<ModuleFileResult<Vec<_>>>()?;
+        let loc = self.read_pointer()?;
+        let line_table = LineTable::default();
+        for _ in 0..self.read_len()? {
+            let idx = self.src.read_u16()?;
+            line_table.insert(idx, self.read_pointer()?);
+        }
+
+        Ok(CompiledCodeObject {
+            name,
+            body,
+            required_argc,
+            default_argc,
+            arg_names,
+            loc,
+            line_table,
+            frame_size,
+        })
+    }
+
+    fn read_constant(&mut self) -> ModuleFileResult<ConstantValue> {
+        match self.src.read_u8()? {
+            CONST_TAG_INTEGER => Ok(ConstantValue::Integer(self.src.read_u64()? as i64)),
+            CONST_TAG_STRING => Ok(ConstantValue::String(self.read_str()?)),
+            CONST_TAG_FLOAT => Ok(ConstantValue::Float(
+                f64::from_bits(self.src.read_u64()?).into(),
+            )),
+            CONST_TAG_CODE_OBJECT => {
+                Ok(ConstantValue::CompiledCodeObject(self.read_code_object()?))
+            }
+            _ => Err(ModuleFileError::Corrupt("unknown constant type")),
+        }
+    }
+
+    fn read_module(mut self) -> ModuleFileResult<CompiledModule> {
+        if self.src.read_bytes(MODULE_FILE_MAGIC.len()).ok() != Some(MODULE_FILE_MAGIC) {
+            return Err(ModuleFileError::NotAModuleFile);
+        }
+        let version = self.read_str()?;
+        if version != COMPILER_BUILD {
+            return Err(ModuleFileError::VersionMismatch(
+                version,
+                COMPILER_BUILD.to_owned(),
+            ));
+        }
+
+        let mut module = CompiledModule::default();
+        if self.src.read_u8()? != 0 {
+            module.widget_root_path = Some(PathBuf::from(self.read_str()?));
+        }
+
+        for _ in 0..self.read_len()? {
+            let name = self.read_str()?;
+            let content = self.read_str()?;
+            self.buffers
+                .push(SourceBuffer::stdin_with_name(&content, &name));
+        }
+
+        let count = self.read_len()?;
+        for idx in 0..count {
+            let cv = self.read_constant()?;
+            if module.constants.insert(cv).ok() != Some(idx as u32) {
+                return Err(ModuleFileError::Corrupt("duplicate constant"));
+            }
+        }
+        // the VM expects __entry to be the last constant in the module
+        let has_entry = count
+            .checked_sub(1)
+            .and_then(|idx| module.constants.get(idx))
+            .is_some_and(|cv| cv.is_compiled_code_object());
+        if !has_entry {
+            return Err(ModuleFileError::Corrupt("missing entry point"));
+        }
+
+        if self.src.get_index() != self.src.len() {
+            return Err(ModuleFileError::Corrupt("trailing data"));
+        }
+
+        Ok(module)
+    }
+}
+
+pub fn read_module_file(data: &[u8]) -> ModuleFileResult<CompiledModule> {
+    ModuleFileReader {
+        src: BytecodeReader::from(data),
+        buffers: vec![],
+    }
+    .read_module()
+}
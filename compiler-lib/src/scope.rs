@@ -1,5 +1,9 @@
 // SPDX-License-Identifier: Apache-2.0
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use aria_parser::ast::SourcePointer;
 use haxby_opcodes::builtin_type_ids::BUILTIN_TYPE_ANY;
@@ -12,17 +16,7 @@ use crate::{
 trait Numeric<Output = Self> {
     fn zero() -> Output;
 
-    fn one() -> Output;
-}
-
-impl Numeric for u8 {
-    fn zero() -> u8 {
-        0_u8
-    }
-
-    fn one() -> u8 {
-        1_u8
-    }
+    fn checked_increment(self) -> Option<Output>;
 }
 
 impl Numeric for u16 {
@@ -30,21 +24,21 @@ impl Numeric for u16 {
         0_u16
     }
 
-    fn one() -> u16 {
-        1_u16
+    fn checked_increment(self) -> Option<u16> {
+        self.checked_add(1)
     }
 }
 
 struct IndexProviderImpl<T>
 where
-    T: std::ops::AddAssign<T> + Numeric + Copy,
+    T: Numeric + Copy,
 {
     next_idx: T,
 }
 
 impl<T> Default for IndexProviderImpl<T>
 where
-    T: std::ops::AddAssign<T> + Numeric + Copy,
+    T: Numeric + Copy,
 {
     fn default() -> Self {
         Self {
@@ -55,12 +49,12 @@ where
 
 impl<T> IndexProviderImpl<T>
 where
-    T: std::ops::AddAssign<T> + Numeric + Copy,
+    T: Numeric + Copy,
 {
-    fn next(&mut self) -> T {
+    fn next(&mut self) -> Option<T> {
         let current = self.next_idx;
-        self.next_idx += T::one();
-        current
+        self.next_idx = current.checked_increment()?;
+        Some(current)
     }
 
     fn get_max_index(&self) -> T {
@@ -70,7 +64,7 @@ where
 
 pub enum ScopeErrorReason {
     TooManyConstants,
-    OverlyDeepClosure,
+    TooManyLocals,
     NoSuchIdentifier(String),
 }
 
@@ -83,10 +77,54 @@ pub type ScopeResult<T = ()> = Result<T, ScopeError>;
 
 #[derive(Default)]
 pub struct ModuleRootScope {
-    symbols: RefCell<HashMap<String, u16>>,
+    symbols: RefCell<HashMap<String, u32>>,
+    imports: RefCell<Vec<(String, SourcePointer)>>,
+    read_names: RefCell<HashSet<String>>,
+    // compiler temporaries live in locals of the module's entry function,
+    // as named values would be visible to anyone importing the module
+    temporaries: RefCell<HashMap<String, u16>>,
+    temporaries_index_provider: RefCell<IndexProviderImpl<u16>>,
 }
 
 impl ModuleRootScope {
+    pub fn num_temporaries(&self) -> u16 {
+        self.temporaries_index_provider.borrow().get_max_index()
+    }
+
+    fn emit_temporary_define(
+        &self,
+        name: &str,
+        dest: Rc<BasicBlock>,
+        loc: SourcePointer,
+    ) -> ScopeResult {
+        let Some(next_idx) = self.temporaries_index_provider.borrow_mut().next() else {
+            return Err(ScopeError {
+                loc,
+                reason: ScopeErrorReason::TooManyLocals,
+            });
+        };
+        self.temporaries
+            .borrow_mut()
+            .insert(name.to_owned(), next_idx);
+        dest.write_opcode_and_source_info(CompilerOpcode::TypedefLocal(next_idx), loc);
+        Ok(())
+    }
+
+    pub fn note_import(&self, name: &str, loc: SourcePointer) {
+        self.imports.borrow_mut().push((name.to_owned(), loc));
+    }
+
+    // names imported into this module that nothing in the module ever reads
+    pub fn unused_imports(&self) -> Vec<(String, SourcePointer)> {
+        let read_names = self.read_names.borrow();
+        self.imports
+            .borrow()
+            .iter()
+            .filter(|(name, _)| !read_names.contains(name))
+            .cloned()
+            .collect()
+    }
+
     pub fn emit_typed_define(
         &self,
         name: &str,
@@ -119,7 +157,10 @@ impl ModuleRootScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
-        if let Some(existing_idx) = self.symbols.borrow().get(name) {
+        if let Some(temp_idx) = self.temporaries.borrow().get(name) {
+            dest.write_opcode_and_source_info(CompilerOpcode::WriteLocal(*temp_idx), loc);
+            Ok(())
+        } else if let Some(existing_idx) = self.symbols.borrow().get(name) {
             dest.write_opcode_and_source_info(CompilerOpcode::WriteNamed(*existing_idx), loc);
             Ok(())
         } else {
@@ -146,6 +187,11 @@ impl ModuleRootScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
+        if let Some(temp_idx) = self.temporaries.borrow().get(name) {
+            dest.write_opcode_and_source_info(CompilerOpcode::ReadLocal(*temp_idx), loc);
+            return Ok(());
+        }
+        self.read_names.borrow_mut().insert(name.to_owned());
         if let Some(existing_idx) = self.symbols.borrow().get(name) {
             dest.write_opcode_and_source_info(CompilerOpcode::ReadNamed(*existing_idx), loc);
         } else {
@@ -165,19 +211,13 @@ impl ModuleRootScope {
         Ok(())
     }
 
-    fn resolve_uplevel_symbol(
-        &self,
-        _: &str,
-        _: Rc<BasicBlock>,
-        _: SourcePointer,
-        _: bool,
-    ) -> ScopeResult<Option<UplevelSymbolResolution>> {
+    fn resolve_uplevel_symbol(&self, _: &str, _: &SourcePointer) -> ScopeResult<Option<u16>> {
         Ok(None)
     }
 }
 
 pub struct ModuleChildScope {
-    symbols: RefCell<HashMap<String, u16>>,
+    symbols: RefCell<HashMap<String, u32>>,
     parent: CompilationScope,
 }
 
@@ -244,34 +284,24 @@ impl ModuleChildScope {
         }
     }
 
-    fn resolve_uplevel_symbol(
-        &self,
-        _: &str,
-        _: Rc<BasicBlock>,
-        _: SourcePointer,
-        _: bool,
-    ) -> ScopeResult<Option<UplevelSymbolResolution>> {
+    fn resolve_uplevel_symbol(&self, _: &str, _: &SourcePointer) -> ScopeResult<Option<u16>> {
         Ok(None)
     }
 }
 
 #[derive(Copy, Clone)]
 pub(crate) struct UplevelInfo {
-    pub idx_in_uplevel: u8,
-}
-
-#[derive(Copy, Clone)]
-struct UplevelSymbolResolution {
-    depth: u8,
-    index_at_depth: u8,
+    pub idx_in_uplevel: u16,
+    pub idx_in_local: u16,
 }
 
 pub struct FunctionRootScope {
-    symbols: RefCell<HashMap<String, u8>>,
-    index_provider: RefCell<IndexProviderImpl<u8>>,
+    symbols: RefCell<HashMap<String, u16>>,
+    index_provider: RefCell<IndexProviderImpl<u16>>,
     parent: CompilationScope,
-    lexical_parent: Option<(CompilationScope, Rc<BasicBlock>)>,
+    lexical_parent: Option<CompilationScope>,
     pub(crate) uplevels: RefCell<Vec<UplevelInfo>>,
+    declared_locals: RefCell<Vec<(u16, String, SourcePointer)>>,
 }
 
 impl FunctionRootScope {
@@ -282,23 +312,43 @@ impl FunctionRootScope {
             parent: parent.get_module_scope().unwrap(),
             lexical_parent: None,
             uplevels: Default::default(),
+            declared_locals: Default::default(),
         }
     }
 
-    fn closure(lexical_parent: (CompilationScope, Rc<BasicBlock>)) -> Self {
+    fn closure(lexical_parent: CompilationScope) -> Self {
         Self {
             symbols: Default::default(),
             index_provider: Default::default(),
-            parent: lexical_parent.0.get_module_scope().unwrap(),
+            parent: lexical_parent.get_module_scope().unwrap(),
             lexical_parent: Some(lexical_parent),
             uplevels: Default::default(),
+            declared_locals: Default::default(),
         }
     }
 
-    pub fn num_locals(&self) -> u8 {
+    pub fn num_locals(&self) -> u16 {
         self.index_provider.borrow().get_max_index()
     }
 
+    fn note_local_declaration(&self, name: &str, loc: SourcePointer) {
+        if let Some(idx) = self.symbols.borrow().get(name) {
+            self.declared_locals
+                .borrow_mut()
+                .push((*idx, name.to_owned(), loc));
+        }
+    }
+
+    // the declarations, in this function or any of its child scopes, of the given locals
+    pub fn declarations_of(&self, locals: &HashSet<u16>) -> Vec<(String, SourcePointer)> {
+        self.declared_locals
+            .borrow()
+            .iter()
+            .filter(|(idx, _, _)| locals.contains(idx))
+            .map(|(_, name, loc)| (name.clone(), loc.clone()))
+            .collect()
+    }
+
     pub fn emit_typed_define(
         &self,
         name: &str,
@@ -306,7 +356,12 @@ impl FunctionRootScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
-        let next_idx = self.index_provider.borrow_mut().next();
+        let Some(next_idx) = self.index_provider.borrow_mut().next() else {
+            return Err(ScopeError {
+                loc,
+                reason: ScopeErrorReason::TooManyLocals,
+            });
+        };
         self.symbols.borrow_mut().insert(name.to_owned(), next_idx);
         dest.write_opcode_and_source_info(CompilerOpcode::TypedefLocal(next_idx), loc);
         Ok(())
@@ -319,55 +374,36 @@ impl FunctionRootScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
-        if let Some(existing_idx) = self.symbols.borrow().get(name) {
-            dest.write_opcode_and_source_info(CompilerOpcode::WriteLocal(*existing_idx), loc);
-            Ok(())
-        } else if let Some(uplevel_info) =
-            self.resolve_uplevel_symbol(name, dest.clone(), loc.clone(), false)?
-        {
-            dest.write_opcode_and_source_info(
-                CompilerOpcode::WriteLocal(uplevel_info.index_at_depth),
-                loc.clone(),
-            );
+        if let Some(existing_idx) = self.resolve_uplevel_symbol(name, &loc)? {
+            dest.write_opcode_and_source_info(CompilerOpcode::WriteLocal(existing_idx), loc);
             Ok(())
         } else {
             self.parent.emit_write(name, consts, dest, loc)
         }
     }
 
+    // captured values are copied into a local by the function prologue, so
+    // that every path through the body (and any nested closure) sees them
     fn store_uplevel_as_local(
         &self,
         name: &str,
-        dest: Rc<BasicBlock>,
-        loc: SourcePointer,
-        uplevel: UplevelSymbolResolution,
-        want_dup_on_stack: bool,
-    ) -> ScopeResult<UplevelSymbolResolution> {
-        if uplevel.depth > 1 {
+        idx_in_uplevel: u16,
+        loc: &SourcePointer,
+    ) -> ScopeResult<u16> {
+        let Some(idx_in_local) = self.index_provider.borrow_mut().next() else {
             return Err(ScopeError {
-                loc,
-                reason: ScopeErrorReason::OverlyDeepClosure,
+                loc: loc.clone(),
+                reason: ScopeErrorReason::TooManyLocals,
             });
-        }
-        let index_in_local = self.index_provider.borrow_mut().next();
+        };
         self.symbols
             .borrow_mut()
-            .insert(name.to_owned(), index_in_local);
+            .insert(name.to_owned(), idx_in_local);
         self.uplevels.borrow_mut().push(UplevelInfo {
-            idx_in_uplevel: uplevel.index_at_depth,
+            idx_in_uplevel,
+            idx_in_local,
         });
-        dest.write_opcode_and_source_info(
-            CompilerOpcode::ReadUplevel(uplevel.index_at_depth),
-            loc.clone(),
-        );
-        if want_dup_on_stack {
-            dest.write_opcode_and_source_info(CompilerOpcode::Dup, loc.clone());
-        }
-        dest.write_opcode_and_source_info(CompilerOpcode::WriteLocal(index_in_local), loc);
-        Ok(UplevelSymbolResolution {
-            depth: 0,
-            index_at_depth: index_in_local,
-        })
+        Ok(idx_in_local)
     }
 
     pub fn emit_read(
@@ -377,43 +413,24 @@ impl FunctionRootScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
-        let maybe_idx = self.symbols.borrow().get(name).cloned();
-        if let Some(existing_idx) = maybe_idx {
+        if let Some(existing_idx) = self.resolve_uplevel_symbol(name, &loc)? {
             dest.write_opcode_and_source_info(CompilerOpcode::ReadLocal(existing_idx), loc);
-            return Ok(());
-        }
-
-        if self
-            .resolve_uplevel_symbol(name, dest.clone(), loc.clone(), true)?
-            .is_some()
-        {
-            return Ok(());
+            Ok(())
+        } else {
+            self.parent.emit_read(name, consts, dest, loc)
         }
-
-        self.parent.emit_read(name, consts, dest, loc)
     }
 
-    fn resolve_uplevel_symbol(
-        &self,
-        name: &str,
-        dest: Rc<BasicBlock>,
-        loc: SourcePointer,
-        want_dup_on_stack: bool,
-    ) -> ScopeResult<Option<UplevelSymbolResolution>> {
+    fn resolve_uplevel_symbol(&self, name: &str, loc: &SourcePointer) -> ScopeResult<Option<u16>> {
         let maybe_idx = self.symbols.borrow().get(name).cloned();
         if let Some(existing_idx) = maybe_idx {
-            Ok(Some(UplevelSymbolResolution {
-                depth: 0,
-                index_at_depth: existing_idx,
-            }))
-        } else if let Some(cp) = &self.lexical_parent {
-            let sr =
-                cp.0.resolve_uplevel_symbol(name, cp.1.clone(), loc.clone(), want_dup_on_stack)?;
-            if let Some(sr) = sr {
-                let sr = self.store_uplevel_as_local(name, dest, loc, sr, want_dup_on_stack)?;
-                Ok(Some(sr))
-            } else {
-                Ok(None)
+            Ok(Some(existing_idx))
+        } else if let Some(lp) = &self.lexical_parent {
+            match lp.resolve_uplevel_symbol(name, loc)? {
+                Some(idx_in_uplevel) => self
+                    .store_uplevel_as_local(name, idx_in_uplevel, loc)
+                    .map(Some),
+                None => Ok(None),
             }
         } else {
             Ok(None)
@@ -422,7 +439,7 @@ impl FunctionRootScope {
 }
 
 pub struct FunctionChildScope {
-    symbols: RefCell<HashMap<String, u8>>,
+    symbols: RefCell<HashMap<String, u16>>,
     parent: CompilationScope,
 }
 
@@ -449,7 +466,12 @@ impl FunctionChildScope {
         dest: Rc<BasicBlock>,
         loc: SourcePointer,
     ) -> ScopeResult {
-        let next_idx = self.get_function_root().index_provider.borrow_mut().next();
+        let Some(next_idx) = self.get_function_root().index_provider.borrow_mut().next() else {
+            return Err(ScopeError {
+                loc,
+                reason: ScopeErrorReason::TooManyLocals,
+            });
+        };
         self.symbols.borrow_mut().insert(name.to_owned(), next_idx);
         dest.write_opcode_and_source_info(CompilerOpcode::TypedefLocal(next_idx), loc);
         Ok(())
@@ -485,21 +507,22 @@ impl FunctionChildScope {
         }
     }
 
-    fn resolve_uplevel_symbol(
-        &self,
-        name: &str,
-        dest: Rc<BasicBlock>,
-        loc: SourcePointer,
-        want_dup_on_stack: bool,
-    ) -> ScopeResult<Option<UplevelSymbolResolution>> {
-        if let Some(existing_idx) = self.symbols.borrow().get(name) {
-            Ok(Some(UplevelSymbolResolution {
-                depth: 0,
-                index_at_depth: *existing_idx,
-            }))
+    fn resolve_uplevel_symbol(&self, name: &str, loc: &SourcePointer) -> ScopeResult<Option<u16>> {
+        let maybe_idx = self.symbols.borrow().get(name).cloned();
+        if let Some(existing_idx) = maybe_idx {
+            Ok(Some(existing_idx))
         } else {
-            self.parent
-                .resolve_uplevel_symbol(name, dest, loc, want_dup_on_stack)
+            self.parent.resolve_uplevel_symbol(name, loc)
+        }
+    }
+
+    fn note_local_declaration(&self, name: &str, loc: SourcePointer) {
+        if let Some(idx) = self.symbols.borrow().get(name) {
+            self.get_function_root().declared_locals.borrow_mut().push((
+                *idx,
+                name.to_owned(),
+                loc,
+            ));
         }
     }
 }
@@ -521,8 +544,8 @@ impl CompilationScope {
         Self::FunctionRoot(Rc::new(FunctionRootScope::root_function(self.clone())))
     }
 
-    pub fn closure(&self, dest: Rc<BasicBlock>) -> Self {
-        Self::FunctionRoot(Rc::new(FunctionRootScope::closure((self.clone(), dest))))
+    pub fn closure(&self) -> Self {
+        Self::FunctionRoot(Rc::new(FunctionRootScope::closure(self.clone())))
     }
 
     pub(crate) fn get_module_scope(&self) -> Option<CompilationScope> {
@@ -575,6 +598,33 @@ impl CompilationScope {
         self.emit_write(name, consts, dest, loc)
     }
 
+    // a value the compiler needs to hold on to while compiling a statement;
+    // unlike a val declared at module level, it is not visible to importers
+    pub fn emit_untyped_temporary_define(
+        &self,
+        name: &str,
+        consts: &mut ConstantValues,
+        dest: Rc<BasicBlock>,
+        loc: SourcePointer,
+    ) -> ScopeResult {
+        match self {
+            Self::ModuleRoot(r) => {
+                dest.write_opcode_and_source_info(
+                    CompilerOpcode::PushBuiltinTy(BUILTIN_TYPE_ANY),
+                    loc.clone(),
+                );
+                r.emit_temporary_define(name, dest.clone(), loc.clone())?;
+                r.emit_write(name, consts, dest, loc)
+            }
+            Self::ModuleChild(c) => c
+                .parent
+                .emit_untyped_temporary_define(name, consts, dest, loc),
+            Self::FunctionRoot(_) | Self::FunctionChild(_) => {
+                self.emit_untyped_define(name, consts, dest, loc)
+            }
+        }
+    }
+
     pub fn emit_write(
         &self,
         name: &str,
@@ -605,18 +655,42 @@ impl CompilationScope {
         }
     }
 
-    fn resolve_uplevel_symbol(
-        &self,
-        name: &str,
-        dest: Rc<BasicBlock>,
-        loc: SourcePointer,
-        want_dup_on_stack: bool,
-    ) -> ScopeResult<Option<UplevelSymbolResolution>> {
+    fn resolve_uplevel_symbol(&self, name: &str, loc: &SourcePointer) -> ScopeResult<Option<u16>> {
+        match self {
+            Self::ModuleRoot(r) => r.resolve_uplevel_symbol(name, loc),
+            Self::ModuleChild(c) => c.resolve_uplevel_symbol(name, loc),
+            Self::FunctionRoot(r) => r.resolve_uplevel_symbol(name, loc),
+            Self::FunctionChild(c) => c.resolve_uplevel_symbol(name, loc),
+        }
+    }
+
+    // records a local that was just defined in this scope, so that it can be
+    // reported if it is never read; module-level values are not tracked
+    pub fn note_local_declaration(&self, name: &str, loc: SourcePointer) {
+        match self {
+            Self::ModuleRoot(_) | Self::ModuleChild(_) => {}
+            Self::FunctionRoot(r) => r.note_local_declaration(name, loc),
+            Self::FunctionChild(c) => c.note_local_declaration(name, loc),
+        }
+    }
+
+    // whether declaring name here would hide a local of the same function that is
+    // bound in an enclosing scope; redeclaring a name within one scope is not shadowing
+    pub fn shadows_outer_local(&self, name: &str) -> bool {
         match self {
-            Self::ModuleRoot(r) => r.resolve_uplevel_symbol(name, dest, loc, want_dup_on_stack),
-            Self::ModuleChild(c) => c.resolve_uplevel_symbol(name, dest, loc, want_dup_on_stack),
-            Self::FunctionRoot(r) => r.resolve_uplevel_symbol(name, dest, loc, want_dup_on_stack),
-            Self::FunctionChild(c) => c.resolve_uplevel_symbol(name, dest, loc, want_dup_on_stack),
+            Self::FunctionChild(c) => c.parent.is_function_local(name),
+            _ => false,
+        }
+    }
+
+    // captured values count as locals once they have been read
+    fn is_function_local(&self, name: &str) -> bool {
+        match self {
+            Self::ModuleRoot(_) | Self::ModuleChild(_) => false,
+            Self::FunctionRoot(r) => r.symbols.borrow().contains_key(name),
+            Self::FunctionChild(c) => {
+                c.symbols.borrow().contains_key(name) || c.parent.is_function_local(name)
+            }
         }
     }
 }
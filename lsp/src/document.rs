@@ -131,6 +131,25 @@ impl DocumentState {
 
         out
     }
+
+    // runs the actual compiler over the document, which is what knows about
+    // unused values, unreachable code and the like
+    pub fn compiler_warning_ranges(&self) -> Vec<(TextRange, String)> {
+        let sb = aria_parser::ast::SourceBuffer::stdin_with_name(&self.text, "<lsp>");
+        let Ok(module) = aria_compiler::compile_from_source(&sb, &Default::default()) else {
+            return vec![];
+        };
+
+        module
+            .warnings
+            .iter()
+            .map(|w| {
+                let start = TextSize::from(w.loc.location.start as u32);
+                let end = TextSize::from(w.loc.location.stop as u32);
+                (TextRange::new(start, end), w.reason.to_string())
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -231,4 +250,16 @@ mod tests {
             errs
         );
     }
+
+    #[test]
+    fn compiler_warnings_are_reported() {
+        let text = "func main() {\n    val y = 2;\n}\n".to_string();
+        let doc = DocumentState::new(text);
+        assert!(doc.parse_error_ranges().is_empty());
+        let warnings = doc.compiler_warning_ranges();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        let (range, msg) = &warnings[0];
+        assert!(msg.contains("'y' is never read"), "{msg}");
+        assert_eq!(doc.line_col(range.start()).line, 1);
+    }
 }
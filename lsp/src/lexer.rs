@@ -14,6 +14,8 @@ pub enum SyntaxKind {
     CatchKwd,
     #[token("continue")]
     ContinueKwd,
+    #[token("defer")]
+    DeferKwd,
     #[token("else")]
     ElseKwd,
     #[token("elsif")]
@@ -22,6 +24,8 @@ pub enum SyntaxKind {
     EnumKwd,
     #[token("extension")]
     ExtensionKwd,
+    #[token("finally")]
+    FinallyKwd,
     #[token("flag")]
     FlagKwd,
     #[token("for")]
@@ -64,6 +68,8 @@ pub enum SyntaxKind {
     ValKwd,
     #[token("while")]
     WhileKwd,
+    #[token("yield")]
+    YieldKwd,
     #[token("and")]
     AndKwd,
 
@@ -133,6 +139,10 @@ pub enum SyntaxKind {
     Arrow,
     #[token("...")]
     Ellipsis,
+    #[token("..")]
+    DotDot,
+    #[token("..=")]
+    DotDotEq,
 
     #[token("(")]
     LeftParen,
@@ -177,6 +187,10 @@ pub enum SyntaxKind {
     #[regex(r#"'([^'\\]|\\.)*'"#)]
     StringLiteral,
 
+    #[token("f\"", |lex| lex_interpolated_string(lex, '"'))]
+    #[token("f'", |lex| lex_interpolated_string(lex, '\''))]
+    InterpolatedStringLiteral,
+
     #[regex(
         r#"[\p{XID_Start}\p{Emoji_Presentation}_$][\p{XID_Continue}\p{Emoji_Presentation}_$]*"#,
         priority = 1
@@ -222,6 +236,12 @@ pub enum SyntaxKind {
     ExprType,
     ExprNonNull,
     ExprNullish,
+    ExprOptionalMember,
+    ExprCoalesce,
+    ExprSpread,
+    ExprRange,
+    ExprIf,
+    ExprMatch,
     Mixin,
     MixinInclude,
     MixinEntry,
@@ -237,14 +257,61 @@ pub enum SyntaxKind {
     MatchRule,
     MatchPattern,
     IdentList,
+    DestructurePattern,
     QualifiedIdent,
     ImportPath,
     ArgList,
+    NamedArg,
     ListLiteral,
+    ListComprehension,
+    MapLiteral,
+    MapComprehension,
+    ComprehensionClause,
     ModuleFlag,
     Eof,
 }
 
+// returns the length of an interpolated string body (up to and including the
+// closing quote), skipping over the embedded expressions and any strings in them
+fn interpolated_string_len(s: &str, quote: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut prev = None;
+    let mut idx = 0;
+    while let Some(c) = s[idx..].chars().next() {
+        idx += c.len_utf8();
+        if depth == 0 {
+            if c == quote {
+                return Some(idx);
+            } else if c == '{' {
+                depth += 1;
+            }
+        } else if c == '{' {
+            depth += 1;
+        } else if c == '}' {
+            depth -= 1;
+        } else if c == '"' || c == '\'' {
+            idx += if prev == Some('f') {
+                interpolated_string_len(&s[idx..], c)?
+            } else {
+                s[idx..].find(c)? + c.len_utf8()
+            };
+        }
+        prev = Some(c);
+    }
+
+    None
+}
+
+fn lex_interpolated_string(lex: &mut logos::Lexer<SyntaxKind>, quote: char) -> bool {
+    match interpolated_string_len(lex.remainder(), quote) {
+        Some(len) => {
+            lex.bump(len);
+            true
+        }
+        None => false,
+    }
+}
+
 pub fn lex(s: &str) -> Vec<Result<(SyntaxKind, &str, logos::Span), LexError>> {
     let mut lexer = SyntaxKind::lexer(s);
     let mut tokens = Vec::new();
@@ -320,6 +387,16 @@ mod tests {
         assert_eq!(tokens[6], SyntaxKind::StringLiteral);
     }
 
+    #[test]
+    fn test_interpolated_string() {
+        let tokens = non_trivia_tokens(r#"f"a {b} {f"{"c"}"}" f'{x}' + f"""#);
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0], SyntaxKind::InterpolatedStringLiteral);
+        assert_eq!(tokens[1], SyntaxKind::InterpolatedStringLiteral);
+        assert_eq!(tokens[2], SyntaxKind::Plus);
+        assert_eq!(tokens[3], SyntaxKind::InterpolatedStringLiteral);
+    }
+
     #[test]
     fn test_operators() {
         let tokens = non_trivia_tokens("+ - * / % == != <= >= << >> && ||");
@@ -378,8 +455,8 @@ mod tests {
             if path.extension().and_then(|s| s.to_str()) == Some("aria") {
                 let filename = path.file_name().unwrap().to_str().unwrap();
 
-                let content =
-                    fs::read_to_string(&path).expect(&format!("Failed to read file: {}", filename));
+                let content = fs::read_to_string(&path)
+                    .unwrap_or_else(|_| panic!("Failed to read file: {}", filename));
 
                 let tokens = lex(&content);
 
@@ -53,6 +53,35 @@ fn to_lsp_range(doc: &DocumentState, range: TextRange) -> Range {
     )
 }
 
+// parse errors, or the compiler's warnings once the document parses
+fn document_diagnostics(doc: &DocumentState) -> Vec<Diagnostic> {
+    let diagnostic = |range, severity, source: &str, message| Diagnostic {
+        range: to_lsp_range(doc, range),
+        severity: Some(severity),
+        code: None,
+        code_description: None,
+        source: Some(source.into()),
+        message,
+        related_information: None,
+        tags: None,
+        data: None,
+    };
+
+    let errors = doc
+        .parse_error_ranges()
+        .into_iter()
+        .map(|(range, msg)| diagnostic(range, DiagnosticSeverity::ERROR, "aria-parser", msg))
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return errors;
+    }
+
+    doc.compiler_warning_ranges()
+        .into_iter()
+        .map(|(range, msg)| diagnostic(range, DiagnosticSeverity::WARNING, "aria-compiler", msg))
+        .collect()
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
@@ -86,23 +115,7 @@ impl LanguageServer for Backend {
         let (diags, uri_clone) = {
             let mut docs = self.documents.lock();
             let doc = DocumentState::new(text);
-            let diags = {
-                let mut v = Vec::new();
-                for (range, msg) in doc.parse_error_ranges() {
-                    v.push(Diagnostic {
-                        range: to_lsp_range(&doc, range),
-                        severity: Some(DiagnosticSeverity::ERROR),
-                        code: None,
-                        code_description: None,
-                        source: Some("aria-parser".into()),
-                        message: msg,
-                        related_information: None,
-                        tags: None,
-                        data: None,
-                    });
-                }
-                v
-            };
+            let diags = document_diagnostics(&doc);
             docs.insert(uri.clone(), doc);
             (diags, uri.clone())
         };
@@ -160,23 +173,7 @@ impl LanguageServer for Backend {
                     }
                 }
                 doc.update_text(text);
-                let diags = {
-                    let mut v = Vec::new();
-                    for (range, msg) in doc.parse_error_ranges() {
-                        v.push(Diagnostic {
-                            range: to_lsp_range(doc, range),
-                            severity: Some(DiagnosticSeverity::ERROR),
-                            code: None,
-                            code_description: None,
-                            source: Some("aria-parser".into()),
-                            message: msg,
-                            related_information: None,
-                            tags: None,
-                            data: None,
-                        });
-                    }
-                    v
-                };
+                let diags = document_diagnostics(doc);
                 (Some(diags), Some(uri.clone()))
             } else {
                 (None, None)
@@ -442,6 +442,8 @@ pub fn parse(text: &str) -> Parse {
                 WhileKwd => self.stmt_while(),
                 ForKwd => self.stmt_for(),
                 ThrowKwd => self.stmt_kwd_with_expr(ThrowKwd),
+                YieldKwd => self.stmt_kwd_with_expr(YieldKwd),
+                DeferKwd => self.stmt_kwd_with_expr(DeferKwd),
                 ReturnKwd => self.stmt_return(),
                 LeftBrace => self.block(),
                 TryKwd => self.try_catch(),
@@ -455,26 +457,79 @@ pub fn parse(text: &str) -> Parse {
         fn stmt_if(&mut self) {
             assert!(self.at(IfKwd));
             let m = self.open();
+            self.if_pieces(false);
+            self.close(m, StmtIf);
+        }
 
+        fn if_pieces(&mut self, is_expr: bool) {
             // if piece
             self.expect(IfKwd);
             let _ = self.expr();
-            self.block();
+            self.branch_block(is_expr);
 
             // elsif pieces
             while self.at(ElsifKwd) {
                 self.expect(ElsifKwd);
                 let _ = self.expr();
-                self.block();
+                self.branch_block(is_expr);
             }
 
             // optional else piece
             if self.at(ElseKwd) {
                 self.expect(ElseKwd);
+                self.branch_block(is_expr);
+            }
+        }
+
+        fn branch_block(&mut self, is_expr: bool) {
+            if is_expr {
+                self.block_expr();
+            } else {
                 self.block();
             }
+        }
 
-            self.close(m, StmtIf);
+        // a block whose last entry may be an expression without a semicolon,
+        // which becomes the value of the block
+        fn block_expr(&mut self) {
+            self.assert_tok(LeftBrace);
+            let m = self.open();
+
+            self.expect(LeftBrace);
+            while !self.at(RightBrace) && !self.eof() {
+                match self.nth(0) {
+                    AssertKwd | BreakKwd | ContinueKwd | ValKwd | WhileKwd | ForKwd | ThrowKwd
+                    | YieldKwd | DeferKwd | ReturnKwd | LeftBrace | TryKwd | StructKwd
+                    | EnumKwd | FuncKwd => self.stmt(),
+                    // these may be statements or the value of the block
+                    IfKwd | MatchKwd => {
+                        let e = self.expr();
+                        if self.at(Semicolon) {
+                            let s = self.open_before(e);
+                            self.expect(Semicolon);
+                            self.close(s, StmtExpr);
+                        }
+                    }
+                    _ => {
+                        let e = self.expr();
+                        if !self.at(RightBrace) {
+                            let s = self.open_before(e);
+                            while self.at(Comma) {
+                                self.expect(Comma);
+                                if self.at(Semicolon) {
+                                    break;
+                                }
+                                let _ = self.expr();
+                            }
+                            self.expect(Semicolon);
+                            self.close(s, StmtExpr);
+                        }
+                    }
+                }
+            }
+            self.expect(RightBrace);
+
+            self.close(m, Block);
         }
 
         fn stmt_for(&mut self) {
@@ -482,7 +537,11 @@ pub fn parse(text: &str) -> Parse {
             let m = self.open();
 
             self.expect(ForKwd);
-            self.expect(Identifier);
+            if self.at(LeftBracket) || self.at(LeftBrace) {
+                self.destructure_pattern();
+            } else {
+                self.expect(Identifier);
+            }
             self.expect(InKwd);
             let _ = self.expr();
             self.block();
@@ -499,21 +558,25 @@ pub fn parse(text: &str) -> Parse {
         fn stmt_match(&mut self) {
             assert!(self.at(MatchKwd));
             let m = self.open();
+            self.match_body(false);
+            self.close(m, StmtMatch);
+        }
 
+        fn match_body(&mut self, is_expr: bool) {
             self.expect(MatchKwd);
             let _ = self.expr();
             self.expect(LeftBrace);
 
             // Parse match rules
             if !self.at(RightBrace) {
-                self.match_rule();
+                self.match_rule(is_expr);
 
                 while (self.at(Comma) || !self.at(RightBrace)) && !self.eof() {
                     if self.at(Comma) {
                         self.expect(Comma);
                     }
                     if !self.at(RightBrace) {
-                        self.match_rule();
+                        self.match_rule(is_expr);
                     }
                 }
 
@@ -527,13 +590,11 @@ pub fn parse(text: &str) -> Parse {
             // optional else piece
             if self.at(ElseKwd) {
                 self.expect(ElseKwd);
-                self.block();
+                self.branch_block(is_expr);
             }
-
-            self.close(m, StmtMatch);
         }
 
-        fn match_rule(&mut self) {
+        fn match_rule(&mut self, is_expr: bool) {
             let m = self.open();
 
             self.match_pattern();
@@ -544,8 +605,20 @@ pub fn parse(text: &str) -> Parse {
                 self.match_pattern();
             }
 
+            // optional guard
+            if self.at(IfKwd) {
+                self.expect(IfKwd);
+                let _ = self.expr();
+            }
+
             self.expect(Arrow); // "=>"
-            self.block();
+            // a match expression rule can also yield a bare expression;
+            // a brace that starts with "key:" is a map literal, not a block
+            if !is_expr || (self.at(LeftBrace) && self.nth(2) != Colon) {
+                self.branch_block(is_expr);
+            } else {
+                let _ = self.expr();
+            }
 
             self.close(m, MatchRule);
         }
@@ -553,21 +626,37 @@ pub fn parse(text: &str) -> Parse {
         fn match_pattern(&mut self) {
             let m = self.open();
 
+            self.match_pattern_simple();
+
+            // Handle "or" alternatives
+            while self.at_contextual_kwd("or") {
+                self.advance();
+                self.match_pattern_simple();
+            }
+
+            self.close(m, MatchPattern);
+        }
+
+        fn match_pattern_simple(&mut self) {
             match self.nth(0) {
                 CaseKwd => {
                     self.expect(CaseKwd);
                     self.expect(Identifier);
                     if self.at(LeftParen) {
                         self.expect(LeftParen);
-                        self.expect(Identifier);
-                        if self.at(Colon) {
-                            self.expect(Colon);
-                            let _ = self.expr();
-                        }
+                        self.match_sub_pattern();
                         self.expect(RightParen);
                     }
                 }
-                Equal | NotEqual | IsaKwd => {
+                IsaKwd => {
+                    self.advance(); // isa
+                    let _ = self.expr();
+                    if self.at_contextual_kwd("as") {
+                        self.advance();
+                        self.expect(Identifier);
+                    }
+                }
+                Equal | NotEqual => {
                     self.advance(); // comparison operator
                     let _ = self.expr();
                 }
@@ -575,10 +664,38 @@ pub fn parse(text: &str) -> Parse {
                     self.advance(); // relational operator
                     let _ = self.expr();
                 }
+                LeftBracket => {
+                    self.expect(LeftBracket);
+                    while !self.at(RightBracket) && !self.eof() {
+                        if self.at(Ellipsis) {
+                            self.expect(Ellipsis);
+                            self.expect(Identifier);
+                        } else {
+                            self.match_sub_pattern();
+                        }
+                        if !self.eat(Comma) {
+                            break;
+                        }
+                    }
+                    self.expect(RightBracket);
+                }
                 _ => self.advance_with_error(MatchPattern),
             }
+        }
 
-            self.close(m, MatchPattern);
+        fn match_sub_pattern(&mut self) {
+            match self.nth(0) {
+                CaseKwd | IsaKwd | Equal | NotEqual | Less | LessEqual | Greater | GreaterEqual
+                | LeftBracket => self.match_pattern(),
+                _ => {
+                    // a binding, optionally typed
+                    self.expect(Identifier);
+                    if self.at(Colon) {
+                        self.expect(Colon);
+                        let _ = self.expr();
+                    }
+                }
+            }
         }
 
         fn stmt_while(&mut self) {
@@ -604,9 +721,21 @@ pub fn parse(text: &str) -> Parse {
 
             self.expect(TryKwd);
             self.block();
-            self.expect(CatchKwd);
-            self.expect(Identifier);
-            self.block();
+            if !self.at(CatchKwd) {
+                self.expect(FinallyKwd);
+                self.block();
+            } else {
+                while self.eat(CatchKwd) {
+                    self.expect(Identifier);
+                    if self.at(Colon) {
+                        self.type_annotation();
+                    }
+                    self.block();
+                }
+                if self.eat(FinallyKwd) {
+                    self.block();
+                }
+            }
 
             self.close(m, TryBlock);
         }
@@ -654,6 +783,15 @@ pub fn parse(text: &str) -> Parse {
 
             self.expect(ValKwd);
 
+            if self.at(LeftBracket) || self.at(LeftBrace) {
+                self.destructure_pattern();
+                self.expect(Assign);
+                let _ = self.expr();
+                self.expect(Semicolon);
+                self.close(m, StmtVal);
+                return;
+            }
+
             loop {
                 self.expect(Identifier);
 
@@ -715,7 +853,7 @@ pub fn parse(text: &str) -> Parse {
             // Recognize an initializer block only when the brace starts with a field or index init
             if self.at(LeftBrace) {
                 let ahead = self.nth(1);
-                if ahead != Dot && ahead != LeftBracket {
+                if ahead != Dot && !(ahead == LeftBracket && self.at_init_index_write()) {
                     return;
                 }
             } else {
@@ -772,6 +910,22 @@ pub fn parse(text: &str) -> Parse {
             self.close(m, IdentList);
         }
 
+        fn destructure_pattern(&mut self) {
+            let m = self.open();
+
+            let closing = if self.at(LeftBracket) {
+                self.expect(LeftBracket);
+                RightBracket
+            } else {
+                self.expect(LeftBrace);
+                RightBrace
+            };
+            self.ident_list();
+            self.expect(closing);
+
+            self.close(m, DestructurePattern);
+        }
+
         fn qualified_ident(&mut self) {
             let m = self.open();
             self.expect(Identifier);
@@ -834,7 +988,8 @@ pub fn parse(text: &str) -> Parse {
             }
             self.expect(Arrow);
 
-            if self.at(LeftBrace) {
+            // a brace that starts with "key:" is a map literal, not a block
+            if self.at(LeftBrace) && self.nth(2) != Colon {
                 self.block();
             } else {
                 let _ = self.expr();
@@ -854,7 +1009,7 @@ pub fn parse(text: &str) -> Parse {
                 // Only consume if the brace actually starts an init block (next is '.' or '[')
                 if op == LeftBrace {
                     let ahead = self.nth(1);
-                    if ahead == Dot || ahead == LeftBracket {
+                    if ahead == Dot || (ahead == LeftBracket && self.at_init_index_write()) {
                         self.init_list();
                         continue;
                     }
@@ -911,6 +1066,32 @@ pub fn parse(text: &str) -> Parse {
                     continue;
                 }
 
+                if op == Question && self.nth(1) == Dot {
+                    let l_bp = 25u8;
+                    if l_bp < min_bp {
+                        break;
+                    }
+                    let m = self.open_before(lhs);
+                    self.expect(Question);
+                    self.expect(Dot);
+                    self.expect(Identifier);
+                    lhs = self.close(m, ExprOptionalMember);
+                    continue;
+                }
+
+                if op == Question && self.nth(1) == Colon {
+                    let (l_bp, r_bp) = (2, 1);
+                    if l_bp < min_bp {
+                        break;
+                    }
+                    let m = self.open_before(lhs);
+                    self.expect(Question);
+                    self.expect(Colon);
+                    let _ = self.expr_bp(r_bp);
+                    lhs = self.close(m, ExprCoalesce);
+                    continue;
+                }
+
                 if op == Question && self.nth(1) == Question {
                     let l_bp = 25u8;
                     if l_bp < min_bp {
@@ -938,6 +1119,24 @@ pub fn parse(text: &str) -> Parse {
                     continue;
                 }
 
+                if op == DotDot || op == DotDotEq {
+                    let (l_bp, r_bp) = (3, 3);
+                    if l_bp < min_bp {
+                        break;
+                    }
+
+                    let m = self.open_before(lhs);
+                    self.advance();
+                    let _ = self.expr_bp(r_bp);
+                    // "step" is not a keyword, only an identifier in this position
+                    if self.at_word("step") {
+                        self.advance();
+                        let _ = self.expr_bp(r_bp);
+                    }
+                    lhs = self.close(m, ExprRange);
+                    continue;
+                }
+
                 if let Some((l_bp, r_bp)) = infix_binding_power(op) {
                     if l_bp < min_bp {
                         break;
@@ -968,8 +1167,15 @@ pub fn parse(text: &str) -> Parse {
             let m = self.open();
 
             match self.nth(0) {
-                HexIntLiteral | OctIntLiteral | BinIntLiteral | DecIntLiteral | FloatLiteral
-                | StringLiteral | TrueKwd | FalseKwd => {
+                HexIntLiteral
+                | OctIntLiteral
+                | BinIntLiteral
+                | DecIntLiteral
+                | FloatLiteral
+                | StringLiteral
+                | InterpolatedStringLiteral
+                | TrueKwd
+                | FalseKwd => {
                     self.advance();
                     self.close(m, ExprLiteral)
                 }
@@ -988,10 +1194,40 @@ pub fn parse(text: &str) -> Parse {
                 }
 
                 LeftBracket => {
-                    self.expr_list(LeftBracket, RightBracket);
+                    self.expect(LeftBracket);
+                    while !self.at(RightBracket) && !self.eof() {
+                        self.list_element();
+                        if self.at(ForKwd) {
+                            self.comprehension_clause();
+                            self.expect(RightBracket);
+                            return self.close(m, ListComprehension);
+                        }
+                        if !self.at(RightBracket) {
+                            self.expect(Comma);
+                        }
+                    }
+                    self.expect(RightBracket);
                     self.close(m, ListLiteral)
                 }
 
+                IfKwd => {
+                    self.if_pieces(true);
+                    self.close(m, ExprIf)
+                }
+
+                MatchKwd => {
+                    self.match_body(true);
+                    self.close(m, ExprMatch)
+                }
+
+                LeftBrace => {
+                    if self.map_entries() {
+                        self.close(m, MapComprehension)
+                    } else {
+                        self.close(m, MapLiteral)
+                    }
+                }
+
                 op if prefix_binding_power(op).is_some() => {
                     let ((), r_bp) = prefix_binding_power(op).unwrap();
                     self.advance();
@@ -1027,10 +1263,76 @@ pub fn parse(text: &str) -> Parse {
             self.expect(right_delim);
         }
 
+        // returns whether the entries turned out to be a comprehension
+        fn map_entries(&mut self) -> bool {
+            self.expect(LeftBrace);
+
+            while !self.at(RightBrace) && !self.eof() {
+                let _ = self.expr();
+                self.expect(Colon);
+                let _ = self.expr();
+                if self.at(ForKwd) {
+                    self.comprehension_clause();
+                    self.expect(RightBrace);
+                    return true;
+                }
+                if !self.at(RightBrace) {
+                    self.expect(Comma);
+                }
+            }
+            self.expect(RightBrace);
+            false
+        }
+
+        // "for x in xs if cond" at the end of a list or map comprehension
+        fn comprehension_clause(&mut self) {
+            let m = self.open();
+            self.expect(ForKwd);
+            if self.at(LeftBracket) || self.at(LeftBrace) {
+                self.destructure_pattern();
+            } else {
+                self.expect(Identifier);
+            }
+            self.expect(InKwd);
+            let _ = self.expr();
+            if self.eat(IfKwd) {
+                let _ = self.expr();
+            }
+            self.close(m, ComprehensionClause);
+        }
+
+        // an expression, or "...list" to spread a list in place
+        fn list_element(&mut self) {
+            if self.at(Ellipsis) {
+                let m = self.open();
+                self.expect(Ellipsis);
+                let _ = self.expr();
+                self.close(m, ExprSpread);
+            } else {
+                let _ = self.expr();
+            }
+        }
+
         fn arg_list(&mut self) {
             assert!(self.at(LeftParen));
             let m = self.open();
-            self.expr_list(LeftParen, RightParen);
+            self.expect(LeftParen);
+            while !self.at(RightParen) && !self.eof() {
+                // named arguments look like "name: value"
+                if self.at(Identifier) && self.nth(1) == Colon {
+                    let n = self.open();
+                    self.expect(Identifier);
+                    self.expect(Colon);
+                    let _ = self.expr();
+                    self.close(n, NamedArg);
+                } else {
+                    self.list_element();
+                }
+                if !self.at(RightParen) {
+                    self.expect(Comma);
+                }
+            }
+            self.expect(RightParen);
             self.close(m, ArgList);
         }
 
@@ -1107,10 +1409,46 @@ pub fn parse(text: &str) -> Parse {
             Eof
         }
 
+        fn at_word(&self, word: &str) -> bool {
+            self.nth(0) == Identifier
+                && self
+                    .tokens
+                    .iter()
+                    .skip(self.pos)
+                    .find(|tok| !is_trivia(tok.0))
+                    .is_some_and(|tok| tok.1 == word)
+        }
+
         fn nth_token(&self, lookahead: usize) -> Option<&(SyntaxKind, &str, logos::Span)> {
             self.tokens.get(self.pos + lookahead)
         }
 
+        // "{ [k] = v" is an initializer block, but "match x { [a] => ..." is not
+        fn at_init_index_write(&self) -> bool {
+            let mut depth = 0;
+            for lookahead in 1.. {
+                match self.nth(lookahead) {
+                    LeftBracket => depth += 1,
+                    RightBracket => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return self.nth(lookahead + 1) == Assign;
+                        }
+                    }
+                    Eof => return false,
+                    _ => {}
+                }
+            }
+            false
+        }
+
+        fn at_contextual_kwd(&self, kwd: &str) -> bool {
+            self.tokens[self.pos..]
+                .iter()
+                .find(|tok| !is_trivia(tok.0))
+                .is_some_and(|tok| tok.0 == Identifier && tok.1 == kwd)
+        }
+
         fn at(&self, kind: SyntaxKind) -> bool {
             self.nth(0) == kind || (kind == Identifier && self.is_keyword(self.nth(0)))
         }
@@ -1132,10 +1470,12 @@ pub fn parse(text: &str) -> Parse {
                     | CaseKwd
                     | CatchKwd
                     | ContinueKwd
+                    | DeferKwd
                     | ElseKwd
                     | ElsifKwd
                     | EnumKwd
                     | ExtensionKwd
+                    | FinallyKwd
                     | FlagKwd
                     | ForKwd
                     | FromKwd
@@ -1157,6 +1497,7 @@ pub fn parse(text: &str) -> Parse {
                     | TypeKwd
                     | ValKwd
                     | WhileKwd
+                    | YieldKwd
                     | AndKwd
                     | TrueKwd
                     | FalseKwd
@@ -1635,6 +1976,393 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_map_literal() {
+        expect_tree(
+            "func test() { val x = {1: 2}; }",
+            &[
+                "File@0..23",
+                "  Func@0..23",
+                "    FuncKwd@0..4 \"func\"",
+                "    Identifier@4..8 \"test\"",
+                "    ParamList@8..10",
+                "      LeftParen@8..9 \"(\"",
+                "      RightParen@9..10 \")\"",
+                "    Block@10..23",
+                "      LeftBrace@10..11 \"{\"",
+                "      StmtVal@11..22",
+                "        ValKwd@11..14 \"val\"",
+                "        Identifier@14..15 \"x\"",
+                "        Assign@15..16 \"=\"",
+                "        MapLiteral@16..21",
+                "          LeftBrace@16..17 \"{\"",
+                "          ExprLiteral@17..18",
+                "            DecIntLiteral@17..18 \"1\"",
+                "          Colon@18..19 \":\"",
+                "          ExprLiteral@19..20",
+                "            DecIntLiteral@19..20 \"2\"",
+                "          RightBrace@20..21 \"}\"",
+                "        Semicolon@21..22 \";\"",
+                "      RightBrace@22..23 \"}\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_val_destructure() {
+        expect_tree(
+            "func test() { val [a, b] = x; }",
+            &[
+                "File@0..23",
+                "  Func@0..23",
+                "    FuncKwd@0..4 \"func\"",
+                "    Identifier@4..8 \"test\"",
+                "    ParamList@8..10",
+                "      LeftParen@8..9 \"(\"",
+                "      RightParen@9..10 \")\"",
+                "    Block@10..23",
+                "      LeftBrace@10..11 \"{\"",
+                "      StmtVal@11..22",
+                "        ValKwd@11..14 \"val\"",
+                "        DestructurePattern@14..19",
+                "          LeftBracket@14..15 \"[\"",
+                "          IdentList@15..18",
+                "            Identifier@15..16 \"a\"",
+                "            Comma@16..17 \",\"",
+                "            Identifier@17..18 \"b\"",
+                "          RightBracket@18..19 \"]\"",
+                "        Assign@19..20 \"=\"",
+                "        ExprName@20..21",
+                "          Identifier@20..21 \"x\"",
+                "        Semicolon@21..22 \";\"",
+                "      RightBrace@22..23 \"}\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_try_finally() {
+        expect_tree(
+            "try {} finally {}",
+            &[
+                "File@0..14",
+                "  TryBlock@0..14",
+                "    TryKwd@0..3 \"try\"",
+                "    Block@3..5",
+                "      LeftBrace@3..4 \"{\"",
+                "      RightBrace@4..5 \"}\"",
+                "    FinallyKwd@5..12 \"finally\"",
+                "    Block@12..14",
+                "      LeftBrace@12..13 \"{\"",
+                "      RightBrace@13..14 \"}\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_typed_catch() {
+        expect_tree(
+            "try {} catch e: A | B {} catch e {}",
+            &[
+                "File@0..25",
+                "  TryBlock@0..25",
+                "    TryKwd@0..3 \"try\"",
+                "    Block@3..5",
+                "      LeftBrace@3..4 \"{\"",
+                "      RightBrace@4..5 \"}\"",
+                "    CatchKwd@5..10 \"catch\"",
+                "    Identifier@10..11 \"e\"",
+                "    Colon@11..12 \":\"",
+                "    ExprType@12..15",
+                "      Identifier@12..13 \"A\"",
+                "      Pipe@13..14 \"|\"",
+                "      Identifier@14..15 \"B\"",
+                "    Block@15..17",
+                "      LeftBrace@15..16 \"{\"",
+                "      RightBrace@16..17 \"}\"",
+                "    CatchKwd@17..22 \"catch\"",
+                "    Identifier@22..23 \"e\"",
+                "    Block@23..25",
+                "      LeftBrace@23..24 \"{\"",
+                "      RightBrace@24..25 \"}\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_named_arguments() {
+        expect_tree(
+            "f(1, retries: 3);",
+            &[
+                "File@0..15",
+                "  StmtExpr@0..15",
+                "    ExprCall@0..14",
+                "      ExprName@0..1",
+                "        Identifier@0..1 \"f\"",
+                "      ArgList@1..14",
+                "        LeftParen@1..2 \"(\"",
+                "        ExprLiteral@2..3",
+                "          DecIntLiteral@2..3 \"1\"",
+                "        Comma@3..4 \",\"",
+                "        NamedArg@4..13",
+                "          Identifier@4..11 \"retries\"",
+                "          Colon@11..12 \":\"",
+                "          ExprLiteral@12..13",
+                "            DecIntLiteral@12..13 \"3\"",
+                "        RightParen@13..14 \")\"",
+                "    Semicolon@14..15 \";\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_spread() {
+        expect_tree(
+            "f([a, ...b], ...c);",
+            &[
+                "File@0..17",
+                "  StmtExpr@0..17",
+                "    ExprCall@0..16",
+                "      ExprName@0..1",
+                "        Identifier@0..1 \"f\"",
+                "      ArgList@1..16",
+                "        LeftParen@1..2 \"(\"",
+                "        ListLiteral@2..10",
+                "          LeftBracket@2..3 \"[\"",
+                "          ExprName@3..4",
+                "            Identifier@3..4 \"a\"",
+                "          Comma@4..5 \",\"",
+                "          ExprSpread@5..9",
+                "            Ellipsis@5..8 \"...\"",
+                "            ExprName@8..9",
+                "              Identifier@8..9 \"b\"",
+                "          RightBracket@9..10 \"]\"",
+                "        Comma@10..11 \",\"",
+                "        ExprSpread@11..15",
+                "          Ellipsis@11..14 \"...\"",
+                "          ExprName@14..15",
+                "            Identifier@14..15 \"c\"",
+                "        RightParen@15..16 \")\"",
+                "    Semicolon@16..17 \";\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_range() {
+        expect_tree(
+            "for i in 0..=n step 2 {}",
+            &[
+                "File@0..18",
+                "  StmtFor@0..18",
+                "    ForKwd@0..3 \"for\"",
+                "    Identifier@3..4 \"i\"",
+                "    InKwd@4..6 \"in\"",
+                "    ExprRange@6..16",
+                "      ExprLiteral@6..7",
+                "        DecIntLiteral@6..7 \"0\"",
+                "      DotDotEq@7..10 \"..=\"",
+                "      ExprName@10..11",
+                "        Identifier@10..11 \"n\"",
+                "      Identifier@11..15 \"step\"",
+                "      ExprLiteral@15..16",
+                "        DecIntLiteral@15..16 \"2\"",
+                "    Block@16..18",
+                "      LeftBrace@16..17 \"{\"",
+                "      RightBrace@17..18 \"}\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_comprehensions() {
+        expect_tree(
+            "val x = [a for a in xs if a > 0]; val y = {k: v for [k, v] in ps};",
+            &[
+                "File@0..45",
+                "  StmtVal@0..22",
+                "    ValKwd@0..3 \"val\"",
+                "    Identifier@3..4 \"x\"",
+                "    Assign@4..5 \"=\"",
+                "    ListComprehension@5..21",
+                "      LeftBracket@5..6 \"[\"",
+                "      ExprName@6..7",
+                "        Identifier@6..7 \"a\"",
+                "      ComprehensionClause@7..20",
+                "        ForKwd@7..10 \"for\"",
+                "        Identifier@10..11 \"a\"",
+                "        InKwd@11..13 \"in\"",
+                "        ExprName@13..15",
+                "          Identifier@13..15 \"xs\"",
+                "        IfKwd@15..17 \"if\"",
+                "        ExprBinary@17..20",
+                "          ExprName@17..18",
+                "            Identifier@17..18 \"a\"",
+                "          Greater@18..19 \">\"",
+                "          ExprLiteral@19..20",
+                "            DecIntLiteral@19..20 \"0\"",
+                "      RightBracket@20..21 \"]\"",
+                "    Semicolon@21..22 \";\"",
+                "  StmtVal@22..45",
+                "    ValKwd@22..25 \"val\"",
+                "    Identifier@25..26 \"y\"",
+                "    Assign@26..27 \"=\"",
+                "    MapComprehension@27..44",
+                "      LeftBrace@27..28 \"{\"",
+                "      ExprName@28..29",
+                "        Identifier@28..29 \"k\"",
+                "      Colon@29..30 \":\"",
+                "      ExprName@30..31",
+                "        Identifier@30..31 \"v\"",
+                "      ComprehensionClause@31..43",
+                "        ForKwd@31..34 \"for\"",
+                "        DestructurePattern@34..39",
+                "          LeftBracket@34..35 \"[\"",
+                "          IdentList@35..38",
+                "            Identifier@35..36 \"k\"",
+                "            Comma@36..37 \",\"",
+                "            Identifier@37..38 \"v\"",
+                "          RightBracket@38..39 \"]\"",
+                "        InKwd@39..41 \"in\"",
+                "        ExprName@41..43",
+                "          Identifier@41..43 \"ps\"",
+                "      RightBrace@43..44 \"}\"",
+                "    Semicolon@44..45 \";\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_optional_chaining() {
+        expect_tree(
+            "val x = a?.b?.c() ?: d??.e;",
+            &[
+                "File@0..22",
+                "  StmtVal@0..22",
+                "    ValKwd@0..3 \"val\"",
+                "    Identifier@3..4 \"x\"",
+                "    Assign@4..5 \"=\"",
+                "    ExprCoalesce@5..21",
+                "      ExprCall@5..14",
+                "        ExprOptionalMember@5..12",
+                "          ExprOptionalMember@5..9",
+                "            ExprName@5..6",
+                "              Identifier@5..6 \"a\"",
+                "            Question@6..7 \"?\"",
+                "            Dot@7..8 \".\"",
+                "            Identifier@8..9 \"b\"",
+                "          Question@9..10 \"?\"",
+                "          Dot@10..11 \".\"",
+                "          Identifier@11..12 \"c\"",
+                "        ArgList@12..14",
+                "          LeftParen@12..13 \"(\"",
+                "          RightParen@13..14 \")\"",
+                "      Question@14..15 \"?\"",
+                "      Colon@15..16 \":\"",
+                "      ExprMember@16..21",
+                "        ExprNullish@16..19",
+                "          ExprName@16..17",
+                "            Identifier@16..17 \"d\"",
+                "          Question@17..18 \"?\"",
+                "          Question@18..19 \"?\"",
+                "        Dot@19..20 \".\"",
+                "        Identifier@20..21 \"e\"",
+                "    Semicolon@21..22 \";\"",
+            ],
+        )
+    }
+
+    #[test]
+    fn test_if_match_expressions() {
+        expect_tree(
+            "val s = if c { f(); 1 } else { 2 };\nval x = match y { case A => 1, case B => { 2 } };",
+            &[
+                "File@0..56",
+                "  StmtVal@0..23",
+                "    ValKwd@0..3 \"val\"",
+                "    Identifier@3..4 \"s\"",
+                "    Assign@4..5 \"=\"",
+                "    ExprIf@5..22",
+                "      IfKwd@5..7 \"if\"",
+                "      ExprName@7..8",
+                "        Identifier@7..8 \"c\"",
+                "      Block@8..15",
+                "        LeftBrace@8..9 \"{\"",
+                "        StmtExpr@9..13",
+                "          ExprCall@9..12",
+                "            ExprName@9..10",
+                "              Identifier@9..10 \"f\"",
+                "            ArgList@10..12",
+                "              LeftParen@10..11 \"(\"",
+                "              RightParen@11..12 \")\"",
+                "          Semicolon@12..13 \";\"",
+                "        ExprLiteral@13..14",
+                "          DecIntLiteral@13..14 \"1\"",
+                "        RightBrace@14..15 \"}\"",
+                "      ElseKwd@15..19 \"else\"",
+                "      Block@19..22",
+                "        LeftBrace@19..20 \"{\"",
+                "        ExprLiteral@20..21",
+                "          DecIntLiteral@20..21 \"2\"",
+                "        RightBrace@21..22 \"}\"",
+                "    Semicolon@22..23 \";\"",
+                "  StmtVal@23..56",
+                "    ValKwd@23..26 \"val\"",
+                "    Identifier@26..27 \"x\"",
+                "    Assign@27..28 \"=\"",
+                "    ExprMatch@28..55",
+                "      MatchKwd@28..33 \"match\"",
+                "      ExprName@33..34",
+                "        Identifier@33..34 \"y\"",
+                "      LeftBrace@34..35 \"{\"",
+                "      MatchRule@35..43",
+                "        MatchPattern@35..40",
+                "          CaseKwd@35..39 \"case\"",
+                "          Identifier@39..40 \"A\"",
+                "        Arrow@40..42 \"=>\"",
+                "        ExprLiteral@42..43",
+                "          DecIntLiteral@42..43 \"1\"",
+                "      Comma@43..44 \",\"",
+                "      MatchRule@44..54",
+                "        MatchPattern@44..49",
+                "          CaseKwd@44..48 \"case\"",
+                "          Identifier@48..49 \"B\"",
+                "        Arrow@49..51 \"=>\"",
+                "        Block@51..54",
+                "          LeftBrace@51..52 \"{\"",
+                "          ExprLiteral@52..53",
+                "            DecIntLiteral@52..53 \"2\"",
+                "          RightBrace@53..54 \"}\"",
+                "      RightBrace@54..55 \"}\"",
+                "    Semicolon@55..56 \";\"",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_yield_stmt() {
+        expect_tree(
+            "func test() { yield 1; }",
+            &[
+                "File@0..19",
+                "  Func@0..19",
+                "    FuncKwd@0..4 \"func\"",
+                "    Identifier@4..8 \"test\"",
+                "    ParamList@8..10",
+                "      LeftParen@8..9 \"(\"",
+                "      RightParen@9..10 \")\"",
+                "    Block@10..19",
+                "      LeftBrace@10..11 \"{\"",
+                "      StmtAssert@11..18",
+                "        YieldKwd@11..16 \"yield\"",
+                "        ExprLiteral@16..17",
+                "          DecIntLiteral@16..17 \"1\"",
+                "        Semicolon@17..18 \";\"",
+                "      RightBrace@18..19 \"}\"",
+            ],
+        )
+    }
+
     #[test]
     fn test_unary_expr() {
         expect_tree(
@@ -1813,7 +2541,8 @@ mod tests {
         let expect_err =
             dir_should_error.contains(&test_dir.file_name().unwrap().to_str().unwrap().to_owned());
 
-        let entries = fs::read_dir(test_dir).expect(&format!("Failed to read directory: {}", dir));
+        let entries =
+            fs::read_dir(test_dir).unwrap_or_else(|_| panic!("Failed to read directory: {}", dir));
 
         for entry in entries {
             let entry = entry.expect("Failed to read directory entry");
@@ -1824,8 +2553,8 @@ mod tests {
 
                 println!("Parsing {}", filename);
 
-                let content =
-                    fs::read_to_string(&path).expect(&format!("Failed to read file: {}", filename));
+                let content = fs::read_to_string(&path)
+                    .unwrap_or_else(|_| panic!("Failed to read file: {}", filename));
 
                 let parse_result = parse(&content);
 
@@ -32,6 +32,12 @@ pub const OPCODE_WRITE_INDEX: u8 = 37;
 pub const OPCODE_READ_ATTRIBUTE: u8 = 38;
 pub const OPCODE_WRITE_ATTRIBUTE: u8 = 39;
 pub const OPCODE_READ_UPLEVEL: u8 = 40;
+// wide variants take a u16 operand, for functions with more than 256 locals or arguments
+pub const OPCODE_READ_LOCAL_WIDE: u8 = 41;
+pub const OPCODE_WRITE_LOCAL_WIDE: u8 = 42;
+pub const OPCODE_TYPEDEF_LOCAL_WIDE: u8 = 43;
+pub const OPCODE_READ_UPLEVEL_WIDE: u8 = 44;
+pub const OPCODE_STORE_UPLEVEL_WIDE: u8 = 45;
 // ...
 pub const OPCODE_EQ: u8 = 50;
 pub const OPCODE_LT: u8 = 51;
@@ -49,12 +55,18 @@ pub const OPCODE_JUMP: u8 = 62;
 pub const OPCODE_JUMP_TRUE: u8 = 63;
 pub const OPCODE_JUMP_FALSE: u8 = 64;
 pub const OPCODE_JUMP_IF_ARG_SUPPLIED: u8 = 65;
+pub const OPCODE_JUMP_IF_ARG_SUPPLIED_WIDE: u8 = 66;
+pub const OPCODE_CALL_NAMED: u8 = 67;
+pub const OPCODE_CALL_SPREAD: u8 = 68;
 // ...
 pub const OPCODE_TRY_ENTER: u8 = 72;
 pub const OPCODE_TRY_EXIT: u8 = 73;
 pub const OPCODE_THROW: u8 = 74;
 pub const OPCODE_CALL: u8 = 75;
 pub const OPCODE_RETURN: u8 = 76;
+pub const OPCODE_YIELD: u8 = 77;
+pub const OPCODE_CALL_WIDE: u8 = 78;
+pub const OPCODE_DEFER: u8 = 79;
 // ...
 pub const OPCODE_BUILD_LIST: u8 = 80;
 pub const OPCODE_BUILD_FUNCTION: u8 = 81;
@@ -69,7 +81,21 @@ pub const OPCODE_NEW_ENUM_VAL: u8 = 89;
 pub const OPCODE_ENUM_CHECK_IS_CASE: u8 = 90;
 pub const OPCODE_ENUM_TRY_EXTRACT_PAYLOAD: u8 = 91;
 pub const OPCODE_TRY_UNWRAP_PROTOCOL: u8 = 92;
+pub const OPCODE_BUILD_STRING: u8 = 93;
+pub const OPCODE_EXTEND_LIST: u8 = 94;
+pub const OPCODE_BUILD_RANGE: u8 = 95;
+// integer arithmetic that throws on overflow, for modules with flag: checked_arithmetic
+pub const OPCODE_ADD_CHECKED: u8 = 96;
+pub const OPCODE_SUB_CHECKED: u8 = 97;
+pub const OPCODE_MUL_CHECKED: u8 = 98;
+pub const OPCODE_DIV_CHECKED: u8 = 99;
+pub const OPCODE_REM_CHECKED: u8 = 100;
+pub const OPCODE_NEG_CHECKED: u8 = 101;
+pub const OPCODE_SHL_CHECKED: u8 = 102;
+pub const OPCODE_SHR_CHECKED: u8 = 103;
 // ...
+// prefixes an opcode whose constant index does not fit in a u16
+pub const OPCODE_WIDE_CONST: u8 = 249;
 pub const OPCODE_IMPORT: u8 = 250;
 pub const OPCODE_LIFT_MODULE: u8 = 251;
 pub const OPCODE_LOAD_DYLIB: u8 = 252;
@@ -81,6 +107,7 @@ pub mod function_attribs {
     pub const FUNC_IS_METHOD:            u8 = 1_u8 << 0;
     pub const METHOD_ATTRIBUTE_TYPE:     u8 = 1_u8 << 1;
     pub const FUNC_ACCEPTS_VARARG:       u8 = 1_u8 << 2;
+    pub const FUNC_IS_GENERATOR:         u8 = 1_u8 << 3;
 }
 
 #[allow(unused_imports)]
@@ -108,6 +135,7 @@ pub mod builtin_type_ids {
     pub const BUILTIN_TYPE_UNIT:           u8 = 10;
     pub const BUILTIN_TYPE_RESULT:         u8 = 11;
     pub const BUILTIN_TYPE_TYPE:           u8 = 12;
+    pub const BUILTIN_TYPE_BIGINT:         u8 = 13;
 }
 
 #[allow(unused_imports)]
@@ -121,10 +149,22 @@ pub mod enum_case_attribs {
 #[allow(unused_imports)]
 use enum_case_attribs::*;
 
+#[rustfmt::skip]
+pub mod range_flags {
+    pub const RANGE_INCLUSIVE:             u8 = 1_u8 << 0;
+    pub const RANGE_HAS_STEP:              u8 = 1_u8 << 1;
+}
+
+#[allow(unused_imports)]
+use range_flags::*;
+
 #[rustfmt::skip]
 pub mod try_unwrap_protocol_mode {
     pub const PROPAGATE_ERROR:  u8 = 1;
     pub const ASSERT_ERROR:     u8 = 2;
+    // push the unwrapped value and true, or the error and false, and let the
+    // compiled code return on its own (so that it can run finally blocks first)
+    pub const BRANCH_ON_ERROR:  u8 = 3;
 }
 
 #[allow(unused_imports)]
@@ -133,7 +173,7 @@ use try_unwrap_protocol_mode::*;
 #[derive(Clone)]
 pub enum Opcode {
     Nop,
-    Push(u16),
+    Push(u32),
     Push0,
     Push1,
     PushTrue,
@@ -152,6 +192,14 @@ pub enum Opcode {
     Neg,
     ShiftLeft,
     ShiftRight,
+    AddChecked,
+    SubChecked,
+    MulChecked,
+    DivChecked,
+    RemChecked,
+    NegChecked,
+    ShiftLeftChecked,
+    ShiftRightChecked,
     Not,
     Equal,
     LessThan,
@@ -161,14 +209,19 @@ pub enum Opcode {
     ReadLocal(u8),
     WriteLocal(u8),
     TypedefLocal(u8),
-    ReadNamed(u16),
-    WriteNamed(u16),
-    TypedefNamed(u16),
+    ReadNamed(u32),
+    WriteNamed(u32),
+    TypedefNamed(u32),
     ReadIndex(u8),
     WriteIndex(u8),
-    ReadAttribute(u16),
-    WriteAttribute(u16),
+    ReadAttribute(u32),
+    WriteAttribute(u32),
     ReadUplevel(u8),
+    ReadLocalWide(u16),
+    WriteLocalWide(u16),
+    TypedefLocalWide(u16),
+    ReadUplevelWide(u16),
+    StoreUplevelWide(u16),
     LogicalAnd,
     LogicalOr,
     Xor,
@@ -178,29 +231,38 @@ pub enum Opcode {
     JumpFalse(u16),
     Jump(u16),
     JumpIfArgSupplied(u8, u16),
+    JumpIfArgSuppliedWide(u16, u16),
     Call(u8),
+    CallWide(u16),
+    CallNamed(u16, u16),
+    CallSpread(u16),
     Return,
+    Yield,
+    Defer,
     TryEnter(u16),
     TryExit,
     Throw,
     BuildList(u32),
+    ExtendList,
+    BuildRange(u8),
     BuildFunction(u8),
     StoreUplevel(u8),
     BuildStruct,
     BuildEnum,
     BuildMixin,
-    BindMethod(u8, u16),
-    BindCase(u8, u16),
+    BindMethod(u8, u32),
+    BindCase(u8, u32),
     IncludeMixin,
-    NewEnumVal(u8, u16),
-    EnumCheckIsCase(u16),
+    NewEnumVal(u8, u32),
+    EnumCheckIsCase(u32),
     EnumTryExtractPayload,
     TryUnwrapProtocol(u8),
+    BuildString(u32),
     Isa,
-    Import(u16),
+    Import(u32),
     LiftModule,
-    LoadDylib(u16),
-    Assert(u16),
+    LoadDylib(u32),
+    Assert(u32),
     Halt,
 }
 
@@ -228,6 +290,14 @@ impl std::fmt::Display for Opcode {
             Self::Neg => write!(f, "NEG"),
             Self::ShiftLeft => write!(f, "SHL"),
             Self::ShiftRight => write!(f, "SHR"),
+            Self::AddChecked => write!(f, "ADD_CHK"),
+            Self::SubChecked => write!(f, "SUB_CHK"),
+            Self::MulChecked => write!(f, "MUL_CHK"),
+            Self::DivChecked => write!(f, "DIV_CHK"),
+            Self::RemChecked => write!(f, "REM_CHK"),
+            Self::NegChecked => write!(f, "NEG_CHK"),
+            Self::ShiftLeftChecked => write!(f, "SHL_CHK"),
+            Self::ShiftRightChecked => write!(f, "SHR_CHK"),
             Self::Not => write!(f, "NOT"),
             Self::ReadLocal(arg0) => write!(f, "READ_LOCAL {arg0}"),
             Self::WriteLocal(arg0) => write!(f, "WRITE_LOCAL {arg0}"),
@@ -240,6 +310,11 @@ impl std::fmt::Display for Opcode {
             Self::ReadAttribute(arg0) => write!(f, "READ_ATTRIB @{arg0}"),
             Self::WriteAttribute(arg0) => write!(f, "WRITE_ATTRIB @{arg0}"),
             Self::ReadUplevel(arg0) => write!(f, "READ_UPLEVEL {arg0}"),
+            Self::ReadLocalWide(arg0) => write!(f, "READ_LOCAL_W {arg0}"),
+            Self::WriteLocalWide(arg0) => write!(f, "WRITE_LOCAL_W {arg0}"),
+            Self::TypedefLocalWide(arg0) => write!(f, "TYPEDEF_LOCAL_W {arg0}"),
+            Self::ReadUplevelWide(arg0) => write!(f, "READ_UPLEVEL_W {arg0}"),
+            Self::StoreUplevelWide(arg0) => write!(f, "STORE_UPLEVEL_W {arg0}"),
             Self::LogicalAnd => write!(f, "ANDL"),
             Self::LogicalOr => write!(f, "ORL"),
             Self::Xor => write!(f, "XOR"),
@@ -253,12 +328,22 @@ impl std::fmt::Display for Opcode {
             Self::JumpFalse(arg0) => write!(f, "JUMP_FALSE {arg0}"),
             Self::Jump(arg0) => write!(f, "JUMP {arg0}"),
             Self::JumpIfArgSupplied(arg0, arg1) => write!(f, "JUMP_IF_ARG_SUPPLIED {arg0} {arg1}"),
+            Self::JumpIfArgSuppliedWide(arg0, arg1) => {
+                write!(f, "JUMP_IF_ARG_SUPPLIED_W {arg0} {arg1}")
+            }
             Self::Call(arg0) => write!(f, "CALL {arg0}"),
+            Self::CallWide(arg0) => write!(f, "CALL_W {arg0}"),
+            Self::CallNamed(arg0, arg1) => write!(f, "CALL_NAMED {arg0} {arg1}"),
+            Self::CallSpread(arg0) => write!(f, "CALL_SPREAD {arg0}"),
             Self::Return => write!(f, "RETURN"),
+            Self::Yield => write!(f, "YIELD"),
+            Self::Defer => write!(f, "DEFER"),
             Self::TryEnter(arg0) => write!(f, "ENTER_TRY {arg0}"),
             Self::TryExit => write!(f, "EXIT_TRY"),
             Self::Throw => write!(f, "THROW"),
             Self::BuildList(arg0) => write!(f, "BUILD_LIST {arg0}"),
+            Self::ExtendList => write!(f, "EXTEND_LIST"),
+            Self::BuildRange(arg0) => write!(f, "BUILD_RANGE {arg0}"),
             Self::BuildFunction(arg0) => write!(f, "BUILD_FUNC {arg0}"),
             Self::StoreUplevel(arg0) => write!(f, "STORE_UPLEVEL {arg0}"),
             Self::BuildStruct => write!(f, "BUILD_STRUCT"),
@@ -271,6 +356,7 @@ impl std::fmt::Display for Opcode {
             Self::EnumCheckIsCase(arg0) => write!(f, "ENUM_CHECK_IS_CASE @{arg0}"),
             Self::EnumTryExtractPayload => write!(f, "ENUM_TRY_EXTRACT_PAYLOAD"),
             Self::TryUnwrapProtocol(mode) => write!(f, "TRY_UNWRAP_PROTOCOL {mode}"),
+            Self::BuildString(arg0) => write!(f, "BUILD_STRING {arg0}"),
             Self::Isa => write!(f, "ISA"),
             Self::Import(arg0) => write!(f, "IMPORT @{arg0}"),
             Self::LiftModule => write!(f, "LIFT_MODULE"),
@@ -281,11 +367,17 @@ impl std::fmt::Display for Opcode {
     }
 }
 
+// constant indices take two bytes whenever they fit, and otherwise four bytes
+// plus an OPCODE_WIDE_CONST prefix, so modules can hold more than 65535 constants
+pub fn const_index_size(idx: u32) -> usize {
+    if idx <= u16::MAX as u32 { 2 } else { 5 }
+}
+
 impl Opcode {
     pub fn byte_size(&self) -> usize {
         match self {
             Self::Nop => 1,
-            Self::Push(_) => 3,
+            Self::Push(n) => 1 + const_index_size(*n),
             Self::Push0 => 1,
             Self::Push1 => 1,
             Self::PushTrue => 1,
@@ -305,18 +397,31 @@ impl Opcode {
             Self::Neg => 1,
             Self::ShiftLeft => 1,
             Self::ShiftRight => 1,
+            Self::AddChecked => 1,
+            Self::SubChecked => 1,
+            Self::MulChecked => 1,
+            Self::DivChecked => 1,
+            Self::RemChecked => 1,
+            Self::NegChecked => 1,
+            Self::ShiftLeftChecked => 1,
+            Self::ShiftRightChecked => 1,
             Self::Not => 1,
             Self::ReadLocal(_) => 2,
             Self::WriteLocal(_) => 2,
             Self::TypedefLocal(_) => 2,
-            Self::ReadNamed(_) => 3,
-            Self::WriteNamed(_) => 3,
-            Self::TypedefNamed(_) => 3,
+            Self::ReadNamed(n) => 1 + const_index_size(*n),
+            Self::WriteNamed(n) => 1 + const_index_size(*n),
+            Self::TypedefNamed(n) => 1 + const_index_size(*n),
             Self::ReadIndex(_) => 2,
             Self::WriteIndex(_) => 2,
-            Self::ReadAttribute(_) => 3,
-            Self::WriteAttribute(_) => 3,
+            Self::ReadAttribute(n) => 1 + const_index_size(*n),
+            Self::WriteAttribute(n) => 1 + const_index_size(*n),
             Self::ReadUplevel(_) => 2,
+            Self::ReadLocalWide(_) => 3,
+            Self::WriteLocalWide(_) => 3,
+            Self::TypedefLocalWide(_) => 3,
+            Self::ReadUplevelWide(_) => 3,
+            Self::StoreUplevelWide(_) => 3,
             Self::LogicalAnd => 1,
             Self::LogicalOr => 1,
             Self::Xor => 1,
@@ -329,30 +434,39 @@ impl Opcode {
             Self::JumpTrue(_) => 3,
             Self::JumpFalse(_) => 3,
             Self::JumpIfArgSupplied(..) => 4,
+            Self::JumpIfArgSuppliedWide(..) => 5,
             Self::Jump(_) => 3,
             Self::Call(_) => 2,
+            Self::CallWide(_) => 3,
+            Self::CallNamed(..) => 5,
+            Self::CallSpread(_) => 3,
             Self::Return => 1,
+            Self::Yield => 1,
+            Self::Defer => 1,
             Self::TryEnter(_) => 3,
             Self::TryExit => 1,
             Self::Throw => 1,
             Self::BuildList(_) => 5,
+            Self::ExtendList => 1,
+            Self::BuildRange(_) => 2,
             Self::BuildFunction(_) => 2,
             Self::StoreUplevel(_) => 2,
             Self::BuildStruct => 1,
             Self::BuildEnum => 1,
             Self::BuildMixin => 1,
-            Self::BindMethod(..) => 4,
-            Self::BindCase(..) => 4,
+            Self::BindMethod(_, n) => 2 + const_index_size(*n),
+            Self::BindCase(_, n) => 2 + const_index_size(*n),
             Self::IncludeMixin => 1,
-            Self::NewEnumVal(..) => 4,
-            Self::EnumCheckIsCase(_) => 3,
+            Self::NewEnumVal(_, n) => 2 + const_index_size(*n),
+            Self::EnumCheckIsCase(n) => 1 + const_index_size(*n),
             Self::EnumTryExtractPayload => 1,
             Self::TryUnwrapProtocol(_) => 2,
+            Self::BuildString(_) => 5,
             Self::Isa => 1,
-            Self::Import(_) => 3,
+            Self::Import(n) => 1 + const_index_size(*n),
             Self::LiftModule => 1,
-            Self::LoadDylib(_) => 3,
-            Self::Assert(_) => 3,
+            Self::LoadDylib(n) => 1 + const_index_size(*n),
+            Self::Assert(n) => 1 + const_index_size(*n),
             Self::Halt => 1,
         }
     }
@@ -3,11 +3,15 @@ use std::{fmt::Display, path::Path, rc::Rc};
 
 use pest::{Parser, error::InputLocation};
 
-use crate::grammar::{HaxbyParser, Rule};
+use crate::{
+    ast::prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    grammar::{HaxbyParser, Rule},
+};
 
 mod derive;
 mod nodes;
 pub mod prettyprint;
+mod recovery;
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub struct Location {
@@ -104,6 +108,11 @@ impl SourceBuffer {
         self.content[..pos].chars().filter(|&c| c == '\n').count()
     }
 
+    pub fn column_index_for_position(&self, pos: usize) -> usize {
+        let (start, _) = self.indices_for_position(pos);
+        self.content[start..pos].chars().count()
+    }
+
     pub fn pointer_to_whole_buffer(&self) -> SourcePointer {
         let start = 0;
         let stop = self.content.len();
@@ -124,13 +133,28 @@ pub struct SourcePointer {
     pub buffer: SourceBuffer,
 }
 
+impl SourcePointer {
+    // spans from the start of this pointer to the end of other, e.g. an operator
+    // chain up to and including one of its operands; the span of a node can
+    // end in whitespace that the grammar skipped after it, which is left out
+    pub fn up_to(&self, other: &SourcePointer) -> SourcePointer {
+        let start = self.location.start;
+        let text = &self.buffer.content[start..other.location.stop];
+        self.buffer.pointer(Location {
+            start,
+            stop: start + text.trim_end().len(),
+        })
+    }
+}
+
 impl Display for SourcePointer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}:{}",
+            "{}:{}:{}",
             self.buffer.name,
-            1 + self.buffer.line_index_for_position(self.location.start)
+            1 + self.buffer.line_index_for_position(self.location.start),
+            1 + self.buffer.column_index_for_position(self.location.start)
         )
     }
 }
@@ -222,10 +246,118 @@ impl ExpressionList {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpreadExpression {
+    pub loc: SourcePointer,
+    pub val: Expression,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListElement {
+    Expression(Expression),
+    SpreadExpression(SpreadExpression),
+}
+
+impl ListElement {
+    pub fn loc(&self) -> &SourcePointer {
+        match self {
+            Self::Expression(e) => e.loc(),
+            Self::SpreadExpression(s) => &s.loc,
+        }
+    }
+}
+
+// the items of a list literal or the positional arguments of a call,
+// where a list can be spread in place with "...list"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementList {
+    pub loc: SourcePointer,
+    pub elements: Vec<ListElement>,
+}
+
+impl ElementList {
+    pub fn empty(loc: SourcePointer) -> Self {
+        Self {
+            loc,
+            elements: vec![],
+        }
+    }
+
+    pub fn has_spread(&self) -> bool {
+        self.elements
+            .iter()
+            .any(|e| matches!(e, ListElement::SpreadExpression(_)))
+    }
+}
+
+impl From<&ExpressionList> for ElementList {
+    fn from(value: &ExpressionList) -> Self {
+        Self {
+            loc: value.loc.clone(),
+            elements: value
+                .expressions
+                .iter()
+                .cloned()
+                .map(ListElement::Expression)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ListLiteral {
     pub loc: SourcePointer,
-    pub items: ExpressionList,
+    pub items: ElementList,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterpolatedStringPiece {
+    Text(String),
+    Expression(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpolatedString {
+    pub loc: SourcePointer,
+    pub pieces: Vec<InterpolatedStringPiece>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapLiteralEntry {
+    pub loc: SourcePointer,
+    pub key: Expression,
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapLiteral {
+    pub loc: SourcePointer,
+    pub entries: Vec<MapLiteralEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComprehensionClause {
+    pub loc: SourcePointer,
+    pub id: ForBinding,
+    pub expr: Box<Expression>,
+    pub cond: Option<Box<Expression>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListComprehension {
+    pub loc: SourcePointer,
+    pub value: Box<Expression>,
+    pub clause: ComprehensionClause,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapComprehension {
+    pub loc: SourcePointer,
+    pub key: Box<Expression>,
+    pub value: Box<Expression>,
+    pub clause: ComprehensionClause,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -249,8 +381,14 @@ pub enum Primary {
     FloatLiteral(FloatLiteral),
     Identifier(Identifier),
     ListLiteral(ListLiteral),
+    ListComprehension(ListComprehension),
+    MapLiteral(MapLiteral),
+    MapComprehension(MapComprehension),
     StringLiteral(StringLiteral),
+    InterpolatedString(InterpolatedString),
     ParenExpression(ParenExpression),
+    IfExpression(IfExpression),
+    MatchExpression(MatchExpression),
 }
 
 impl Primary {
@@ -260,8 +398,14 @@ impl Primary {
             Self::FloatLiteral(fp) => &fp.loc,
             Self::Identifier(id) => &id.loc,
             Self::ListLiteral(ll) => &ll.loc,
+            Self::ListComprehension(lc) => &lc.loc,
+            Self::MapLiteral(ml) => &ml.loc,
+            Self::MapComprehension(mc) => &mc.loc,
             Self::StringLiteral(sl) => &sl.loc,
+            Self::InterpolatedString(is) => &is.loc,
             Self::ParenExpression(pe) => &pe.loc,
+            Self::IfExpression(ie) => &ie.loc,
+            Self::MatchExpression(me) => &me.loc,
         }
     }
 
@@ -296,16 +440,30 @@ pub struct PostfixTermAttribute {
     pub id: Identifier,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostfixTermOptionalAttribute {
+    pub loc: SourcePointer,
+    pub id: Identifier,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PostfixTermIndex {
     pub loc: SourcePointer,
     pub index: ExpressionList,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedArgument {
+    pub loc: SourcePointer,
+    pub name: Identifier,
+    pub value: Expression,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PostfixTermCall {
     pub loc: SourcePointer,
-    pub args: ExpressionList,
+    pub args: ElementList,
+    pub named: Vec<NamedArgument>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -373,6 +531,7 @@ pub struct PostfixTermTryProtocol {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PostfixTerm {
     PostfixTermAttribute(PostfixTermAttribute),
+    PostfixTermOptionalAttribute(PostfixTermOptionalAttribute),
     PostfixTermIndex(PostfixTermIndex),
     PostfixTermCall(PostfixTermCall),
     PostfixTermObjectWrite(PostfixTermObjectWrite),
@@ -403,6 +562,21 @@ impl PostfixExpression {
         }
     }
 
+    pub fn index_read(base: &Primary, index: &Expression) -> PostfixExpression {
+        let read_index = PostfixTerm::PostfixTermIndex(PostfixTermIndex {
+            loc: base.loc().clone(),
+            index: ExpressionList {
+                loc: base.loc().clone(),
+                expressions: vec![index.clone()],
+            },
+        });
+        Self {
+            loc: base.loc().clone(),
+            base: base.clone(),
+            terms: vec![read_index],
+        }
+    }
+
     pub fn method_call(base: &Primary, name: &str, args: &[Expression]) -> PostfixExpression {
         let read_attr = PostfixTerm::PostfixTermAttribute(PostfixTermAttribute {
             loc: base.loc().clone(),
@@ -413,10 +587,11 @@ impl PostfixExpression {
         });
         let call_attr = PostfixTerm::PostfixTermCall(PostfixTermCall {
             loc: base.loc().clone(),
-            args: ExpressionList {
+            args: ElementList::from(&ExpressionList {
                 loc: base.loc().clone(),
                 expressions: args.to_vec(),
-            },
+            }),
+            named: vec![],
         });
         Self {
             loc: base.loc().clone(),
@@ -682,11 +857,29 @@ pub struct TernaryExpression {
     pub false_expression: Box<Expression>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeExpression {
+    pub loc: SourcePointer,
+    pub from: Box<LogOperation>,
+    pub to: Box<LogOperation>,
+    pub inclusive: bool,
+    pub step: Option<Box<LogOperation>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoalesceExpression {
+    pub loc: SourcePointer,
+    pub value: Box<Expression>,
+    pub default: Box<Expression>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(clippy::large_enum_variant)]
 pub enum Expression {
+    CoalesceExpression(CoalesceExpression),
     LambdaFunction(LambdaFunction),
     LogOperation(LogOperation),
+    RangeExpression(RangeExpression),
     TernaryExpression(TernaryExpression),
 }
 
@@ -733,8 +926,10 @@ impl From<&PostfixExpression> for Expression {
 impl Expression {
     pub fn loc(&self) -> &SourcePointer {
         match self {
+            Expression::CoalesceExpression(c) => &c.loc,
             Expression::LogOperation(c) => &c.loc,
             Expression::LambdaFunction(f) => &f.loc,
+            Expression::RangeExpression(r) => &r.loc,
             Expression::TernaryExpression(t) => &t.loc,
         }
     }
@@ -758,7 +953,8 @@ impl Expression {
 
         let call = PostfixTerm::PostfixTermCall(PostfixTermCall {
             loc: loc.clone(),
-            args,
+            args: ElementList::from(&args),
+            named: vec![],
         });
 
         let pfe = PostfixExpression {
@@ -867,6 +1063,40 @@ pub struct ValDeclStatement {
     pub decls: Vec<ValDeclEntry>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListDestructure {
+    pub loc: SourcePointer,
+    pub names: IdentifierList,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectDestructure {
+    pub loc: SourcePointer,
+    pub names: IdentifierList,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DestructurePattern {
+    ListDestructure(ListDestructure),
+    ObjectDestructure(ObjectDestructure),
+}
+
+impl DestructurePattern {
+    pub fn loc(&self) -> &SourcePointer {
+        match self {
+            Self::ListDestructure(l) => &l.loc,
+            Self::ObjectDestructure(o) => &o.loc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValDestructureStatement {
+    pub loc: SourcePointer,
+    pub pattern: DestructurePattern,
+    pub val: Expression,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AssignStatement {
     pub loc: SourcePointer,
@@ -941,7 +1171,42 @@ impl MatchPatternComp {
 pub struct MatchPatternEnumCase {
     pub loc: SourcePointer,
     pub case: Identifier,
-    pub payload: Option<DeclarationId>,
+    pub payload: Option<Box<MatchPattern>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchPatternBind {
+    pub loc: SourcePointer,
+    pub id: DeclarationId,
+}
+
+impl From<&DeclarationId> for MatchPatternBind {
+    fn from(value: &DeclarationId) -> Self {
+        Self {
+            loc: value.loc.clone(),
+            id: value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchPatternIsaBind {
+    pub loc: SourcePointer,
+    pub ty: Expression,
+    pub name: Identifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchPatternList {
+    pub loc: SourcePointer,
+    pub items: Vec<MatchPattern>,
+    pub rest: Option<Identifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchPatternAlternatives {
+    pub loc: SourcePointer,
+    pub alternatives: Vec<MatchPattern>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -949,6 +1214,10 @@ pub enum MatchPattern {
     MatchPatternComp(MatchPatternComp),
     MatchPatternRel(MatchPatternRel),
     MatchPatternEnumCase(MatchPatternEnumCase),
+    MatchPatternBind(MatchPatternBind),
+    MatchPatternIsaBind(MatchPatternIsaBind),
+    MatchPatternList(MatchPatternList),
+    MatchPatternAlternatives(MatchPatternAlternatives),
 }
 
 impl MatchPattern {
@@ -957,6 +1226,10 @@ impl MatchPattern {
             Self::MatchPatternComp(e) => &e.loc,
             Self::MatchPatternRel(e) => &e.loc,
             Self::MatchPatternEnumCase(c) => &c.loc,
+            Self::MatchPatternBind(b) => &b.loc,
+            Self::MatchPatternIsaBind(i) => &i.loc,
+            Self::MatchPatternList(l) => &l.loc,
+            Self::MatchPatternAlternatives(a) => &a.loc,
         }
     }
 }
@@ -965,6 +1238,7 @@ impl MatchPattern {
 pub struct MatchRule {
     pub loc: SourcePointer,
     pub patterns: Vec<MatchPattern>,
+    pub guard: Option<Expression>,
     pub then: CodeBlock,
 }
 
@@ -984,7 +1258,11 @@ impl MatchRule {
             loc: loc.clone(),
             value: case.to_owned(),
         };
-        let payload = payload.map(|p| DeclarationId::from(&p));
+        let payload = payload.map(|p| {
+            Box::new(MatchPattern::MatchPatternBind(MatchPatternBind::from(
+                &DeclarationId::from(&p),
+            )))
+        });
         let case_pattern = MatchPattern::MatchPatternEnumCase(MatchPatternEnumCase {
             loc: enumm.loc.clone(),
             case,
@@ -998,6 +1276,7 @@ impl MatchRule {
         Self {
             loc,
             patterns: vec![isa_pattern, case_pattern],
+            guard: None,
             then,
         }
     }
@@ -1011,6 +1290,54 @@ pub struct MatchStatement {
     pub els: Option<ElsePiece>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockExpression {
+    pub loc: SourcePointer,
+    pub body: Vec<Statement>,
+    pub value: Box<Expression>,
+}
+
+impl From<&Expression> for BlockExpression {
+    fn from(value: &Expression) -> Self {
+        Self {
+            loc: value.loc().clone(),
+            body: vec![],
+            value: Box::new(value.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfExpressionPiece {
+    pub loc: SourcePointer,
+    pub expression: Box<Expression>,
+    pub then: BlockExpression,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IfExpression {
+    pub loc: SourcePointer,
+    pub iff: IfExpressionPiece,
+    pub elsif: Vec<IfExpressionPiece>,
+    pub els: Option<BlockExpression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchExpressionRule {
+    pub loc: SourcePointer,
+    pub patterns: Vec<MatchPattern>,
+    pub guard: Option<Expression>,
+    pub then: BlockExpression,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchExpression {
+    pub loc: SourcePointer,
+    pub expr: Box<Expression>,
+    pub rules: Vec<MatchExpressionRule>,
+    pub els: Option<BlockExpression>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct WhileStatement {
     pub loc: SourcePointer,
@@ -1019,10 +1346,16 @@ pub struct WhileStatement {
     pub els: Option<ElsePiece>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForBinding {
+    Identifier(Identifier),
+    DestructurePattern(DestructurePattern),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ForStatement {
     pub loc: SourcePointer,
-    pub id: Identifier,
+    pub id: ForBinding,
     pub expr: Expression,
     pub then: CodeBlock,
     pub els: Option<ElsePiece>,
@@ -1049,6 +1382,18 @@ pub struct ThrowStatement {
     pub val: Expression,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YieldStatement {
+    pub loc: SourcePointer,
+    pub val: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferStatement {
+    pub loc: SourcePointer,
+    pub val: Expression,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AssertStatement {
     pub loc: SourcePointer,
@@ -1065,18 +1410,26 @@ pub struct ContinueStatement {
     pub loc: SourcePointer,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatchClause {
+    pub loc: SourcePointer,
+    pub id: DeclarationId,
+    pub body: CodeBlock,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TryBlock {
     pub loc: SourcePointer,
     pub body: CodeBlock,
-    pub id: Identifier,
-    pub catch: CodeBlock,
+    pub catches: Vec<CatchClause>,
+    pub finally: Option<CodeBlock>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(clippy::large_enum_variant)]
 pub enum Statement {
     ValDeclStatement(ValDeclStatement),
+    ValDestructureStatement(ValDestructureStatement),
     AssignStatement(AssignStatement),
     WriteOpEqStatement(WriteOpEqStatement),
     IfStatement(IfStatement),
@@ -1086,6 +1439,8 @@ pub enum Statement {
     CodeBlock(CodeBlock),
     ReturnStatement(ReturnStatement),
     ThrowStatement(ThrowStatement),
+    YieldStatement(YieldStatement),
+    DeferStatement(DeferStatement),
     TryBlock(TryBlock),
     AssertStatement(AssertStatement),
     ExpressionStatement(ExpressionStatement),
@@ -1100,6 +1455,7 @@ impl Statement {
     pub fn loc(&self) -> &SourcePointer {
         match self {
             Self::ValDeclStatement(a) => &a.loc,
+            Self::ValDestructureStatement(a) => &a.loc,
             Self::AssignStatement(a) => &a.loc,
             Self::WriteOpEqStatement(a) => &a.loc,
             Self::IfStatement(a) => &a.loc,
@@ -1109,6 +1465,8 @@ impl Statement {
             Self::CodeBlock(a) => &a.loc,
             Self::ReturnStatement(a) => &a.loc,
             Self::ThrowStatement(a) => &a.loc,
+            Self::YieldStatement(a) => &a.loc,
+            Self::DeferStatement(a) => &a.loc,
             Self::TryBlock(a) => &a.loc,
             Self::AssertStatement(a) => &a.loc,
             Self::ExpressionStatement(a) => &a.loc,
@@ -1383,6 +1741,7 @@ pub struct ImportFromStatement {
 pub enum ModuleFlag {
     NoStandardLibrary,
     UsesDylib(String),
+    CheckedArithmetic,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1401,6 +1760,7 @@ impl ModuleFlags {
 pub enum TopLevelEntry {
     ExpressionStatement(ExpressionStatement),
     ValDeclStatement(ValDeclStatement),
+    ValDestructureStatement(ValDestructureStatement),
     WriteOpEqStatement(WriteOpEqStatement),
     AssignStatement(AssignStatement),
     FunctionDecl(FunctionDecl),
@@ -1419,6 +1779,32 @@ pub enum TopLevelEntry {
     TryBlock(TryBlock),
 }
 
+impl TopLevelEntry {
+    pub fn loc(&self) -> &SourcePointer {
+        match self {
+            Self::ValDeclStatement(a) => &a.loc,
+            Self::ValDestructureStatement(a) => &a.loc,
+            Self::WriteOpEqStatement(a) => &a.loc,
+            Self::AssignStatement(a) => &a.loc,
+            Self::FunctionDecl(a) => &a.loc,
+            Self::StructDecl(a) => &a.loc,
+            Self::MixinDecl(a) => &a.loc,
+            Self::EnumDecl(a) => &a.loc,
+            Self::ExtensionDecl(a) => &a.loc,
+            Self::AssertStatement(a) => &a.loc,
+            Self::ExpressionStatement(a) => &a.loc,
+            Self::ImportStatement(a) => &a.loc,
+            Self::ImportFromStatement(a) => &a.loc,
+            Self::IfStatement(a) => &a.loc,
+            Self::MatchStatement(a) => &a.loc,
+            Self::WhileStatement(a) => &a.loc,
+            Self::ForStatement(a) => &a.loc,
+            Self::CodeBlock(a) => &a.loc,
+            Self::TryBlock(a) => &a.loc,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParsedModule {
     pub loc: SourcePointer,
@@ -1438,9 +1824,10 @@ impl std::fmt::Display for ParserError {
     }
 }
 
-pub type ParserResult<T> = Result<T, ParserError>;
+pub type ParserResult<T, E = ParserError> = Result<T, E>;
 
-pub fn source_to_ast(source: &SourceBuffer) -> ParserResult<ParsedModule> {
+// all syntax errors in the source are reported, not just the first one
+pub fn source_to_ast(source: &SourceBuffer) -> ParserResult<ParsedModule, Vec<ParserError>> {
     let input = &source.as_str();
     let parse_tree = HaxbyParser::parse(Rule::module, input);
     match parse_tree {
@@ -1448,13 +1835,18 @@ pub fn source_to_ast(source: &SourceBuffer) -> ParserResult<ParsedModule> {
             pt.next_back().expect("invalid parse tree"),
             source,
         )),
-        Err(err) => {
-            let loc = From::from(&err.location);
-            let ptr = source.pointer(loc);
-            Err(ParserError {
-                loc: ptr,
-                msg: err.variant.message().to_string(),
-            })
-        }
+        Err(err) => Err(recovery::collect_errors(
+            source,
+            recovery::parser_error(source, &err),
+        )),
     }
 }
+
+// formats source code the way the pretty-printer would print its AST,
+// keeping comments and blank lines between declarations and statements
+pub fn format_source(source: &SourceBuffer) -> ParserResult<String, Vec<ParserError>> {
+    let ast = source_to_ast(source)?;
+    Ok(ast
+        .prettyprint(PrintoutAccumulator::for_source(source))
+        .value())
+}
@@ -34,7 +34,11 @@ impl Derive for ArgumentList {
 
 impl PrettyPrintable for ArgumentList {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        let buffer = buffer.write_separated_list(&self.names, ",");
-        if self.vararg { buffer << "..." } else { buffer }
+        let buffer = buffer.write_separated_list(&self.names, ", ");
+        match (self.vararg, self.names.is_empty()) {
+            (false, _) => buffer,
+            (true, true) => buffer << "...",
+            (true, false) => buffer << ", ...",
+        }
     }
 }
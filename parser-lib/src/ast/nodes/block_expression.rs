@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        BlockExpression, Expression, SourceBuffer, SourcePointer, Statement,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for BlockExpression {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::block_expr);
+        let loc = From::from(&p.as_span());
+        let mut body = vec![];
+        let mut value = None;
+        for next in p.into_inner() {
+            match next.as_rule() {
+                Rule::statement => body.push(Statement::from_parse_tree(next, source)),
+                Rule::expression => value = Some(Expression::from_parse_tree(next, source)),
+                _ => panic!("invalid block entry"),
+            }
+        }
+        Self {
+            loc: source.pointer(loc),
+            body,
+            value: Box::new(value.expect("need value")),
+        }
+    }
+}
+
+// the statements of a block expression, followed by its value, are printed as one block
+enum BlockExpressionEntry<'a> {
+    Statement(&'a Statement),
+    Value(&'a Expression),
+}
+
+impl BlockExpressionEntry<'_> {
+    fn loc(&self) -> &SourcePointer {
+        match self {
+            Self::Statement(s) => s.loc(),
+            Self::Value(v) => v.loc(),
+        }
+    }
+}
+
+impl PrettyPrintable for BlockExpressionEntry<'_> {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        match self {
+            Self::Statement(s) => s.prettyprint(buffer),
+            Self::Value(v) => v.prettyprint(buffer),
+        }
+    }
+}
+
+impl PrettyPrintable for BlockExpression {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let block_end = self.loc.location.stop;
+        if self.body.is_empty() && buffer.fits_on_line(self.value.as_ref(), block_end) {
+            return buffer << "{ " << self.value.as_ref() << " }";
+        }
+        let entries = self
+            .body
+            .iter()
+            .map(BlockExpressionEntry::Statement)
+            .chain(std::iter::once(BlockExpressionEntry::Value(&self.value)))
+            .collect::<Vec<_>>();
+        buffer.write_block(
+            &entries,
+            BlockExpressionEntry::loc,
+            |_| "",
+            self.loc.location.start,
+        )
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        CatchClause, CodeBlock, DeclarationId,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for CatchClause {
+    gen_from_components!(catch_clause; id: DeclarationId, body: CodeBlock);
+}
+
+impl PrettyPrintable for CatchClause {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "catch " << &self.id << " " << &self.body
+    }
+}
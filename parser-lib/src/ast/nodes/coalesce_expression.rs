@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        CoalesceExpression, Expression, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for CoalesceExpression {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::coalesce_expr);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let value = Expression::from_range_expr(inner.next().expect("need value"), source);
+        let default = Expression::from_parse_tree(inner.next().expect("need default"), source);
+        Self {
+            loc: source.pointer(loc),
+            value: Box::new(value),
+            default: Box::new(default),
+        }
+    }
+}
+
+impl PrettyPrintable for CoalesceExpression {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << self.value.as_ref() << " ?: " << self.default.as_ref()
+    }
+}
@@ -25,6 +25,11 @@ impl Derive for CodeBlock {
 
 impl PrettyPrintable for CodeBlock {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer.write_indented_list(&self.entries, "{\n", "\n", "\n}")
+        buffer.write_block(
+            &self.entries,
+            Statement::loc,
+            |_| "",
+            self.loc.location.start,
+        )
     }
 }
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        ComprehensionClause, Expression, ForBinding, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for ComprehensionClause {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::comprehension_clause);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let id = ForBinding::from_parse_tree(inner.next().expect("need binding"), source);
+        let expr = Expression::from_parse_tree(inner.next().expect("need expression"), source);
+        let cond = inner
+            .next()
+            .map(|c| Box::new(Expression::from_parse_tree(c, source)));
+        Self {
+            loc: source.pointer(loc),
+            id,
+            expr: Box::new(expr),
+            cond,
+        }
+    }
+}
+
+impl PrettyPrintable for ComprehensionClause {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let buffer = buffer << "for " << &self.id << " in " << self.expr.as_ref();
+        match &self.cond {
+            Some(cond) => buffer << " if " << cond.as_ref(),
+            None => buffer,
+        }
+    }
+}
@@ -29,7 +29,7 @@ impl PrettyPrintable for DeclarationId {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         let buffer = buffer << &self.name;
         if let Some(ty) = &self.ty {
-            buffer << " : " << ty
+            buffer << ": " << ty
         } else {
             buffer
         }
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        DeferStatement, Expression,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for DeferStatement {
+    gen_from_components!(defer_stmt; val: Expression);
+}
+
+impl PrettyPrintable for DeferStatement {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "defer " << &self.val << ";"
+    }
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        DestructurePattern, ListDestructure, ObjectDestructure,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_options,
+};
+
+impl Derive for DestructurePattern {
+    gen_from_options!(
+        destructure_pattern;
+        (list_destructure, ListDestructure),
+        (object_destructure, ObjectDestructure),
+    );
+}
+
+impl PrettyPrintable for DestructurePattern {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        match self {
+            Self::ListDestructure(l) => l.prettyprint(buffer),
+            Self::ObjectDestructure(o) => o.prettyprint(buffer),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        ElementList, ListElement, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for ElementList {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::element_list);
+        let loc = From::from(&p.as_span());
+        let elements = p
+            .into_inner()
+            .map(|e| ListElement::from_parse_tree(e, source))
+            .collect::<Vec<_>>();
+        Self {
+            loc: source.pointer(loc),
+            elements,
+        }
+    }
+}
+
+impl PrettyPrintable for ElementList {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer.write_separated_list(&self.elements, ", ")
+    }
+}
@@ -24,6 +24,6 @@ impl Derive for ElsePiece {
 
 impl PrettyPrintable for ElsePiece {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "else " << &self.then
+        buffer << " else " << &self.then
     }
 }
@@ -27,7 +27,7 @@ impl Derive for EnumCaseDecl {
 
 impl PrettyPrintable for EnumCaseDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        let buffer = buffer << &self.name;
+        let buffer = buffer << "case " << &self.name;
         if let Some(payload) = &self.payload {
             buffer << "(" << payload << ")"
         } else {
@@ -44,6 +44,14 @@ impl Derive for EnumDecl {
 
 impl PrettyPrintable for EnumDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        (buffer << "enum " << &self.name).write_indented_list(&self.body, "{\n", "\n", "\n}")
+        (buffer << "enum " << &self.name << " ").write_block(
+            &self.body,
+            EnumDeclEntry::loc,
+            |entry| match entry {
+                EnumDeclEntry::EnumCaseDecl(_) => ",",
+                EnumDeclEntry::StructEntry(_) => "",
+            },
+            self.name.loc.location.stop,
+        )
     }
 }
@@ -2,7 +2,8 @@
 use crate::{
     ast::TernaryExpression,
     ast::{
-        Expression, LambdaFunction, LogOperation, SourceBuffer,
+        CoalesceExpression, Expression, LambdaFunction, LogOperation, RangeExpression,
+        SourceBuffer,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -14,7 +15,17 @@ impl Derive for Expression {
         assert!(p.as_rule() == Rule::expression);
         let content = p.into_inner().next().expect("needs an atom inside");
         match content.as_rule() {
-            Rule::log => Self::LogOperation(LogOperation::from_parse_tree(content, source)),
+            Rule::coalesce_expr => {
+                if content.clone().into_inner().len() == 1 {
+                    let range = content
+                        .into_inner()
+                        .next()
+                        .expect("needs a range expression");
+                    Self::from_range_expr(range, source)
+                } else {
+                    Self::CoalesceExpression(CoalesceExpression::from_parse_tree(content, source))
+                }
+            }
             Rule::lambda_f => {
                 Self::LambdaFunction(LambdaFunction::from_parse_tree(content, source))
             }
@@ -26,11 +37,29 @@ impl Derive for Expression {
     }
 }
 
+impl Expression {
+    // a range expression without a range operator is just its operand
+    pub(crate) fn from_range_expr(
+        p: pest::iterators::Pair<'_, Rule>,
+        source: &SourceBuffer,
+    ) -> Self {
+        assert!(p.as_rule() == Rule::range_expr);
+        if p.clone().into_inner().len() == 1 {
+            let log = p.into_inner().next().expect("needs a log operation");
+            Self::LogOperation(LogOperation::from_parse_tree(log, source))
+        } else {
+            Self::RangeExpression(RangeExpression::from_parse_tree(p, source))
+        }
+    }
+}
+
 impl PrettyPrintable for Expression {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         match self {
+            Expression::CoalesceExpression(c) => c.prettyprint(buffer),
             Expression::LogOperation(c) => c.prettyprint(buffer),
             Expression::LambdaFunction(f) => f.prettyprint(buffer),
+            Expression::RangeExpression(r) => r.prettyprint(buffer),
             Expression::TernaryExpression(t) => t.prettyprint(buffer),
         }
     }
@@ -25,6 +25,6 @@ impl Derive for ExpressionList {
 
 impl PrettyPrintable for ExpressionList {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer.write_separated_list(&self.expressions, ",")
+        buffer.write_separated_list(&self.expressions, ", ")
     }
 }
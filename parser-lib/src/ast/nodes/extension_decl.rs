@@ -29,6 +29,11 @@ impl Derive for ExtensionDecl {
 
 impl PrettyPrintable for ExtensionDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        (buffer << "extension " << &self.target).write_indented_list(&self.body, "{\n", "\n", "\n}")
+        (buffer << "extension " << &self.target << " ").write_block(
+            &self.body,
+            StructEntry::loc,
+            |_| "",
+            self.target.loc().location.stop,
+        )
     }
 }
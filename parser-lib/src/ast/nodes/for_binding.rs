@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        DestructurePattern, ForBinding, Identifier,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_options,
+};
+
+impl Derive for ForBinding {
+    gen_from_options!(
+        for_binding;
+        (identifier, Identifier),
+        (destructure_pattern, DestructurePattern),
+    );
+}
+
+impl PrettyPrintable for ForBinding {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        match self {
+            Self::Identifier(i) => i.prettyprint(buffer),
+            Self::DestructurePattern(d) => d.prettyprint(buffer),
+        }
+    }
+}
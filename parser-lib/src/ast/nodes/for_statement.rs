@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     ast::{
-        CodeBlock, ElsePiece, Expression, ForStatement, Identifier,
+        CodeBlock, ElsePiece, Expression, ForBinding, ForStatement,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -16,7 +16,7 @@ impl Derive for ForStatement {
         assert!(p.as_rule() == Rule::for_stmt);
         let loc = From::from(&p.as_span());
         let mut inner = p.into_inner();
-        let id = Identifier::from_parse_tree(inner.next().expect("need identifier"), source);
+        let id = ForBinding::from_parse_tree(inner.next().expect("need binding"), source);
         let expr = Expression::from_parse_tree(inner.next().expect("need expression"), source);
         let then = CodeBlock::from_parse_tree(inner.next().expect("need then block"), source);
         let els = inner
@@ -35,6 +35,6 @@ impl Derive for ForStatement {
 
 impl PrettyPrintable for ForStatement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "for( " << &self.id << " in " << &self.expr << ") " << &self.then
+        buffer << "for " << &self.id << " in " << &self.expr << " " << &self.then << &self.els
     }
 }
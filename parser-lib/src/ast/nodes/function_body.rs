@@ -38,6 +38,21 @@ impl Derive for FunctionBody {
 
 impl PrettyPrintable for FunctionBody {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << &self.code
+        // "= expr;" is stored as a block with a single return, that starts at the "="
+        if let [Statement::ReturnStatement(ReturnStatement { val: Some(val), .. })] =
+            self.code.entries.as_slice()
+            && self
+                .code
+                .loc
+                .buffer
+                .content
+                .as_bytes()
+                .get(self.code.loc.location.start)
+                == Some(&b'=')
+        {
+            buffer << "= " << val << ";"
+        } else {
+            buffer << &self.code
+        }
     }
 }
@@ -33,6 +33,6 @@ impl Derive for FunctionDecl {
 
 impl PrettyPrintable for FunctionDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "func " << &self.name << " (" << &self.args << ") " << &self.body
+        buffer << "func " << &self.name << "(" << &self.args << ") " << &self.body
     }
 }
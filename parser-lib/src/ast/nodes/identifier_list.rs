@@ -25,6 +25,6 @@ impl Derive for IdentifierList {
 
 impl PrettyPrintable for IdentifierList {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer.write_separated_list(&self.identifiers, ",")
+        buffer.write_separated_list(&self.identifiers, ", ")
     }
 }
@@ -27,6 +27,6 @@ impl Derive for IfCondPiece {
 
 impl PrettyPrintable for IfCondPiece {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "(" << &self.expression << ")" << &self.then
+        buffer << &self.expression << " " << &self.then
     }
 }
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        BlockExpression, IfExpression, IfExpressionPiece, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for IfExpression {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::if_expr);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let iff = IfExpressionPiece::from_parse_tree(inner.next().expect("need if piece"), source);
+        let mut elsif = vec![];
+        let mut els = None;
+        for next in inner {
+            match next.as_rule() {
+                Rule::if_expr_piece => elsif.push(IfExpressionPiece::from_parse_tree(next, source)),
+                Rule::block_expr => els = Some(BlockExpression::from_parse_tree(next, source)),
+                _ => panic!("invalid if entry"),
+            }
+        }
+        Self {
+            loc: source.pointer(loc),
+            iff,
+            elsif,
+            els,
+        }
+    }
+}
+
+impl PrettyPrintable for IfExpression {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let mut buffer = buffer << "if " << &self.iff;
+        for elsif in &self.elsif {
+            buffer = buffer << " elsif " << elsif;
+        }
+        if let Some(els) = &self.els {
+            buffer << " else " << els
+        } else {
+            buffer
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        BlockExpression, Expression, IfExpressionPiece, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for IfExpressionPiece {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::if_expr_piece);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let expr = inner.next().expect("need expression");
+        let body = inner.next().expect("need body");
+        let expression = Expression::from_parse_tree(expr, source);
+        let then = BlockExpression::from_parse_tree(body, source);
+        Self {
+            loc: source.pointer(loc),
+            expression: Box::new(expression),
+            then,
+        }
+    }
+}
+
+impl PrettyPrintable for IfExpressionPiece {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << self.expression.as_ref() << " " << &self.then
+    }
+}
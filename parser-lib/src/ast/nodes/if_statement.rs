@@ -49,7 +49,7 @@ impl PrettyPrintable for IfStatement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         let mut this = self.iff.prettyprint(buffer);
         for elsif in &self.elsif {
-            this = elsif.prettyprint(this);
+            this = elsif.prettyprint(this << " ");
         }
         this << &self.els
     }
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Expression, InterpolatedString, InterpolatedStringPiece, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+use super::string_literal::{escape_string, pick_quote, process_string_escapes};
+
+impl Derive for InterpolatedString {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::interp_str_literal);
+        let loc = From::from(&p.as_span());
+        let pieces = p
+            .into_inner()
+            .map(|piece| match piece.as_rule() {
+                Rule::interp_str_text_dbl_qt | Rule::interp_str_text_sgl_qt => {
+                    let text = piece.as_str().replace("{{", "{").replace("}}", "}");
+                    InterpolatedStringPiece::Text(process_string_escapes(&text))
+                }
+                Rule::interp_str_expr => {
+                    let expr = piece.into_inner().next().expect("need an expression");
+                    InterpolatedStringPiece::Expression(Expression::from_parse_tree(expr, source))
+                }
+                _ => panic!("unexpected interpolated string piece"),
+            })
+            .collect::<Vec<_>>();
+        Self {
+            loc: source.pointer(loc),
+            pieces,
+        }
+    }
+}
+
+impl InterpolatedString {
+    fn text(&self) -> String {
+        self.pieces
+            .iter()
+            .filter_map(|piece| match piece {
+                InterpolatedStringPiece::Text(text) => Some(text.as_str()),
+                InterpolatedStringPiece::Expression(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl PrettyPrintable for InterpolatedString {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        if let Some(text) = buffer.string_literal_source(&self.loc) {
+            return buffer.write_verbatim(&text);
+        }
+        let quote = pick_quote(&self.text());
+        let mut buffer = buffer << format!("f{quote}");
+        for piece in &self.pieces {
+            buffer = match piece {
+                InterpolatedStringPiece::Text(text) => {
+                    buffer
+                        << escape_string(text, quote)
+                            .replace("{", "{{")
+                            .replace("}", "}}")
+                }
+                InterpolatedStringPiece::Expression(expr) => buffer << "{" << expr << "}",
+            };
+        }
+        buffer << quote.to_string()
+    }
+}
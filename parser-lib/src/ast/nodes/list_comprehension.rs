@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        ComprehensionClause, Expression, ListComprehension, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for ListComprehension {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::list_comprehension);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let value = Expression::from_parse_tree(inner.next().expect("need value"), source);
+        let clause =
+            ComprehensionClause::from_parse_tree(inner.next().expect("need for clause"), source);
+        Self {
+            loc: source.pointer(loc),
+            value: Box::new(value),
+            clause,
+        }
+    }
+}
+
+impl PrettyPrintable for ListComprehension {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "[" << self.value.as_ref() << " " << &self.clause << "]"
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        IdentifierList, ListDestructure,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for ListDestructure {
+    gen_from_components!(list_destructure; names: IdentifierList);
+}
+
+impl PrettyPrintable for ListDestructure {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "[" << &self.names << "]"
+    }
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Expression, ListElement, SpreadExpression,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_options,
+};
+
+impl Derive for ListElement {
+    gen_from_options!(
+        list_element;
+        (spread_expr, SpreadExpression),
+        (expression, Expression),
+    );
+}
+
+impl PrettyPrintable for ListElement {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        match self {
+            Self::Expression(e) => e.prettyprint(buffer),
+            Self::SpreadExpression(s) => s.prettyprint(buffer),
+        }
+    }
+}
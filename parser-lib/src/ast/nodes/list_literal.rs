@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     ast::{
-        ExpressionList, ListLiteral, SourceBuffer,
+        ElementList, ListLiteral, SourceBuffer,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -14,9 +14,9 @@ impl Derive for ListLiteral {
         let loc = From::from(&p.as_span());
         let mut inner = p.into_inner();
         let items = if let Some(next) = inner.next() {
-            ExpressionList::from_parse_tree(next, source)
+            ElementList::from_parse_tree(next, source)
         } else {
-            ExpressionList::empty(source.pointer(loc))
+            ElementList::empty(source.pointer(loc))
         };
         Self {
             loc: source.pointer(loc),
@@ -27,6 +27,12 @@ impl Derive for ListLiteral {
 
 impl PrettyPrintable for ListLiteral {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "[" << &self.items << "]"
+        let items = self
+            .items
+            .elements
+            .iter()
+            .map(|e| (e as &dyn PrettyPrintable, e.loc()))
+            .collect::<Vec<_>>();
+        buffer.write_wrapped_list(&items, "[", "]")
     }
 }
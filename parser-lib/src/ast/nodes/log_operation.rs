@@ -52,7 +52,7 @@ impl PrettyPrintable for LogOperation {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         let mut this = self.left.prettyprint(buffer);
         for (op, atom) in &self.right {
-            this = this << op << atom;
+            this = this << " " << op << " " << atom;
         }
         this
     }
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        ComprehensionClause, Expression, MapComprehension, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for MapComprehension {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::map_comprehension);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let key = Expression::from_parse_tree(inner.next().expect("need key"), source);
+        let value = Expression::from_parse_tree(inner.next().expect("need value"), source);
+        let clause =
+            ComprehensionClause::from_parse_tree(inner.next().expect("need for clause"), source);
+        Self {
+            loc: source.pointer(loc),
+            key: Box::new(key),
+            value: Box::new(value),
+            clause,
+        }
+    }
+}
+
+impl PrettyPrintable for MapComprehension {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer
+            << "{"
+            << self.key.as_ref()
+            << ": "
+            << self.value.as_ref()
+            << " "
+            << &self.clause
+            << "}"
+    }
+}
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Expression, MapLiteralEntry,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for MapLiteralEntry {
+    gen_from_components!(map_literal_entry; key: Expression, value: Expression);
+}
+
+impl PrettyPrintable for MapLiteralEntry {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << &self.key << ": " << &self.value
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        BlockExpression, Expression, MatchExpression, MatchExpressionRule, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for MatchExpression {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::match_expr);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let expr = Expression::from_parse_tree(inner.next().expect("need expression"), source);
+        let mut rules = vec![];
+        let mut els = None;
+        for next in inner {
+            match next.as_rule() {
+                Rule::match_expr_rule => {
+                    rules.push(MatchExpressionRule::from_parse_tree(next, source));
+                }
+                Rule::block_expr => {
+                    els = Some(BlockExpression::from_parse_tree(next, source));
+                }
+                _ => panic!("invalid rule entry"),
+            }
+        }
+        Self {
+            loc: source.pointer(loc),
+            expr: Box::new(expr),
+            rules,
+            els,
+        }
+    }
+}
+
+impl PrettyPrintable for MatchExpression {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let buffer = (buffer << "match " << self.expr.as_ref() << " ").write_block(
+            &self.rules,
+            |rule| &rule.loc,
+            |_| ",",
+            self.expr.loc().location.stop,
+        );
+        if let Some(e) = &self.els {
+            buffer << " else " << e
+        } else {
+            buffer
+        }
+    }
+}
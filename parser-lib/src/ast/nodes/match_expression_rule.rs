@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        BlockExpression, Expression, MatchExpressionRule, MatchPattern, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for MatchExpressionRule {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::match_expr_rule);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let mut patterns = vec![];
+        let mut guard = None;
+        let then = {
+            loop {
+                let next = inner.next().expect("need rules");
+                match next.as_rule() {
+                    Rule::match_pattern => {
+                        patterns.push(MatchPattern::from_parse_tree(next, source));
+                    }
+                    Rule::match_guard => {
+                        let expr = next.into_inner().next().expect("need guard expression");
+                        guard = Some(Expression::from_parse_tree(expr, source));
+                    }
+                    Rule::block_expr => {
+                        break BlockExpression::from_parse_tree(next, source);
+                    }
+                    Rule::expression => {
+                        break BlockExpression::from(&Expression::from_parse_tree(next, source));
+                    }
+                    _ => panic!("invalid rule entry"),
+                }
+            }
+        };
+        Self {
+            loc: source.pointer(loc),
+            patterns,
+            guard,
+            then,
+        }
+    }
+}
+
+impl PrettyPrintable for MatchExpressionRule {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let buffer = buffer.write_separated_list(&self.patterns, " and ");
+        let buffer = if let Some(guard) = &self.guard {
+            buffer << " if " << guard
+        } else {
+            buffer
+        };
+        // "=> expr" is stored as a block with no statements that starts where its value does
+        if self.then.body.is_empty()
+            && self.then.loc.location.start == self.then.value.loc().location.start
+        {
+            buffer << " => " << self.then.value.as_ref()
+        } else {
+            buffer << " => " << &self.then
+        }
+    }
+}
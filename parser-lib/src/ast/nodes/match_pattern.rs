@@ -1,20 +1,70 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     ast::{
-        MatchPattern, MatchPatternComp, MatchPatternEnumCase, MatchPatternRel,
+        MatchPattern, MatchPatternAlternatives, MatchPatternBind, MatchPatternComp,
+        MatchPatternEnumCase, MatchPatternIsaBind, MatchPatternList, MatchPatternRel, SourceBuffer,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
-    gen_from_options,
+    grammar::Rule,
 };
 
+fn simple_pattern_from_parse_tree(
+    p: pest::iterators::Pair<'_, Rule>,
+    source: &SourceBuffer,
+) -> MatchPattern {
+    assert!(p.as_rule() == Rule::match_pattern_simple);
+    let next = p.into_inner().next().expect("need pattern");
+    match next.as_rule() {
+        Rule::match_pattern_comp => {
+            MatchPattern::MatchPatternComp(MatchPatternComp::from_parse_tree(next, source))
+        }
+        Rule::match_pattern_enum_case => {
+            MatchPattern::MatchPatternEnumCase(MatchPatternEnumCase::from_parse_tree(next, source))
+        }
+        Rule::match_pattern_isa_bind => {
+            MatchPattern::MatchPatternIsaBind(MatchPatternIsaBind::from_parse_tree(next, source))
+        }
+        Rule::match_pattern_list => {
+            MatchPattern::MatchPatternList(MatchPatternList::from_parse_tree(next, source))
+        }
+        Rule::match_pattern_rel => {
+            MatchPattern::MatchPatternRel(MatchPatternRel::from_parse_tree(next, source))
+        }
+        _ => panic!("invalid node"),
+    }
+}
+
 impl Derive for MatchPattern {
-    gen_from_options!(
-        match_pattern;
-        (match_pattern_comp, MatchPatternComp),
-        (match_pattern_enum_case, MatchPatternEnumCase),
-        (match_pattern_rel, MatchPatternRel),
-    );
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        match p.as_rule() {
+            Rule::match_sub_pattern => {
+                let next = p.into_inner().next().expect("need pattern");
+                match next.as_rule() {
+                    Rule::match_pattern_bind => {
+                        Self::MatchPatternBind(MatchPatternBind::from_parse_tree(next, source))
+                    }
+                    _ => Self::from_parse_tree(next, source),
+                }
+            }
+            Rule::match_pattern => {
+                let loc = From::from(&p.as_span());
+                let mut alternatives = p
+                    .into_inner()
+                    .map(|i| simple_pattern_from_parse_tree(i, source))
+                    .collect::<Vec<_>>();
+                if alternatives.len() == 1 {
+                    alternatives.remove(0)
+                } else {
+                    Self::MatchPatternAlternatives(MatchPatternAlternatives {
+                        loc: source.pointer(loc),
+                        alternatives,
+                    })
+                }
+            }
+            _ => panic!("invalid node"),
+        }
+    }
 }
 
 impl PrettyPrintable for MatchPattern {
@@ -23,6 +73,10 @@ impl PrettyPrintable for MatchPattern {
             Self::MatchPatternComp(e) => e.prettyprint(buffer),
             Self::MatchPatternRel(e) => e.prettyprint(buffer),
             Self::MatchPatternEnumCase(e) => e.prettyprint(buffer),
+            Self::MatchPatternBind(b) => b.prettyprint(buffer),
+            Self::MatchPatternIsaBind(i) => i.prettyprint(buffer),
+            Self::MatchPatternList(l) => l.prettyprint(buffer),
+            Self::MatchPatternAlternatives(a) => a.prettyprint(buffer),
         }
     }
 }
@@ -0,0 +1,11 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::ast::{
+    MatchPatternAlternatives,
+    prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+};
+
+impl PrettyPrintable for MatchPatternAlternatives {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer.write_separated_list(&self.alternatives, " or ")
+    }
+}
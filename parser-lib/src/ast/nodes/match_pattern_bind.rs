@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        DeclarationId, MatchPatternBind,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for MatchPatternBind {
+    gen_from_components!(match_pattern_bind; id: DeclarationId);
+}
+
+impl PrettyPrintable for MatchPatternBind {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << &self.id
+    }
+}
@@ -14,6 +14,6 @@ impl Derive for MatchPatternComp {
 
 impl PrettyPrintable for MatchPatternComp {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << &self.op << &self.expr
+        buffer << &self.op << " " << &self.expr
     }
 }
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Expression, Identifier, MatchPatternIsaBind,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for MatchPatternIsaBind {
+    gen_from_components!(match_pattern_isa_bind; ty: Expression, name: Identifier);
+}
+
+impl PrettyPrintable for MatchPatternIsaBind {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "isa " << &self.ty << " as " << &self.name
+    }
+}
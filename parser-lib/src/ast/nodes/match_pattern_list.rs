@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Identifier, MatchPattern, MatchPatternList, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for MatchPatternList {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::match_pattern_list);
+        let loc = From::from(&p.as_span());
+        let mut items = vec![];
+        let mut rest = None;
+        for next in p.into_inner() {
+            match next.as_rule() {
+                Rule::match_sub_pattern => {
+                    items.push(MatchPattern::from_parse_tree(next, source));
+                }
+                Rule::match_pattern_list_rest => {
+                    let id = next.into_inner().next().expect("need identifier");
+                    rest = Some(Identifier::from_parse_tree(id, source));
+                }
+                _ => panic!("invalid list pattern entry"),
+            }
+        }
+        Self {
+            loc: source.pointer(loc),
+            items,
+            rest,
+        }
+    }
+}
+
+impl PrettyPrintable for MatchPatternList {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let buffer = (buffer << "[").write_separated_list(&self.items, ", ");
+        let buffer = match &self.rest {
+            Some(rest) if self.items.is_empty() => buffer << "..." << rest,
+            Some(rest) => buffer << ", ..." << rest,
+            None => buffer,
+        };
+        buffer << "]"
+    }
+}
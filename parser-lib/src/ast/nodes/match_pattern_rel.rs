@@ -14,6 +14,6 @@ impl Derive for MatchPatternRel {
 
 impl PrettyPrintable for MatchPatternRel {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << &self.op << &self.expr
+        buffer << &self.op << " " << &self.expr
     }
 }
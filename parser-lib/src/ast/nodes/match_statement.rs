@@ -45,12 +45,11 @@ impl Derive for MatchStatement {
 
 impl PrettyPrintable for MatchStatement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        let buffer =
-            (buffer << "match " << &self.expr).write_indented_list(&self.rules, "{\n", "\n", "");
-        if let Some(e) = &self.els {
-            buffer << "\n else " << e << "\n}"
-        } else {
-            buffer << "\n}"
-        }
+        (buffer << "match " << &self.expr << " ").write_block(
+            &self.rules,
+            |rule| &rule.loc,
+            |_| ",",
+            self.expr.loc().location.stop,
+        ) << &self.els
     }
 }
@@ -23,8 +23,8 @@ impl PrettyPrintable for MethodAccess {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         buffer
             << match self {
-                MethodAccess::Instance => "instance",
-                MethodAccess::Type => "type",
+                MethodAccess::Instance => "",
+                MethodAccess::Type => "type ",
             }
     }
 }
@@ -40,6 +40,6 @@ impl Derive for MethodDecl {
 
 impl PrettyPrintable for MethodDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << &self.access << " func " << &self.name << " (" << &self.args << ") " << &self.body
+        buffer << &self.access << "func " << &self.name << "(" << &self.args << ") " << &self.body
     }
 }
@@ -29,6 +29,11 @@ impl Derive for MixinDecl {
 
 impl PrettyPrintable for MixinDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        (buffer << "mixin " << &self.name).write_indented_list(&self.body, "{\n", "\n", "\n}")
+        (buffer << "mixin " << &self.name << " ").write_block(
+            &self.body,
+            StructEntry::loc,
+            |_| "",
+            self.name.loc.location.stop,
+        )
     }
 }
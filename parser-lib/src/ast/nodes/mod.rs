@@ -6,12 +6,19 @@ mod argument_decl;
 mod argument_list;
 mod assert_statement;
 mod assign_statement;
+mod block_expression;
 mod break_statement;
+mod catch_clause;
+mod coalesce_expression;
 mod code_block;
 mod comp_operation;
 mod comp_symbol;
+mod comprehension_clause;
 mod continue_statement;
 mod declaration_id;
+mod defer_statement;
+mod destructure_pattern;
+mod element_list;
 mod else_piece;
 mod elsif_piece;
 mod enum_case_decl;
@@ -22,12 +29,15 @@ mod expression_list;
 mod expression_statement;
 mod extension_decl;
 mod float_literal;
+mod for_binding;
 mod for_statement;
 mod function_body;
 mod function_decl;
 mod identifier;
 mod identifier_list;
 mod if_cond_piece;
+mod if_expression;
+mod if_expression_piece;
 mod if_piece;
 mod if_statement;
 mod import_from_statement;
@@ -35,14 +45,27 @@ mod import_path;
 mod import_statement;
 mod import_target;
 mod int_literal;
+mod interpolated_string;
 mod lambda_body;
 mod lambda_function;
+mod list_comprehension;
+mod list_destructure;
+mod list_element;
 mod list_literal;
 mod log_operation;
 mod log_symbol;
+mod map_comprehension;
+mod map_literal;
+mod map_literal_entry;
+mod match_expression;
+mod match_expression_rule;
 mod match_pattern;
+mod match_pattern_alternatives;
+mod match_pattern_bind;
 mod match_pattern_comp;
 mod match_pattern_enum_case;
+mod match_pattern_isa_bind;
+mod match_pattern_list;
 mod match_pattern_rel;
 mod match_rule;
 mod match_statement;
@@ -54,6 +77,8 @@ mod module_flag;
 mod module_flags;
 mod mul_operation;
 mod mul_symbol;
+mod named_argument;
+mod object_destructure;
 mod operator_decl;
 mod operator_symbol;
 mod paren_expression;
@@ -68,15 +93,18 @@ mod postfix_term_field_write;
 mod postfix_term_index;
 mod postfix_term_index_write;
 mod postfix_term_object_write;
+mod postfix_term_optional_attribute;
 mod postfix_term_try_protocol;
 mod postfix_term_write;
 mod postfix_term_write_list;
 mod primary;
+mod range_expression;
 mod rel_operation;
 mod rel_symbol;
 mod return_statement;
 mod shift_operation;
 mod shift_symbol;
+mod spread_expression;
 mod statement;
 mod string_literal;
 mod struct_decl;
@@ -89,5 +117,7 @@ mod unary_operation;
 mod unary_symbol;
 mod val_decl_entry;
 mod val_decl_statement;
+mod val_destructure_statement;
 mod while_statement;
 mod write_op_eq_statement;
+mod yield_statement;
@@ -8,6 +8,8 @@ use crate::{
     grammar::Rule,
 };
 
+use super::string_literal::{escape_string, pick_quote};
+
 impl Derive for ModuleFlag {
     fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
         assert!(p.as_rule() == Rule::module_flag);
@@ -19,6 +21,7 @@ impl Derive for ModuleFlag {
                 let path = StringLiteral::from_parse_tree(inner.next().expect("need path"), source);
                 Self::UsesDylib(path.value)
             }
+            "checked_arithmetic" => Self::CheckedArithmetic,
             _ => panic!("unknown module flag"),
         }
     }
@@ -28,7 +31,15 @@ impl PrettyPrintable for ModuleFlag {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         match self {
             ModuleFlag::NoStandardLibrary => buffer << "flag: no_std;",
-            ModuleFlag::UsesDylib(dylib) => buffer << "flag: uses_dylib(" << dylib.as_str() << ");",
+            ModuleFlag::UsesDylib(dylib) => {
+                let quote = pick_quote(dylib);
+                buffer
+                    << format!(
+                        "flag: uses_dylib({quote}{}{quote});",
+                        escape_string(dylib, quote)
+                    )
+            }
+            ModuleFlag::CheckedArithmetic => buffer << "flag: checked_arithmetic;",
         }
     }
 }
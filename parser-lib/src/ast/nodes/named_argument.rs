@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Expression, Identifier, NamedArgument,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for NamedArgument {
+    gen_from_components!(named_arg; name: Identifier, value: Expression);
+}
+
+impl PrettyPrintable for NamedArgument {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << &self.name << ": " << &self.value
+    }
+}
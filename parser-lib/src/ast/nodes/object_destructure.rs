@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        IdentifierList, ObjectDestructure,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for ObjectDestructure {
+    gen_from_components!(object_destructure; names: IdentifierList);
+}
+
+impl PrettyPrintable for ObjectDestructure {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "{" << &self.names << "}"
+    }
+}
@@ -49,7 +49,7 @@ impl PrettyPrintable for OperatorDecl {
                 "operator "
             }
             << &self.symbol
-            << " ("
+            << "("
             << &self.args
             << ") "
             << &self.body
@@ -34,8 +34,13 @@ impl Derive for ParsedModule {
 
 impl PrettyPrintable for ParsedModule {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        self.flags
-            .prettyprint(buffer)
-            .write_separated_list(&self.entries, "\n")
+        let mut buffer = buffer.write_header_trivia();
+        for flag in &self.flags.flags {
+            buffer = (buffer << flag).write("\n");
+        }
+        if !self.flags.flags.is_empty() && !self.entries.is_empty() {
+            buffer = buffer.write("\n");
+        }
+        buffer.write_module_entries(&self.entries, TopLevelEntry::loc)
     }
 }
@@ -10,13 +10,14 @@ use crate::{
 
 use crate::ast::{
     PostfixTermAttribute, PostfixTermCall, PostfixTermEnumCase, PostfixTermIndex,
-    PostfixTermObjectWrite, PostfixTermTryProtocol,
+    PostfixTermObjectWrite, PostfixTermOptionalAttribute, PostfixTermTryProtocol,
 };
 
 impl Derive for PostfixTerm {
     gen_from_options!(
         postfix_term;
         (postfix_term_attrib, PostfixTermAttribute),
+        (postfix_term_opt_attrib, PostfixTermOptionalAttribute),
         (postfix_term_index, PostfixTermIndex),
         (postfix_term_call, PostfixTermCall),
         (postfix_term_enum_case, PostfixTermEnumCase),
@@ -29,6 +30,7 @@ impl PrettyPrintable for PostfixTerm {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         match self {
             Self::PostfixTermAttribute(a) => a.prettyprint(buffer),
+            Self::PostfixTermOptionalAttribute(a) => a.prettyprint(buffer),
             Self::PostfixTermIndex(i) => i.prettyprint(buffer),
             Self::PostfixTermCall(c) => c.prettyprint(buffer),
             Self::PostfixTermEnumCase(c) => c.prettyprint(buffer),
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     ast::{
-        ExpressionList, PostfixTermCall, SourceBuffer,
+        ElementList, ListElement, NamedArgument, PostfixTermCall, SourceBuffer,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -11,23 +11,34 @@ use crate::{
 impl Derive for PostfixTermCall {
     fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
         assert!(p.as_rule() == Rule::postfix_term_call);
-        let loc = From::from(&p.as_span());
-        let mut inner = p.into_inner();
-        let args = inner.find(|val| val.as_rule() == Rule::expr_list);
-        let args = if let Some(el) = args {
-            ExpressionList::from_parse_tree(el, source)
-        } else {
-            ExpressionList::empty(source.pointer(loc))
-        };
-        Self {
-            loc: source.pointer(loc),
-            args,
+        let loc = source.pointer(From::from(&p.as_span()));
+        let mut args = ElementList::empty(loc.clone());
+        let mut named = vec![];
+        for arg in p.into_inner() {
+            if arg.as_rule() == Rule::named_arg {
+                named.push(NamedArgument::from_parse_tree(arg, source));
+            } else {
+                args.elements
+                    .push(ListElement::from_parse_tree(arg, source));
+            }
         }
+        Self { loc, args, named }
     }
 }
 
 impl PrettyPrintable for PostfixTermCall {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "(" << &self.args << ")"
+        let args = self
+            .args
+            .elements
+            .iter()
+            .map(|e| (e as &dyn PrettyPrintable, e.loc()))
+            .chain(
+                self.named
+                    .iter()
+                    .map(|n| (n as &dyn PrettyPrintable, &n.loc)),
+            )
+            .collect::<Vec<_>>();
+        buffer.write_wrapped_list(&args, "(", ")")
     }
 }
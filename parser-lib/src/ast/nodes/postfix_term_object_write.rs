@@ -14,6 +14,12 @@ impl Derive for PostfixTermObjectWrite {
 
 impl PrettyPrintable for PostfixTermObjectWrite {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "{" << &self.terms << "}"
+        let terms = self
+            .terms
+            .terms
+            .iter()
+            .map(|t| (t as &dyn PrettyPrintable, t.loc()))
+            .collect::<Vec<_>>();
+        buffer.write_wrapped_list(&terms, " {", "}")
     }
 }
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        Identifier, PostfixTermOptionalAttribute,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for PostfixTermOptionalAttribute {
+    gen_from_components!(postfix_term_opt_attrib; id: Identifier);
+}
+
+impl PrettyPrintable for PostfixTermOptionalAttribute {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "?." << &self.id
+    }
+}
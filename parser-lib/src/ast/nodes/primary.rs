@@ -10,8 +10,14 @@ use crate::{
 
 use crate::ast::FloatLiteral;
 use crate::ast::Identifier;
+use crate::ast::IfExpression;
 use crate::ast::IntLiteral;
+use crate::ast::InterpolatedString;
+use crate::ast::ListComprehension;
 use crate::ast::ListLiteral;
+use crate::ast::MapComprehension;
+use crate::ast::MapLiteral;
+use crate::ast::MatchExpression;
 use crate::ast::ParenExpression;
 use crate::ast::StringLiteral;
 
@@ -22,8 +28,14 @@ impl Derive for Primary {
         (fp_literal, FloatLiteral),
         (identifier, Identifier),
         (list_literal, ListLiteral),
+        (list_comprehension, ListComprehension),
+        (map_literal, MapLiteral),
+        (map_comprehension, MapComprehension),
         (str_literal, StringLiteral),
-        (paren_expr, ParenExpression)
+        (interp_str_literal, InterpolatedString),
+        (paren_expr, ParenExpression),
+        (if_expr, IfExpression),
+        (match_expr, MatchExpression)
     );
 }
 
@@ -34,8 +46,14 @@ impl PrettyPrintable for Primary {
             Self::FloatLiteral(fp) => fp.prettyprint(buffer),
             Self::Identifier(id) => id.prettyprint(buffer),
             Self::ListLiteral(ll) => ll.prettyprint(buffer),
+            Self::ListComprehension(lc) => lc.prettyprint(buffer),
+            Self::MapLiteral(ml) => ml.prettyprint(buffer),
+            Self::MapComprehension(mc) => mc.prettyprint(buffer),
             Self::StringLiteral(sl) => sl.prettyprint(buffer),
+            Self::InterpolatedString(is) => is.prettyprint(buffer),
             Self::ParenExpression(pe) => pe.prettyprint(buffer),
+            Self::IfExpression(ie) => ie.prettyprint(buffer),
+            Self::MatchExpression(me) => me.prettyprint(buffer),
         }
     }
 }
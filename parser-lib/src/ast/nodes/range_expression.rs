@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        LogOperation, RangeExpression, SourceBuffer,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    grammar::Rule,
+};
+
+impl Derive for RangeExpression {
+    fn from_parse_tree(p: pest::iterators::Pair<'_, Rule>, source: &SourceBuffer) -> Self {
+        assert!(p.as_rule() == Rule::range_expr);
+        let loc = From::from(&p.as_span());
+        let mut inner = p.into_inner();
+        let from = LogOperation::from_parse_tree(inner.next().unwrap(), source);
+        let inclusive = inner.next().unwrap().as_str() == "..=";
+        let to = LogOperation::from_parse_tree(inner.next().unwrap(), source);
+        let step = inner
+            .nth(1)
+            .map(|s| Box::new(LogOperation::from_parse_tree(s, source)));
+        RangeExpression {
+            loc: source.pointer(loc),
+            from: Box::new(from),
+            to: Box::new(to),
+            inclusive,
+            step,
+        }
+    }
+}
+
+impl PrettyPrintable for RangeExpression {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        let buffer = self.from.prettyprint(buffer)
+            << if self.inclusive { "..=" } else { ".." }
+            << self.to.as_ref();
+        match &self.step {
+            Some(step) => buffer << " step " << step.as_ref(),
+            None => buffer,
+        }
+    }
+}
@@ -39,7 +39,7 @@ impl PrettyPrintable for ShiftOperation {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         let this = self.left.prettyprint(buffer);
         if let Some(rhs) = &self.right {
-            this << &rhs.0 << &rhs.1
+            this << " " << &rhs.0 << " " << &rhs.1
         } else {
             this
         }
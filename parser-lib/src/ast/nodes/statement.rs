@@ -1,10 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     ast::{
-        AssertStatement, AssignStatement, BreakStatement, CodeBlock, ContinueStatement, EnumDecl,
-        ExpressionStatement, ForStatement, FunctionDecl, IfStatement, MatchStatement,
-        ReturnStatement, Statement, StructDecl, ThrowStatement, TryBlock, ValDeclStatement,
-        WhileStatement, WriteOpEqStatement,
+        AssertStatement, AssignStatement, BreakStatement, CodeBlock, ContinueStatement,
+        DeferStatement, EnumDecl, ExpressionStatement, ForStatement, FunctionDecl, IfStatement,
+        MatchStatement, ReturnStatement, Statement, StructDecl, ThrowStatement, TryBlock,
+        ValDeclStatement, ValDestructureStatement, WhileStatement, WriteOpEqStatement,
+        YieldStatement,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -27,8 +28,11 @@ impl Derive for Statement {
         (try_block, TryBlock),
         (val_add_eq_write, WriteOpEqStatement),
         (val_decl_stmt, ValDeclStatement),
+        (val_destructure_stmt, ValDestructureStatement),
         (val_write_stmt, AssignStatement),
         (while_stmt, WhileStatement),
+        (yield_stmt, YieldStatement),
+        (defer_stmt, DeferStatement),
         (struct_decl, StructDecl),
         (enum_decl, EnumDecl),
         (function_decl, FunctionDecl),
@@ -39,6 +43,7 @@ impl PrettyPrintable for Statement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         match self {
             Self::ValDeclStatement(v) => v.prettyprint(buffer),
+            Self::ValDestructureStatement(v) => v.prettyprint(buffer),
             Self::AssignStatement(a) => a.prettyprint(buffer),
             Self::WriteOpEqStatement(w) => w.prettyprint(buffer),
             Self::IfStatement(i) => i.prettyprint(buffer),
@@ -48,6 +53,8 @@ impl PrettyPrintable for Statement {
             Self::CodeBlock(c) => c.prettyprint(buffer),
             Self::ReturnStatement(r) => r.prettyprint(buffer),
             Self::ThrowStatement(t) => t.prettyprint(buffer),
+            Self::YieldStatement(y) => y.prettyprint(buffer),
+            Self::DeferStatement(d) => d.prettyprint(buffer),
             Self::TryBlock(t) => t.prettyprint(buffer),
             Self::AssertStatement(a) => a.prettyprint(buffer),
             Self::ExpressionStatement(e) => e.prettyprint(buffer),
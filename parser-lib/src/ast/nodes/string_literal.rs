@@ -11,71 +11,96 @@ use crate::{
 // TODO: process string literals in the compiler code, not the parser
 // the parser has no good way to report an error, so complete the processing
 // in the compiler where we can fail on an invalid escape sequence
-fn process_string_literal(s: &str) -> String {
-    fn process_string_escapes(s: &str) -> String {
-        let mut result = String::new();
-        let mut chars = s.chars().peekable();
+pub(super) fn process_string_escapes(s: &str) -> String {
+    let mut result = String::new();
+    let mut chars = s.chars().peekable();
 
-        while let Some(c) = chars.next() {
-            if c == '\\' {
-                if let Some(next) = chars.peek() {
-                    match next {
-                        'n' => {
-                            result.push('\n');
-                            chars.next();
-                        }
-                        't' => {
-                            result.push('\t');
-                            chars.next();
-                        }
-                        '\\' => {
-                            result.push('\\');
-                            chars.next();
-                        }
-                        'X' | 'x' => {
-                            let _ = chars.next();
-                            if let (Some(high), Some(low)) = (chars.next(), chars.next())
-                                && let (Some(high), Some(low)) =
-                                    (high.to_digit(16), low.to_digit(16))
-                            {
-                                result.push((high << 4 | low) as u8 as char);
-                            }
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.peek() {
+                match next {
+                    'n' => {
+                        result.push('\n');
+                        chars.next();
+                    }
+                    't' => {
+                        result.push('\t');
+                        chars.next();
+                    }
+                    '\\' => {
+                        result.push('\\');
+                        chars.next();
+                    }
+                    'X' | 'x' => {
+                        let _ = chars.next();
+                        if let (Some(high), Some(low)) = (chars.next(), chars.next())
+                            && let (Some(high), Some(low)) = (high.to_digit(16), low.to_digit(16))
+                        {
+                            result.push((high << 4 | low) as u8 as char);
                         }
-                        'U' | 'u' => {
+                    }
+                    'U' | 'u' => {
+                        let _ = chars.next();
+                        if chars.peek() == Some(&'{') {
                             let _ = chars.next();
-                            if chars.peek() == Some(&'{') {
-                                let _ = chars.next();
-                                let mut hex_digits = String::new();
-                                while let Some(&next) = chars.peek() {
-                                    if next == '}' {
-                                        let _ = chars.next();
-                                        break;
-                                    } else {
-                                        hex_digits.push(chars.next().unwrap());
-                                    }
-                                }
-                                if let Ok(codepoint) = u32::from_str_radix(&hex_digits, 16)
-                                    && let Some(chr) = char::from_u32(codepoint)
-                                {
-                                    result.push(chr);
+                            let mut hex_digits = String::new();
+                            while let Some(&next) = chars.peek() {
+                                if next == '}' {
+                                    let _ = chars.next();
+                                    break;
+                                } else {
+                                    hex_digits.push(chars.next().unwrap());
                                 }
                             }
+                            if let Ok(codepoint) = u32::from_str_radix(&hex_digits, 16)
+                                && let Some(chr) = char::from_u32(codepoint)
+                            {
+                                result.push(chr);
+                            }
                         }
-                        _ => {
-                            result.push(c);
-                        }
                     }
-                } else {
-                    result.push(c);
+                    _ => {
+                        result.push(c);
+                    }
                 }
             } else {
                 result.push(c);
             }
+        } else {
+            result.push(c);
         }
+    }
 
-        result
+    result
+}
+
+// prefers double quotes, unless the string contains some and no single quotes
+pub(super) fn pick_quote(s: &str) -> char {
+    if s.contains('"') && !s.contains('\'') {
+        '\''
+    } else {
+        '"'
     }
+}
 
+// the inverse of process_string_escapes, for a string between the given quotes
+pub(super) fn escape_string(s: &str, quote: char) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            c if c == quote || c.is_ascii_control() => {
+                result.push_str(&format!("\\x{:02x}", c as u32));
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn process_string_literal(s: &str) -> String {
     let s = &s[1..s.len() - 1];
     process_string_escapes(s)
 }
@@ -93,6 +118,13 @@ impl Derive for StringLiteral {
 
 impl PrettyPrintable for StringLiteral {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer.write(&self.value)
+        if let Some(text) = buffer.string_literal_source(&self.loc) {
+            return buffer.write_verbatim(&text);
+        }
+        let quote = pick_quote(&self.value);
+        buffer.write(&format!(
+            "{quote}{}{quote}",
+            escape_string(&self.value, quote)
+        ))
     }
 }
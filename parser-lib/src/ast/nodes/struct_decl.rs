@@ -29,6 +29,11 @@ impl Derive for StructDecl {
 
 impl PrettyPrintable for StructDecl {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        (buffer << "struct " << &self.name).write_indented_list(&self.body, "{\n", "\n", "\n}")
+        (buffer << "struct " << &self.name << " ").write_block(
+            &self.body,
+            StructEntry::loc,
+            |_| "",
+            self.name.loc.location.stop,
+        )
     }
 }
@@ -4,7 +4,7 @@ use crate::{
         AssertStatement, AssignStatement, CodeBlock, EnumDecl, ExpressionStatement, ExtensionDecl,
         ForStatement, FunctionDecl, IfStatement, ImportFromStatement, ImportStatement,
         MatchStatement, MixinDecl, StructDecl, TopLevelEntry, TryBlock, ValDeclStatement,
-        WhileStatement, WriteOpEqStatement,
+        ValDestructureStatement, WhileStatement, WriteOpEqStatement,
         derive::Derive,
         prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
     },
@@ -24,6 +24,7 @@ impl Derive for TopLevelEntry {
         (mixin_decl, MixinDecl),
         (struct_decl, StructDecl),
         (val_decl_stmt, ValDeclStatement),
+        (val_destructure_stmt, ValDestructureStatement),
         (val_write_stmt, AssignStatement),
         (if_stmt, IfStatement),
         (match_stmt, MatchStatement),
@@ -39,6 +40,7 @@ impl PrettyPrintable for TopLevelEntry {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
         match self {
             Self::ValDeclStatement(v) => v.prettyprint(buffer),
+            Self::ValDestructureStatement(v) => v.prettyprint(buffer),
             Self::WriteOpEqStatement(w) => w.prettyprint(buffer),
             Self::AssignStatement(a) => a.prettyprint(buffer),
             Self::FunctionDecl(f) => f.prettyprint(buffer),
@@ -0,0 +1,19 @@
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    ast::{
+        DestructurePattern, Expression, ValDestructureStatement,
+        derive::Derive,
+        prettyprint::{PrettyPrintable, printout_accumulator::PrintoutAccumulator},
+    },
+    gen_from_components,
+};
+
+impl Derive for ValDestructureStatement {
+    gen_from_components!(val_destructure_stmt; pattern: DestructurePattern, val: Expression);
+}
+
+impl PrettyPrintable for ValDestructureStatement {
+    fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
+        buffer << "val " << &self.pattern << " = " << &self.val << ";"
+    }
+}
@@ -33,6 +33,6 @@ impl Derive for WhileStatement {
 
 impl PrettyPrintable for WhileStatement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << "while " << &self.cond << &self.then
+        buffer << "while " << &self.cond << " " << &self.then << &self.els
     }
 }
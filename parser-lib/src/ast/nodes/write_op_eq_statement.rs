@@ -14,6 +14,6 @@ impl Derive for WriteOpEqStatement {
 
 impl PrettyPrintable for WriteOpEqStatement {
     fn prettyprint(&self, buffer: PrintoutAccumulator) -> PrintoutAccumulator {
-        buffer << &self.id << &self.op << &self.val << ";"
+        buffer << &self.id << " " << &self.op << " " << &self.val << ";"
     }
 }
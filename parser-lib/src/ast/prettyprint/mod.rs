@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod printout_accumulator;
+mod trivia;
 
 use crate::ast::prettyprint::printout_accumulator::PrintoutAccumulator;
 
@@ -1,13 +1,31 @@
 // SPDX-License-Identifier: Apache-2.0
-use crate::ast::prettyprint::PrettyPrintable;
+use crate::ast::{
+    Location, SourceBuffer, SourcePointer,
+    prettyprint::{PrettyPrintable, trivia::Trivia},
+};
+
+// lists that would make a line longer than this are split one item per line
+const MAX_LINE_WIDTH: usize = 100;
 
 #[derive(Default)]
 pub struct PrintoutAccumulator {
     buffer: String,
     depth: usize,
+    trivia: Option<Trivia>,
+    // set while measuring how long a value is when printed on one line
+    measuring: bool,
 }
 
 impl PrintoutAccumulator {
+    // an accumulator that prints the comments and blank lines of the source
+    // along with its AST, as needed to format source code
+    pub fn for_source(source: &SourceBuffer) -> Self {
+        Self {
+            trivia: Some(Trivia::new(source)),
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn indent_more(mut self) -> Self {
         self.depth += 1;
         self
@@ -20,38 +38,351 @@ impl PrintoutAccumulator {
         self
     }
 
+    // indentation is only written before the first character of a line,
+    // so that blank lines do not end up with trailing whitespace
     pub(crate) fn write(mut self, text: &str) -> Self {
-        if self.depth == 0 || text.find('\n').is_none() {
-            self.buffer.push_str(text);
+        for ch in text.chars() {
+            if ch != '\n' && self.buffer.ends_with('\n') {
+                self.buffer.push_str(&" ".repeat(self.depth * 4));
+            }
+            self.buffer.push(ch);
+        }
+        self
+    }
+
+    // the source code of a string literal when formatting source code, so that
+    // it is written back with the same quotes and escape sequences
+    pub(crate) fn string_literal_source(&self, loc: &SourcePointer) -> Option<String> {
+        let text = self.trivia.as_ref()?.text(&loc.location);
+        let quoted = text.strip_prefix('f').unwrap_or(text);
+        let quote = quoted.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        (quoted.len() >= 2 && quoted.ends_with(quote)).then(|| text.to_owned())
+    }
+
+    // writes text as is, without indenting the lines after the first
+    pub(crate) fn write_verbatim(mut self, text: &str) -> Self {
+        if self.buffer.ends_with('\n') && !text.is_empty() {
+            self.buffer.push_str(&" ".repeat(self.depth * 4));
+        }
+        self.buffer.push_str(text);
+        self
+    }
+
+    fn column(&self) -> usize {
+        match self.buffer.rfind('\n') {
+            Some(idx) if idx + 1 == self.buffer.len() => self.depth * 4,
+            Some(idx) => self.buffer[idx + 1..].chars().count(),
+            None => self.buffer.chars().count(),
+        }
+    }
+
+    // prints value with every list on one line, without consuming any of the
+    // comments in the source; blocks of statements still span multiple lines
+    fn flat(&self, value: &dyn PrettyPrintable) -> String {
+        let buffer = Self {
+            trivia: self.trivia.clone(),
+            measuring: true,
+            ..Default::default()
+        };
+        value.prettyprint(buffer).value()
+    }
+
+    // true if value can be printed on the rest of the current line, and there
+    // are no comments before end that would have to go along with it
+    pub(crate) fn fits_on_line(&self, value: &dyn PrettyPrintable, end: usize) -> bool {
+        if self.measuring {
+            return true;
+        }
+        let flat = self.flat(value);
+        !flat.contains('\n')
+            && self.column() + flat.chars().count() + 4 <= MAX_LINE_WIDTH
+            && !self
+                .trivia
+                .as_ref()
+                .is_some_and(|t| t.has_comments_before(end))
+    }
+
+    fn write_comments(mut self, comments: Vec<(bool, String)>, mut first: bool) -> (Self, bool) {
+        for (blank, comment) in comments {
+            if blank && !first {
+                self = self.write("\n");
+            }
+            self = self.write(&comment).write("\n");
+            first = false;
+        }
+        (self, first)
+    }
+
+    // writes the comments in the source before pos, one per line, and keeps
+    // blank lines between them and pos; call at the start of a line
+    fn write_leading_trivia(mut self, pos: usize, at_block_start: bool) -> Self {
+        let Some(trivia) = self.trivia.as_mut() else {
+            return self;
+        };
+        let (comments, blank_before_pos) = trivia.take_leading(pos);
+        let (this, first) = self.write_comments(comments, at_block_start);
+        if blank_before_pos && !first {
+            this.write("\n")
         } else {
-            for ch in text.chars() {
-                self.buffer.push(ch);
-                if ch == '\n' {
-                    self.buffer.push_str(&" ".repeat(self.depth * 4));
-                }
+            this
+        }
+    }
+
+    // writes a comment that follows an entry ending at stop on the same line
+    fn write_trailing_trivia(mut self, stop: usize) -> Self {
+        let Some(trivia) = self.trivia.as_mut() else {
+            return self;
+        };
+        trivia.set_last(trivia.code_end(stop));
+        match trivia.take_trailing() {
+            Some(comment) => self.write(" ").write(&comment),
+            None => self,
+        }
+    }
+
+    // writes the remaining comments before the end of a block, each on its own line
+    fn write_closing_trivia(mut self, end: usize) -> Self {
+        let Some(trivia) = self.trivia.as_mut() else {
+            return self;
+        };
+        let (comments, _) = trivia.take_leading(end);
+        let mut first = true;
+        for (blank, comment) in comments {
+            self = self.write("\n");
+            if blank && !first {
+                self = self.write("\n");
             }
+            self = self.write(&comment);
+            first = false;
         }
         self
     }
 
-    pub(crate) fn write_indented_list<T: PrettyPrintable + std::fmt::Debug>(
+    // prints an entry followed by its suffix; an entry with comments inside it
+    // that its printout has no place for, such as one between the operands of
+    // an expression, is copied from the source instead, so they stay where they are
+    fn write_entry(self, entry: &dyn PrettyPrintable, loc: &SourcePointer, suffix: &str) -> Self {
+        let Some(trivia) = self.trivia.clone().filter(|_| !self.measuring) else {
+            return entry.prettyprint(self).write(suffix);
+        };
+        let end = trivia.code_end(loc.location.stop);
+        let len = self.buffer.len();
+        let mut this = entry.prettyprint(self);
+        if !this
+            .trivia
+            .as_ref()
+            .is_some_and(|t| t.has_comments_before(end))
+        {
+            return this.write(suffix);
+        }
+
+        this.buffer.truncate(len);
+        let mut trivia = trivia;
+        let source = trivia
+            .text(&Location {
+                start: loc.location.start,
+                stop: end,
+            })
+            .to_owned();
+        trivia.skip_to(end);
+        this.trivia = Some(trivia);
+        let source = source.strip_suffix(suffix).unwrap_or(&source);
+        this.write_verbatim(source).write(suffix)
+    }
+
+    // an empty statement is printed right after the entry before it, unless
+    // that ended in a comment
+    fn is_glued_to_previous(&self, loc: &SourcePointer, after_comment: bool) -> bool {
+        !after_comment
+            && self
+                .trivia
+                .as_ref()
+                .is_some_and(|t| t.is_empty_statement(&loc.location))
+    }
+
+    // writes entries one per line, each followed by its suffix and any comment
+    // on the same line, then the comments left before the end of the block
+    fn write_entries<'a>(
+        mut self,
+        entries: impl Iterator<Item = (&'a dyn PrettyPrintable, &'a SourcePointer, &'static str)>,
+    ) -> Self {
+        let mut after_comment = true;
+        for (i, (entry, loc, suffix)) in entries.enumerate() {
+            if !self.is_glued_to_previous(loc, after_comment) {
+                self = self
+                    .write("\n")
+                    .write_leading_trivia(loc.location.start, i == 0);
+            }
+            self = self.write_entry(entry, loc, suffix);
+            let len = self.buffer.len();
+            self = self.write_trailing_trivia(loc.location.stop);
+            after_comment = self.buffer.len() != len;
+        }
+        let end = self
+            .trivia
+            .as_ref()
+            .map_or(0, |t| t.next_code_position(t.last()));
+        self.write_closing_trivia(end)
+    }
+
+    // writes entries one per line, followed by their suffix, between braces;
+    // header_end is where the source code before the opening brace ends
+    pub(crate) fn write_block<T: PrettyPrintable>(
+        mut self,
+        entries: &[T],
+        loc: impl Fn(&T) -> &SourcePointer,
+        suffix: impl Fn(&T) -> &'static str,
+        header_end: usize,
+    ) -> Self {
+        let mut has_comments = false;
+        let mut trailing = None;
+        if let Some(trivia) = self.trivia.as_mut() {
+            let open = trivia.block_start(header_end);
+            let end =
+                trivia.next_code_position(entries.last().map_or(open, |e| loc(e).location.stop));
+            trivia.set_last(open);
+            has_comments = trivia.has_comments_before(end);
+            // a comment on the line of the opening brace stays there
+            if !self.measuring {
+                trailing = trivia.take_trailing();
+            }
+        }
+        if entries.is_empty() && !has_comments {
+            return self.write("{}");
+        }
+
+        let mut this = self.write("{");
+        if let Some(comment) = trailing {
+            this = this.write(" ").write(&comment);
+        }
+        this.indent_more()
+            .write_entries(
+                entries
+                    .iter()
+                    .map(|e| (e as &dyn PrettyPrintable, loc(e), suffix(e))),
+            )
+            .indent_less()
+            .write("\n}")
+    }
+
+    // writes the entries of a module one per line, followed by any comments after them
+    pub(crate) fn write_module_entries<T: PrettyPrintable>(
+        mut self,
+        entries: &[T],
+        loc: impl Fn(&T) -> &SourcePointer,
+    ) -> Self {
+        let mut first = self.buffer.is_empty() || self.buffer.ends_with("\n\n");
+        let mut after_comment = true;
+        for (i, entry) in entries.iter().enumerate() {
+            let entry_loc = loc(entry);
+            if !self.is_glued_to_previous(entry_loc, after_comment) {
+                if i > 0 {
+                    self = self.write("\n");
+                }
+                self = self.write_leading_trivia(entry_loc.location.start, first);
+            }
+            self = self.write_entry(entry, entry_loc, "");
+            let len = self.buffer.len();
+            self = self.write_trailing_trivia(entry_loc.location.stop);
+            after_comment = self.buffer.len() != len;
+            first = false;
+        }
+        if !entries.is_empty() {
+            self = self.write("\n");
+        }
+        match self.trivia.as_mut() {
+            Some(trivia) => {
+                let (comments, _) = trivia.take_leading(usize::MAX);
+                self.write_comments(comments, first).0
+            }
+            None => self,
+        }
+    }
+
+    // writes the comments before the first line of code of the source
+    pub(crate) fn write_header_trivia(self) -> Self {
+        match &self.trivia {
+            Some(trivia) => {
+                let pos = trivia.next_code_position(0);
+                self.write_leading_trivia(pos, true)
+            }
+            None => self,
+        }
+    }
+
+    // writes a comma separated list on one line if it fits, otherwise one item
+    // per line with a trailing comma; a list whose items are all on one line but
+    // the last, such as a call taking a lambda last, keeps to the opening line
+    pub(crate) fn write_wrapped_list(
         self,
-        values: &[T],
-        prefix: &str,
-        sep: &str,
-        suffix: &str,
+        values: &[(&dyn PrettyPrintable, &SourcePointer)],
+        open: &str,
+        close: &str,
     ) -> Self {
-        let mut this = self.indent_more().write(prefix);
-        let mut first = true;
-        for value in values {
-            if first {
-                first = false;
-            } else {
-                this = this.write(sep);
+        if self.measuring {
+            return self.write_inline_list(values, open, close);
+        }
+
+        let flat = values
+            .iter()
+            .map(|(v, _)| self.flat(*v))
+            .collect::<Vec<_>>();
+        let has_comments = match (&self.trivia, values.last()) {
+            (Some(trivia), Some((_, loc))) => {
+                let end = trivia.next_code_position(trivia.code_end(loc.location.stop));
+                trivia.has_comments_before(end)
+            }
+            _ => false,
+        };
+        // how far the list reaches before its first line break, if any
+        let mut width = open.len();
+        for (i, f) in flat.iter().enumerate() {
+            width += if i > 0 { 2 } else { 0 };
+            match f.split_once('\n') {
+                Some((first_line, _)) => {
+                    width += first_line.chars().count();
+                    break;
+                }
+                None => width += f.chars().count(),
+            }
+        }
+        if flat.iter().all(|f| !f.contains('\n')) {
+            width += close.len();
+        }
+        let fits = !has_comments
+            && self.column() + width <= MAX_LINE_WIDTH
+            && flat.iter().rev().skip(1).all(|f| !f.contains('\n'));
+
+        if values.is_empty() || fits && flat.iter().all(|f| !f.contains('\n')) {
+            self.write(open).write(&flat.join(", ")).write(close)
+        } else if fits || values.len() == 1 && !has_comments {
+            // print again rather than reuse the flat text, so that comments
+            // in the last item are kept and any lists in it can be split
+            self.write_inline_list(values, open, close)
+        } else {
+            self.write(open)
+                .indent_more()
+                .write_entries(values.iter().map(|(v, loc)| (*v, *loc, ",")))
+                .indent_less()
+                .write("\n")
+                .write(close)
+        }
+    }
+
+    fn write_inline_list(
+        self,
+        values: &[(&dyn PrettyPrintable, &SourcePointer)],
+        open: &str,
+        close: &str,
+    ) -> Self {
+        let mut this = self.write(open);
+        for (i, (value, _)) in values.iter().enumerate() {
+            if i > 0 {
+                this = this.write(", ");
             }
             this = value.prettyprint(this);
         }
-        this.indent_less().write(suffix)
+        this.write(close)
     }
 
     pub(crate) fn write_separated_list<T: PrettyPrintable + std::fmt::Debug>(
@@ -125,6 +456,14 @@ impl std::ops::Shl<usize> for PrintoutAccumulator {
     }
 }
 
+impl std::ops::Shl<u32> for PrintoutAccumulator {
+    type Output = Self;
+
+    fn shl(self, n: u32) -> Self::Output {
+        self.write(&n.to_string())
+    }
+}
+
 impl std::ops::Shl<u16> for PrintoutAccumulator {
     type Output = Self;
 
@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::rc::Rc;
+
+use crate::ast::{Location, SourceBuffer};
+
+// the parts of the source that do not make it into the AST, but that a
+// formatter needs to put back: comments, and blank lines between entries
+#[derive(Clone)]
+pub(crate) struct Trivia {
+    source: SourceBuffer,
+    comments: Rc<[Location]>,
+    // index of the first comment that has not been printed yet
+    next: usize,
+    // source offset where the last thing printed ended
+    last: usize,
+}
+
+fn scan_comments(src: &str) -> Vec<Location> {
+    let bytes = src.as_bytes();
+    let mut comments = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                let start = i;
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                comments.push(Location { start, stop: i });
+            }
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    comments
+}
+
+impl Trivia {
+    pub(crate) fn new(source: &SourceBuffer) -> Self {
+        Self {
+            source: source.clone(),
+            comments: scan_comments(source.content.as_str()).into(),
+            next: 0,
+            last: 0,
+        }
+    }
+
+    pub(crate) fn text(&self, loc: &Location) -> &str {
+        self.source.content[loc.start..loc.stop].trim_end()
+    }
+
+    fn has_blank_line(&self, from: usize, to: usize) -> bool {
+        let to = to.min(self.source.content.len());
+        from < to
+            && self.source.content[from..to]
+                .chars()
+                .filter(|&c| c == '\n')
+                .count()
+                > 1
+    }
+
+    // the offset of the first character at or after pos that is not
+    // whitespace, a comment or a separating comma
+    pub(crate) fn next_code_position(&self, mut pos: usize) -> usize {
+        let bytes = self.source.content.as_bytes();
+        let first = self.comments.partition_point(|c| c.stop <= pos);
+        let mut comments = self.comments[first..].iter().peekable();
+        while pos < bytes.len() {
+            if let Some(c) = comments.peek()
+                && c.start == pos
+            {
+                pos = c.stop;
+                comments.next();
+            } else if matches!(bytes[pos], b' ' | b'\t' | b'\r' | b'\n' | b',') {
+                pos += 1;
+            } else {
+                break;
+            }
+        }
+        pos
+    }
+
+    // the offset just past the last character of code at or before stop; the
+    // span of a rule ending in an optional part can include whatever whitespace
+    // and comments the parser skipped looking for it
+    pub(crate) fn code_end(&self, mut stop: usize) -> usize {
+        let bytes = self.source.content.as_bytes();
+        loop {
+            while stop > 0 && matches!(bytes[stop - 1], b' ' | b'\t' | b'\r' | b'\n') {
+                stop -= 1;
+            }
+            let idx = self.comments.partition_point(|c| c.stop < stop);
+            match self.comments.get(idx) {
+                Some(c) if c.start < stop => stop = c.start,
+                _ => return stop,
+            }
+        }
+    }
+
+    // true for an empty statement, i.e. a lone semicolon, with no comments before it
+    pub(crate) fn is_empty_statement(&self, loc: &Location) -> bool {
+        !self.has_comments_before(loc.start)
+            && &self.source.content[loc.start..self.code_end(loc.stop).max(loc.start)] == ";"
+    }
+
+    // the offset just past the opening brace of a block whose header ends at pos
+    pub(crate) fn block_start(&self, mut pos: usize) -> usize {
+        let bytes = self.source.content.as_bytes();
+        loop {
+            pos = self.next_code_position(pos);
+            match bytes.get(pos) {
+                Some(b'{') => return pos + 1,
+                Some(_) => pos += 1,
+                None => return pos,
+            }
+        }
+    }
+
+    pub(crate) fn last(&self) -> usize {
+        self.last
+    }
+
+    pub(crate) fn set_last(&mut self, pos: usize) {
+        self.last = pos;
+    }
+
+    // marks the comments that come before pos as printed, along with the code up to pos
+    pub(crate) fn skip_to(&mut self, pos: usize) {
+        while self.comments.get(self.next).is_some_and(|c| c.start < pos) {
+            self.next += 1;
+        }
+        self.last = pos;
+    }
+
+    pub(crate) fn has_comments_before(&self, pos: usize) -> bool {
+        self.comments.get(self.next).is_some_and(|c| c.start < pos)
+    }
+
+    // returns the comments that come before pos, each with a flag saying
+    // whether a blank line separates it from whatever was printed before it;
+    // the final flag says whether a blank line comes before pos itself
+    pub(crate) fn take_leading(&mut self, pos: usize) -> (Vec<(bool, String)>, bool) {
+        let mut leading = vec![];
+        while let Some(c) = self.comments.get(self.next)
+            && c.start < pos
+        {
+            let blank = self.has_blank_line(self.last, c.start);
+            leading.push((blank, self.text(c).to_owned()));
+            self.last = c.stop.max(self.last);
+            self.next += 1;
+        }
+        (leading, self.has_blank_line(self.last, pos))
+    }
+
+    // returns a comment that follows the last thing printed on the same line
+    pub(crate) fn take_trailing(&mut self) -> Option<String> {
+        let c = self.comments.get(self.next)?;
+        if c.start < self.last
+            || !self.source.content[self.last..c.start]
+                .chars()
+                .all(|ch| matches!(ch, ' ' | '\t' | ','))
+        {
+            return None;
+        }
+        let text = self.text(c).to_owned();
+        self.last = c.stop;
+        self.next += 1;
+        Some(text)
+    }
+}
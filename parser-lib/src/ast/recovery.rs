@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// pest stops at the first syntax error. To report more than one per file, the
+// statement (or declaration) around the error is blanked out and the source is
+// parsed again, until it parses cleanly or no further progress can be made.
+// Blanking keeps byte offsets and newlines intact, so every error still points
+// at the right place in the original buffer.
+
+use pest::Parser;
+
+use crate::grammar::{HaxbyParser, Rule};
+
+use super::{ParserError, SourceBuffer};
+
+const MAX_ERRORS: usize = 64;
+
+// a declaration always starts a new statement, even if the previous one never ended
+const DECLARATION_KEYWORDS: &[&str] = &["func", "struct", "enum", "mixin", "extension", "import"];
+
+// tokens that can follow a closing brace without starting a new statement
+const CONTINUATION_KEYWORDS: &[&str] = &["else", "elsif", "catch", "finally"];
+const CONTINUATION_PUNCTUATION: &str = ".,?!=+-*/%<>&|^:";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Open(char),
+    Close(char),
+    Semicolon,
+    Word,
+    Other,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    stop: usize,
+}
+
+// just enough of a lexer to find brackets and semicolons that are actually
+// part of the code, i.e. not inside a string literal or a comment
+fn tokenize(src: &str) -> Vec<Token> {
+    let bytes = src.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b' ' | b'\t' | b'\r' | b'\n' => {
+                i += 1;
+                continue;
+            }
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                TokenKind::Other
+            }
+            b'(' | b'[' | b'{' => {
+                i += 1;
+                TokenKind::Open(bytes[start] as char)
+            }
+            b')' | b']' | b'}' => {
+                i += 1;
+                TokenKind::Close(bytes[start] as char)
+            }
+            b';' => {
+                i += 1;
+                TokenKind::Semicolon
+            }
+            c if c == b'_' || c.is_ascii_alphanumeric() => {
+                while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                TokenKind::Word
+            }
+            _ => {
+                // skip a whole (possibly multibyte) character
+                i += src[i..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Other
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            stop: i,
+        });
+    }
+
+    tokens
+}
+
+fn is_declaration(tokens: &[Token], src: &str, idx: usize) -> bool {
+    let token = tokens[idx];
+    DECLARATION_KEYWORDS.contains(&&src[token.start..token.stop])
+}
+
+// a brace that follows an operator (or another open bracket) starts a map
+// literal or similar expression, not a block of statements; "=>" is the
+// exception, since match rules are followed by a code block
+fn is_expression_brace(tokens: &[Token], src: &str, idx: usize) -> bool {
+    if idx == 0 {
+        return false;
+    }
+    let prev = tokens[idx - 1];
+    match prev.kind {
+        TokenKind::Open(_) => true,
+        TokenKind::Other => {
+            let is_arrow = &src[prev.start..prev.stop] == ">"
+                && idx >= 2
+                && &src[tokens[idx - 2].start..tokens[idx - 2].stop] == "=";
+            !is_arrow
+        }
+        _ => false,
+    }
+}
+
+// returns the index of the first token of the statement containing token idx,
+// and how many brackets opened inside that statement are still open at idx
+fn statement_start(tokens: &[Token], src: &str, idx: usize) -> (usize, usize) {
+    let mut depth = 0_usize;
+    let mut unclosed = 0_usize;
+    for i in (0..idx).rev() {
+        match tokens[i].kind {
+            TokenKind::Close(c) => {
+                if depth == 0 && c == '}' {
+                    return (i + 1, unclosed);
+                }
+                depth += 1;
+            }
+            TokenKind::Open(c) => {
+                if depth == 0 {
+                    if c == '{' && !is_expression_brace(tokens, src, i) {
+                        return (i + 1, unclosed);
+                    }
+                    unclosed += 1;
+                } else {
+                    depth -= 1;
+                }
+            }
+            TokenKind::Semicolon if depth == 0 => return (i + 1, unclosed),
+            TokenKind::Word if depth == 0 && is_declaration(tokens, src, i) => {
+                return (i, unclosed);
+            }
+            _ => {}
+        }
+    }
+    (0, unclosed)
+}
+
+fn matching_open(tokens: &[Token], close: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    for i in (0..close).rev() {
+        match tokens[i].kind {
+            TokenKind::Close(_) => depth += 1,
+            TokenKind::Open(_) if depth == 0 => return Some(i),
+            TokenKind::Open(_) => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+// returns one past the index of the last token of the statement containing token idx,
+// or None if the input ends before all brackets opened within the statement are closed
+fn statement_end(tokens: &[Token], src: &str, idx: usize, unclosed: usize) -> Option<usize> {
+    let mut depth = unclosed;
+    for i in idx..tokens.len() {
+        match tokens[i].kind {
+            TokenKind::Open(_) => depth += 1,
+            TokenKind::Close(c) => {
+                if depth == 0 {
+                    if c == '}' {
+                        return Some(i);
+                    }
+                    continue;
+                }
+                depth -= 1;
+                if depth == 0 && c == '}' {
+                    let ends_here = match tokens.get(i + 1) {
+                        None => true,
+                        Some(next) => match next.kind {
+                            TokenKind::Word => {
+                                !CONTINUATION_KEYWORDS.contains(&&src[next.start..next.stop])
+                            }
+                            TokenKind::Other => !src[next.start..next.stop]
+                                .starts_with(|c| CONTINUATION_PUNCTUATION.contains(c)),
+                            TokenKind::Close('}') => true,
+                            _ => false,
+                        },
+                    };
+                    if ends_here {
+                        return Some(i + 1);
+                    }
+                }
+            }
+            TokenKind::Semicolon if depth == 0 => return Some(i + 1),
+            TokenKind::Word if depth == 0 && i > idx && is_declaration(tokens, src, i) => {
+                return Some(i);
+            }
+            _ => {}
+        }
+    }
+    if depth == 0 { Some(tokens.len()) } else { None }
+}
+
+// blanks out the statement around byte offset pos, or returns None if there is nothing to blank
+fn blank_statement_at(src: &str, pos: usize) -> Option<String> {
+    let tokens = tokenize(src);
+    let idx = tokens.iter().position(|t| t.stop > pos)?;
+    let (mut first, unclosed) = statement_start(&tokens, src, idx);
+    // an error right at the start of a statement that follows a block usually
+    // means the block ended an expression that is missing its semicolon, e.g.
+    // a match or if expression; the broken statement is that one, not this one
+    if first == idx
+        && first > 0
+        && tokens[first - 1].kind == TokenKind::Close('}')
+        && let Some(open) = matching_open(&tokens, first - 1)
+    {
+        first = statement_start(&tokens, src, open).0;
+    }
+    // a bracket that is never closed would swallow the rest of the file,
+    // so assume it is the error and resynchronize as if it was not there
+    let last = statement_end(&tokens, src, idx, unclosed)
+        .or_else(|| statement_end(&tokens, src, idx, 0))
+        .unwrap_or(tokens.len());
+    // never blank the brace that closes the enclosing block
+    if first >= last {
+        return None;
+    }
+
+    let start = tokens[first].start;
+    let stop = tokens[last - 1].stop;
+
+    let mut blanked = String::with_capacity(src.len());
+    blanked.push_str(&src[..start]);
+    for c in src[start..stop].chars() {
+        if c == '\n' {
+            blanked.push('\n');
+        } else {
+            blanked.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    }
+    blanked.push_str(&src[stop..]);
+    Some(blanked)
+}
+
+pub(super) fn parser_error(source: &SourceBuffer, err: &pest::error::Error<Rule>) -> ParserError {
+    let loc = From::from(&err.location);
+    ParserError {
+        loc: source.pointer(loc),
+        msg: err.variant.message().to_string(),
+    }
+}
+
+pub(super) fn collect_errors(source: &SourceBuffer, first: ParserError) -> Vec<ParserError> {
+    let mut errors = vec![first];
+    let mut text = source.as_str();
+
+    while errors.len() < MAX_ERRORS {
+        let pos = errors.last().expect("no errors").loc.location.start;
+        let Some(blanked) = blank_statement_at(&text, pos) else {
+            break;
+        };
+        text = blanked;
+
+        match HaxbyParser::parse(Rule::module, &text) {
+            Ok(_) => break,
+            Err(err) => {
+                let err = parser_error(source, &err);
+                if errors
+                    .iter()
+                    .any(|e| e.loc.location.start == err.loc.location.start)
+                {
+                    break;
+                }
+                errors.push(err);
+            }
+        }
+    }
+
+    errors.sort_by_key(|e| e.loc.location.start);
+    errors
+}
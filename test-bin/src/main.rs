@@ -2,15 +2,16 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    path::PathBuf,
     process::{ExitCode, Termination, exit},
     time::{Duration, Instant},
 };
 
 use aria_compiler::compile_from_source;
 use aria_parser::ast::SourceBuffer;
-use clap::{Parser, command};
+use clap::Parser;
 use glob::Paths;
-use haxby_vm::vm::VirtualMachine;
+use haxby_vm::vm::{VirtualMachine, VmOptions};
 use rayon::prelude::*;
 use regex::Regex;
 
@@ -80,6 +81,12 @@ fn parse_tags_from_file(path: &str) -> HashSet<String> {
     tags
 }
 
+// imports are compiled once per run and shared by all tests, in a cache that is
+// deleted at the end of the run rather than in the user's module cache
+static MODULE_CACHE_DIR: once_cell::sync::Lazy<PathBuf> = once_cell::sync::Lazy::new(|| {
+    std::env::temp_dir().join(format!("aria-test-module-cache-{}", std::process::id()))
+});
+
 fn run_test_from_pattern(path: &str) -> TestCaseResult {
     let tags = parse_tags_from_file(path);
     let start_wall = Instant::now();
@@ -104,7 +111,10 @@ fn run_test_from_pattern(path: &str) -> TestCaseResult {
             }
         };
 
-        let mut vm = VirtualMachine::default();
+        let mut vm = VirtualMachine::with_options(VmOptions {
+            module_cache: Some(MODULE_CACHE_DIR.clone()),
+            ..Default::default()
+        });
 
         let entry_rm = match vm.load_module("", entry_cm) {
             Ok(rle) => match rle {
@@ -191,7 +201,7 @@ impl SuiteReport {
                 self.passes.sort_by(|a, b| a.0.cmp(&b.0));
             }
             SortBy::Duration => {
-                self.passes.sort_by(|a, b| a.1.cmp(&b.1));
+                self.passes.sort_by_key(|a| a.1);
             }
         }
     }
@@ -282,6 +292,8 @@ fn main() -> SuiteReport {
             exit(1);
         }
     };
+    let _ = std::fs::remove_dir_all(&*MODULE_CACHE_DIR);
+
     if results.num_fails() == 0 && !args.verbose {
         println!("All tests passed; --verbose to print full report");
         exit(0);
@@ -2,8 +2,8 @@
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Arity {
-    pub required: u8,
-    pub optional: u8,
+    pub required: u16,
+    pub optional: u16,
 }
 
 impl Arity {
@@ -14,7 +14,7 @@ impl Arity {
         }
     }
 
-    pub fn required(r: u8) -> Self {
+    pub fn required(r: u16) -> Self {
         Self {
             required: r,
             optional: 0,
@@ -26,6 +26,7 @@ impl BuiltinFunctionImpl for Alloc {
                 let rv = match b.get_tag() {
                     BVK::Boolean => RuntimeValue::Boolean(false.into()),
                     BVK::Integer => RuntimeValue::Integer(0.into()),
+                    BVK::BigInt => RuntimeValue::BigInt(num_bigint::BigInt::ZERO.into()),
                     BVK::Float => RuntimeValue::Float(0.0.into()),
                     BVK::List => RuntimeValue::List(crate::runtime_value::list::List::from(&[])),
                     BVK::String => RuntimeValue::String("".into()),
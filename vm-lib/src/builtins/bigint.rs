@@ -0,0 +1,328 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use haxby_opcodes::function_attribs::{FUNC_IS_METHOD, METHOD_ATTRIBUTE_TYPE};
+use num_bigint::BigInt;
+
+use crate::{
+    error::vm_error::VmErrorReason,
+    frame::Frame,
+    runtime_value::{
+        RuntimeValue, builtin_type::BuiltinType, function::BuiltinFunctionImpl,
+        kind::RuntimeValueType,
+    },
+    vm::RunloopExit,
+};
+
+use super::{VmBuiltins, integer::int_format};
+
+// the error messages match the ones from Int.parse and Int.parse_radix
+fn parse_radix(s: &str, base: u32) -> Result<BigInt, String> {
+    if !(2..=36).contains(&base) {
+        return Err("invalid radix".to_owned());
+    }
+
+    if s.is_empty() {
+        return Err("empty integer string".to_owned());
+    }
+
+    if let Some(chr) = s.chars().find(|c| !c.is_digit(base)) {
+        return Err(format!("invalid integer digit: {chr}"));
+    }
+
+    BigInt::parse_bytes(s.as_bytes(), base).ok_or_else(|| "invalid integer string".to_owned())
+}
+
+fn parse(s: &str) -> Result<BigInt, String> {
+    let (negative, s) = if let Some(rest) = s.strip_prefix('-') {
+        (true, rest)
+    } else {
+        (false, s.strip_prefix('+').unwrap_or(s))
+    };
+
+    let (base, s) = match s.get(0..2) {
+        Some("0x" | "0X") => (16, &s[2..]),
+        Some("0b" | "0B") => (2, &s[2..]),
+        Some("0o" | "0O") => (8, &s[2..]),
+        _ => (10, s),
+    };
+
+    let value = parse_radix(s, base)?;
+    Ok(if negative { -value } else { value })
+}
+
+fn parse_result(
+    vm: &crate::vm::VirtualMachine,
+    val: Result<BigInt, String>,
+) -> Result<RuntimeValue, VmErrorReason> {
+    match val {
+        Ok(val) => vm
+            .builtins
+            .create_result_ok(RuntimeValue::BigInt(val.into())),
+        Err(msg) => vm
+            .builtins
+            .create_result_err(RuntimeValue::String(msg.into())),
+    }
+}
+
+#[derive(Default)]
+struct New {}
+impl BuiltinFunctionImpl for New {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let _ = VmBuiltins::extract_arg(frame, |x| x.as_builtin_type().cloned())?;
+        let val = VmBuiltins::extract_arg(frame, |x| match x {
+            RuntimeValue::Integer(i) => Some(From::from(&i)),
+            RuntimeValue::BigInt(i) => Some(i),
+            _ => None,
+        })?;
+        frame.stack.push(RuntimeValue::BigInt(val));
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD | METHOD_ATTRIBUTE_TYPE
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(2)
+    }
+
+    fn name(&self) -> &str {
+        "new"
+    }
+}
+
+#[derive(Default)]
+struct Parse {}
+impl BuiltinFunctionImpl for Parse {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        vm: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let _ = VmBuiltins::extract_arg(frame, |x| x.as_builtin_type().cloned())?;
+        let s = VmBuiltins::extract_arg(frame, |x| x.as_string().cloned())?.raw_value();
+        frame.stack.push(parse_result(vm, parse(&s))?);
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD | METHOD_ATTRIBUTE_TYPE
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(2)
+    }
+
+    fn name(&self) -> &str {
+        "parse"
+    }
+}
+
+#[derive(Default)]
+struct ParseRadix {}
+impl BuiltinFunctionImpl for ParseRadix {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        vm: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let _ = VmBuiltins::extract_arg(frame, |x| x.as_builtin_type().cloned())?;
+        let s = VmBuiltins::extract_arg(frame, |x| x.as_string().cloned())?.raw_value();
+        let base = VmBuiltins::extract_arg(frame, |x| x.as_integer().cloned())?.raw_value();
+        let val = match u32::try_from(base) {
+            Ok(base) => parse_radix(&s, base),
+            Err(_) => Err("invalid radix".to_owned()),
+        };
+        frame.stack.push(parse_result(vm, val)?);
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD | METHOD_ATTRIBUTE_TYPE
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(3)
+    }
+
+    fn name(&self) -> &str {
+        "parse_radix"
+    }
+}
+
+#[derive(Default)]
+struct ToStringRadix {}
+impl BuiltinFunctionImpl for ToStringRadix {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let this = VmBuiltins::extract_arg(frame, |x| x.as_big_int().cloned())?;
+        let base = VmBuiltins::extract_arg(frame, |x| x.as_integer().cloned())?.raw_value();
+        match u32::try_from(base) {
+            Ok(base) if (2..=36).contains(&base) => {
+                let result = this.as_bigint().to_str_radix(base);
+                frame.stack.push(RuntimeValue::String(result.into()));
+                Ok(RunloopExit::Ok(()))
+            }
+            _ => Err(VmErrorReason::OperationFailed("invalid radix".to_owned()).into()),
+        }
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(2)
+    }
+
+    fn name(&self) -> &str {
+        "to_string_radix"
+    }
+}
+
+#[derive(Default)]
+struct Prettyprint {}
+impl BuiltinFunctionImpl for Prettyprint {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let this = VmBuiltins::extract_arg(frame, |x| x.as_big_int().cloned())?;
+        let output_string = if !frame.stack.is_empty() {
+            let format_style =
+                VmBuiltins::extract_arg(frame, |x| x.as_string().cloned())?.raw_value();
+            int_format(this.as_bigint(), &format_style)
+        } else {
+            this.as_bigint().to_string()
+        };
+        frame.stack.push(RuntimeValue::String(output_string.into()));
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity {
+            required: 1,
+            optional: 1,
+        }
+    }
+
+    fn name(&self) -> &str {
+        "prettyprint"
+    }
+}
+
+#[derive(Default)]
+struct Hash {}
+impl BuiltinFunctionImpl for Hash {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let this = VmBuiltins::extract_arg(frame, |x| x.as_big_int().cloned())?;
+        frame
+            .stack
+            .push(RuntimeValue::Integer(this.hash_value().into()));
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(1)
+    }
+
+    fn name(&self) -> &str {
+        "hash"
+    }
+}
+
+#[derive(Default)]
+struct ToInt {}
+impl BuiltinFunctionImpl for ToInt {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let this = VmBuiltins::extract_arg(frame, |x| x.as_big_int().cloned())?;
+        match this.to_int() {
+            Some(val) => {
+                frame.stack.push(RuntimeValue::Integer(val));
+                Ok(RunloopExit::Ok(()))
+            }
+            None => Err(VmErrorReason::IntegerOverflow.into()),
+        }
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(1)
+    }
+
+    fn name(&self) -> &str {
+        "int"
+    }
+}
+
+#[derive(Default)]
+struct ToFloat {}
+impl BuiltinFunctionImpl for ToFloat {
+    fn eval(
+        &self,
+        frame: &mut Frame,
+        _: &mut crate::vm::VirtualMachine,
+    ) -> crate::vm::ExecutionResult<RunloopExit> {
+        let this = VmBuiltins::extract_arg(frame, |x| x.as_big_int().cloned())?;
+        frame.stack.push(RuntimeValue::Float(this.to_fp()));
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> crate::arity::Arity {
+        crate::arity::Arity::required(1)
+    }
+
+    fn name(&self) -> &str {
+        "float"
+    }
+}
+
+pub(super) fn insert_bigint_builtins(builtins: &mut VmBuiltins) {
+    let bigint_builtin =
+        BuiltinType::new(crate::runtime_value::builtin_type::BuiltinValueKind::BigInt);
+
+    bigint_builtin.insert_builtin::<New>();
+    bigint_builtin.insert_builtin::<Parse>();
+    bigint_builtin.insert_builtin::<ParseRadix>();
+    bigint_builtin.insert_builtin::<ToStringRadix>();
+    bigint_builtin.insert_builtin::<Prettyprint>();
+    bigint_builtin.insert_builtin::<Hash>();
+    bigint_builtin.insert_builtin::<ToInt>();
+    bigint_builtin.insert_builtin::<ToFloat>();
+
+    builtins.insert(
+        "BigInt",
+        RuntimeValue::Type(RuntimeValueType::Builtin(bigint_builtin)),
+    );
+}
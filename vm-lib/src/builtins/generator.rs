@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cell::RefCell;
+
+use haxby_opcodes::function_attribs::FUNC_IS_METHOD;
+
+use crate::{
+    arity::Arity,
+    builtins::VmBuiltins,
+    error::vm_error::VmErrorReason,
+    frame::Frame,
+    runtime_value::{
+        RuntimeValue,
+        function::{BuiltinFunctionImpl, Function},
+        object::Object,
+        opaque::OpaqueValue,
+    },
+    vm::{ExecutionResult, RunloopExit, VirtualMachine},
+};
+
+pub(crate) struct GeneratorState {
+    func: Function,
+    frame: Frame,
+    argc: u16,
+    done: bool,
+}
+
+impl GeneratorState {
+    fn finish(&mut self) {
+        self.done = true;
+        // release whatever the generator body was holding on to
+        self.frame = Frame::default();
+    }
+}
+
+#[derive(Default)]
+struct Next {}
+impl BuiltinFunctionImpl for Next {
+    fn eval(&self, frame: &mut Frame, vm: &mut VirtualMachine) -> ExecutionResult<RunloopExit> {
+        let aria_this = VmBuiltins::extract_arg(frame, |x: RuntimeValue| x.as_object().cloned())?;
+
+        let generator_impl = aria_this
+            .read("__impl")
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+        let generator_state = generator_impl
+            .as_opaque_concrete::<RefCell<GeneratorState>>()
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+        let mut state = generator_state.try_borrow_mut().map_err(|_| {
+            VmErrorReason::OperationFailed("generator is already running".to_owned())
+        })?;
+
+        if state.done {
+            frame.stack.push(vm.builtins.create_maybe_none()?);
+            return Ok(RunloopExit::Ok(()));
+        }
+
+        let GeneratorState {
+            func,
+            frame: gen_frame,
+            argc,
+            ..
+        } = &mut *state;
+        match func.eval_in_frame(*argc, gen_frame, vm) {
+            Ok(RunloopExit::Ok(_)) => {
+                if gen_frame.is_suspended() {
+                    let value = gen_frame.stack.try_pop().ok_or(VmErrorReason::EmptyStack)?;
+                    frame.stack.push(vm.builtins.create_maybe_some(value)?);
+                } else {
+                    // the return value of a generator is not observable
+                    state.finish();
+                    frame.stack.push(vm.builtins.create_maybe_none()?);
+                }
+                Ok(RunloopExit::Ok(()))
+            }
+            Ok(RunloopExit::Exception(e)) => {
+                state.finish();
+                Ok(RunloopExit::Exception(e))
+            }
+            Err(e) => {
+                state.finish();
+                Err(e)
+            }
+        }
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::required(1)
+    }
+
+    fn name(&self) -> &str {
+        "next"
+    }
+}
+
+// the arguments have already been pushed onto frame; the body does not start
+// running until the first call to next()
+pub(crate) fn create_generator(
+    func: Function,
+    frame: Frame,
+    argc: u16,
+    vm: &VirtualMachine,
+) -> ExecutionResult<RuntimeValue> {
+    let generator_rv = vm
+        .builtins
+        .load_named_value("Generator")
+        .ok_or(VmErrorReason::UnexpectedVmState)?;
+    let generator_struct = generator_rv
+        .as_struct()
+        .ok_or(VmErrorReason::UnexpectedVmState)?;
+
+    let state = GeneratorState {
+        func,
+        frame,
+        argc,
+        done: false,
+    };
+    let obj = Object::new(generator_struct).with_value(
+        "__impl",
+        RuntimeValue::Opaque(OpaqueValue::new(RefCell::new(state))),
+    );
+    let aria_obj = RuntimeValue::Object(obj.clone());
+    let next = Function::new_builtin::<Next>();
+    obj.write("next", aria_obj.bind(next));
+    Ok(aria_obj)
+}
@@ -13,7 +13,11 @@ use crate::{
 
 use super::VmBuiltins;
 
-fn int_format(n: i64, fmt: &str) -> String {
+// shared with BigInt, which supports the same format strings
+pub(super) fn int_format<N>(n: N, fmt: &str) -> String
+where
+    N: std::fmt::Display + std::fmt::LowerHex + std::fmt::UpperHex,
+{
     // Determine if format ends with 'x' or 'X' for hexadecimal formatting
     let (base, digits_spec) = if let Some(stripped) = fmt.strip_suffix('x') {
         (16, (stripped, false)) // lowercase hex
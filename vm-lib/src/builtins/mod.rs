@@ -16,10 +16,12 @@ use crate::{
 
 mod alloc;
 mod arity;
+mod bigint;
 mod boolean;
 mod cmdline_args;
 mod exit;
 mod float;
+pub(crate) mod generator;
 mod getenv;
 mod hasattr;
 mod integer;
@@ -30,6 +32,7 @@ mod now;
 mod prettyprint;
 mod print;
 mod println;
+pub(crate) mod range;
 mod readattr;
 mod readln;
 mod setenv;
@@ -80,6 +83,7 @@ impl Default for VmBuiltins {
 
         alloc::insert_builtins(&mut this);
         arity::insert_builtins(&mut this);
+        bigint::insert_bigint_builtins(&mut this);
         boolean::insert_boolean_builtins(&mut this);
         cmdline_args::insert_builtins(&mut this);
         exit::insert_builtins(&mut this);
@@ -145,6 +149,7 @@ impl VmBuiltins {
             BUILTIN_TYPE_RUNTIME_ERROR => Some(self.get_builtin_type_by_name("RuntimeError")),
             BUILTIN_TYPE_UNIT => Some(self.get_builtin_type_by_name("Unit")),
             BUILTIN_TYPE_TYPE => Some(self.get_builtin_type_by_name("Type")),
+            BUILTIN_TYPE_BIGINT => Some(self.get_builtin_type_by_name("BigInt")),
             _ => None,
         }
     }
@@ -94,13 +94,11 @@ impl BuiltinFunctionImpl for Next {
     }
 }
 
-#[allow(unused)]
 pub fn create_iterator_struct(iter_struct: &Struct, imp: NativeIteratorImpl) -> RuntimeValue {
-    let obj = RuntimeValue::Object(Object::new(iter_struct));
     let impl_attrib = OpaqueValue::new(RefCell::new(imp));
-    obj.write_attribute("__impl", RuntimeValue::Opaque(impl_attrib));
+    let obj = Object::new(iter_struct).with_value("__impl", RuntimeValue::Opaque(impl_attrib));
+    let aria_obj = RuntimeValue::Object(obj.clone());
     let next = Function::new_builtin::<Next>();
-    let bound_next = obj.bind(next);
-    obj.write_attribute("next", bound_next);
-    obj
+    obj.write("next", aria_obj.bind(next));
+    aria_obj
 }
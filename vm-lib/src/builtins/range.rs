@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use haxby_opcodes::function_attribs::FUNC_IS_METHOD;
+
+use crate::{
+    arity::Arity,
+    builtins::{
+        VmBuiltins,
+        native_iterator::{NativeIteratorImpl, create_iterator_struct},
+    },
+    error::vm_error::VmErrorReason,
+    frame::Frame,
+    runtime_value::{
+        RuntimeValue,
+        function::{BuiltinFunctionImpl, Function},
+        object::Object,
+        opaque::OpaqueValue,
+    },
+    vm::{ExecutionResult, RunloopExit, VirtualMachine},
+};
+
+#[derive(Clone, Copy)]
+struct RangeBounds {
+    from: i64,
+    to: i64,
+    step: i64,
+    inclusive: bool,
+}
+
+struct RangeIterator {
+    current: Option<i64>,
+    bounds: RangeBounds,
+}
+
+impl Iterator for RangeIterator {
+    type Item = RuntimeValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        let RangeBounds {
+            to,
+            step,
+            inclusive,
+            ..
+        } = self.bounds;
+        let in_range = if step > 0 {
+            current < to || (inclusive && current == to)
+        } else {
+            current > to || (inclusive && current == to)
+        };
+        if !in_range {
+            self.current = None;
+            return None;
+        }
+
+        // stop rather than wrap around once the next value would overflow
+        self.current = current.checked_add(step);
+        Some(RuntimeValue::Integer(current.into()))
+    }
+}
+
+#[derive(Default)]
+struct RangeIter {}
+impl BuiltinFunctionImpl for RangeIter {
+    fn eval(&self, frame: &mut Frame, vm: &mut VirtualMachine) -> ExecutionResult<RunloopExit> {
+        let aria_this = VmBuiltins::extract_arg(frame, |x: RuntimeValue| x.as_object().cloned())?;
+
+        let range_impl = aria_this
+            .read("__impl")
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+        let bounds = *range_impl
+            .as_opaque_concrete::<RangeBounds>()
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+
+        let range_rv = vm
+            .builtins
+            .load_named_value("Range")
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+        let iter_rv = range_rv
+            .as_struct()
+            .and_then(|s| s.load_named_value("RangeIterator"))
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+        let iter_struct = iter_rv
+            .as_struct()
+            .ok_or(VmErrorReason::UnexpectedVmState)?;
+
+        let iter = RangeIterator {
+            current: Some(bounds.from),
+            bounds,
+        };
+        frame.stack.push(create_iterator_struct(
+            iter_struct,
+            NativeIteratorImpl::new(iter),
+        ));
+        Ok(RunloopExit::Ok(()))
+    }
+
+    fn attrib_byte(&self) -> u8 {
+        FUNC_IS_METHOD
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::required(1)
+    }
+
+    fn name(&self) -> &str {
+        "iterator"
+    }
+}
+
+pub(crate) fn create_range(
+    from: i64,
+    to: i64,
+    step: i64,
+    inclusive: bool,
+    vm: &VirtualMachine,
+) -> ExecutionResult<RuntimeValue> {
+    let range_rv = vm
+        .builtins
+        .load_named_value("Range")
+        .ok_or(VmErrorReason::UnexpectedVmState)?;
+    let range_struct = range_rv
+        .as_struct()
+        .ok_or(VmErrorReason::UnexpectedVmState)?;
+
+    let bounds = RangeBounds {
+        from,
+        to,
+        step,
+        inclusive,
+    };
+    let obj = Object::new(range_struct)
+        .with_value("from", RuntimeValue::Integer(from.into()))
+        .with_value("to", RuntimeValue::Integer(to.into()))
+        .with_value("step", RuntimeValue::Integer(step.into()))
+        .with_value("inclusive", RuntimeValue::Boolean(inclusive.into()))
+        .with_value("__impl", RuntimeValue::Opaque(OpaqueValue::new(bounds)));
+    let aria_obj = RuntimeValue::Object(obj.clone());
+    let iterator = Function::new_builtin::<RangeIter>();
+    obj.write("iterator", aria_obj.bind(iterator));
+    Ok(aria_obj)
+}
@@ -56,14 +56,23 @@ impl VmException {
 impl VmException {
     pub(crate) fn fill_in_backtrace(&self) {
         let bt_list = List::from(&[]);
+        // [file, line, column, end line, end column], all counting from 0;
+        // the end points just past the expression
         for bt_entry in self.backtrace.entries_iter() {
-            let buf_name = bt_entry.buffer.name.clone();
-            let buf_line = bt_entry
-                .buffer
-                .line_index_for_position(bt_entry.location.start);
-            let buf_name = RuntimeValue::String(buf_name.into());
-            let buf_line = RuntimeValue::Integer((buf_line as i64).into());
-            bt_list.append(RuntimeValue::List(List::from(&[buf_name, buf_line])));
+            let buffer = &bt_entry.buffer;
+            let position = |pos: usize| {
+                [
+                    buffer.line_index_for_position(pos),
+                    buffer.column_index_for_position(pos),
+                ]
+                .map(|idx| RuntimeValue::Integer((idx as i64).into()))
+            };
+            let [line, column] = position(bt_entry.location.start);
+            let [end_line, end_column] = position(bt_entry.location.stop);
+            let buf_name = RuntimeValue::String(buffer.name.clone().into());
+            bt_list.append(RuntimeValue::List(List::from(&[
+                buf_name, line, column, end_line, end_column,
+            ])));
         }
         let _ = self
             .value
@@ -99,10 +108,18 @@ impl VmException {
                 case: some_or_err!(rt_err.get_idx_of_case("DivisionByZero"), err),
                 payload: None,
             },
+            VmErrorReason::DuplicateArgumentName(s) => ExceptionData {
+                case: some_or_err!(rt_err.get_idx_of_case("DuplicateArgumentName"), err),
+                payload: Some(RuntimeValue::String(s.clone().into())),
+            },
             VmErrorReason::EnumWithoutPayload => ExceptionData {
                 case: some_or_err!(rt_err.get_idx_of_case("EnumWithoutPayload"), err),
                 payload: None,
             },
+            VmErrorReason::IntegerOverflow => ExceptionData {
+                case: some_or_err!(rt_err.get_idx_of_case("IntegerOverflow"), err),
+                payload: None,
+            },
             VmErrorReason::IndexOutOfBounds(idx) => ExceptionData {
                 case: some_or_err!(rt_err.get_idx_of_case("IndexOutOfBounds"), err),
                 payload: Some(RuntimeValue::Integer((*idx as i64).into())),
@@ -119,6 +136,10 @@ impl VmException {
                     payload: Some(RuntimeValue::Object(argc_mismatch_obj)),
                 }
             }
+            VmErrorReason::MissingArgument(s) => ExceptionData {
+                case: some_or_err!(rt_err.get_idx_of_case("MissingArgument"), err),
+                payload: Some(RuntimeValue::String(s.clone().into())),
+            },
             VmErrorReason::NoSuchCase(s) => ExceptionData {
                 case: some_or_err!(rt_err.get_idx_of_case("NoSuchCase"), err),
                 payload: Some(RuntimeValue::String(s.clone().into())),
@@ -135,6 +156,10 @@ impl VmException {
                 case: some_or_err!(rt_err.get_idx_of_case("UnexpectedType"), err),
                 payload: None,
             },
+            VmErrorReason::UnknownArgumentName(s) => ExceptionData {
+                case: some_or_err!(rt_err.get_idx_of_case("UnknownArgumentName"), err),
+                payload: Some(RuntimeValue::String(s.clone().into())),
+            },
             _ => {
                 return Err(err);
             }
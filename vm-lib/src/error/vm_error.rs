@@ -25,6 +25,9 @@ pub enum VmErrorReason {
     #[error("runtime stack is empty")]
     EmptyStack,
 
+    #[error("integer overflow")]
+    IntegerOverflow,
+
     #[error("index {0} out of bounds")]
     IndexOutOfBounds(usize),
 
@@ -43,6 +46,15 @@ pub enum VmErrorReason {
     #[error("mismatched argument count, expected {0} actual {1}")]
     MismatchedArgumentCount(usize, usize),
 
+    #[error("no value supplied for argument '{0}'")]
+    MissingArgument(String),
+
+    #[error("function has no argument named '{0}'")]
+    UnknownArgumentName(String),
+
+    #[error("argument '{0}' supplied more than once")]
+    DuplicateArgumentName(String),
+
     #[error("unknown named identifier: '{0}'")]
     NoSuchIdentifier(String),
 
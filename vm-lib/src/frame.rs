@@ -34,7 +34,13 @@ pub struct Frame {
     pub(crate) ctrl_blocks: Stack<ControlBlock>,
     pub(crate) locals: Vec<LocalVariable>,
     pub(crate) func: Option<Function>,
-    pub argc: u8,
+    pub argc: u16,
+    // arguments below argc that a named call left out, see is_arg_supplied
+    pub(crate) skipped_args: Vec<u16>,
+    // if this frame was suspended by a yield, the bytecode offset to resume from
+    pub(crate) suspended_at: Option<usize>,
+    // callables registered by defer, to run in reverse order when the function exits
+    pub(crate) deferred: Vec<RuntimeValue>,
 }
 
 impl Frame {
@@ -59,7 +65,7 @@ impl Frame {
         this
     }
 
-    pub(crate) fn new_with_n_locals(n: u8) -> Self {
+    pub(crate) fn new_with_n_locals(n: u16) -> Self {
         let mut this = Self {
             stack: Default::default(),
             line_table: None,
@@ -67,6 +73,9 @@ impl Frame {
             locals: Vec::with_capacity(n as usize),
             func: None,
             argc: 0,
+            skipped_args: vec![],
+            suspended_at: None,
+            deferred: vec![],
         };
         for _ in 0..n {
             this.locals.push(LocalVariable::default())
@@ -74,11 +83,20 @@ impl Frame {
         this
     }
 
-    pub(crate) fn set_argc(&mut self, argc: u8) -> &mut Self {
+    pub(crate) fn set_argc(&mut self, argc: u16) -> &mut Self {
         self.argc = argc;
         self
     }
 
+    pub(crate) fn is_arg_supplied(&self, idx: u16) -> bool {
+        (idx as usize) < self.argc as usize + self.skipped_args.len()
+            && !self.skipped_args.contains(&idx)
+    }
+
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+    }
+
     pub(crate) fn set_line_table(&mut self, lt: Option<&LineTable>) -> &mut Self {
         self.line_table = lt.cloned();
         self
@@ -8,6 +8,7 @@ pub mod console;
 pub mod error;
 pub mod frame;
 pub mod mixin_includer;
+pub mod module_cache;
 pub mod opcodes;
 pub mod runtime_module;
 pub mod runtime_value;
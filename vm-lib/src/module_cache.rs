@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: Apache-2.0
+use std::{
+    fs::File,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use aria_compiler::{
+    CompilationOptions,
+    module::CompiledModule,
+    module_file::{COMPILER_BUILD, MODULE_FILE_EXTENSION, read_module_file, write_module_file},
+};
+use aria_parser::ast::SourceBuffer;
+use sha2::{Digest, Sha256};
+
+// compiled imports are stored on disk keyed by a hash of everything that
+// affects the compiler output: the source text and path, the compiler build and the
+// compilation options; changing any of them yields a different key, so stale entries
+// are never looked up again, and are deleted once they have gone unused for a while
+const MAX_UNUSED_ENTRY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+// scanning the whole cache on every store would slow down the imports it is
+// meant to speed up, so it is pruned at most once a day, as recorded by the
+// modification time of this file in the cache directory
+const PRUNE_MARKER: &str = ".last-prune";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub struct ModuleCache {
+    dir: PathBuf,
+    // set once this cache has checked whether pruning is due
+    prune_checked: AtomicBool,
+}
+
+impl ModuleCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            prune_checked: AtomicBool::new(false),
+        }
+    }
+
+    // $XDG_CACHE_HOME/aria, falling back to ~/.cache/aria
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(base.join("aria"))
+    }
+
+    fn entry_path(&self, sb: &SourceBuffer, options: &CompilationOptions) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(COMPILER_BUILD);
+        hasher.update([options.optimize as u8, options.checked_arithmetic as u8]);
+        hasher.update((sb.name.len() as u64).to_le_bytes());
+        hasher.update(&sb.name);
+        hasher.update(sb.content.as_bytes());
+        let key = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        self.dir.join(key).with_extension(MODULE_FILE_EXTENSION)
+    }
+
+    pub fn load(&self, sb: &SourceBuffer, options: &CompilationOptions) -> Option<CompiledModule> {
+        let path = self.entry_path(sb, options);
+        let data = std::fs::read(&path).ok()?;
+        let module = read_module_file(&data).ok()?;
+        // entries are aged by modification time, since access times are often not kept
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(module)
+    }
+
+    // failing to store an entry only means the module is compiled again next time
+    pub fn store(&self, sb: &SourceBuffer, options: &CompilationOptions, module: &CompiledModule) {
+        static NEXT_TEMP_ID: AtomicUsize = AtomicUsize::new(0);
+
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+
+        // write to a temporary file first, so concurrent readers never see a partial entry
+        let temp = self.dir.join(format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        if std::fs::write(&temp, write_module_file(module)).is_err()
+            || std::fs::rename(&temp, self.entry_path(sb, options)).is_err()
+        {
+            let _ = std::fs::remove_file(&temp);
+        }
+
+        self.prune_if_due();
+    }
+
+    fn prune_if_due(&self) {
+        if self.prune_checked.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let marker = self.dir.join(PRUNE_MARKER);
+        let pruned_recently = std::fs::metadata(&marker)
+            .and_then(|md| md.modified())
+            .ok()
+            .and_then(|last| last.elapsed().ok())
+            .is_some_and(|age| age < PRUNE_INTERVAL);
+        if !pruned_recently && std::fs::write(&marker, []).is_ok() {
+            self.prune(MAX_UNUSED_ENTRY_AGE);
+        }
+    }
+
+    // deletes the entries that were neither stored nor loaded in the last max_age,
+    // and any temporary file left behind by a process that died while storing one
+    pub fn prune(&self, max_age: Duration) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let Some(cutoff) = SystemTime::now().checked_sub(max_age) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let is_stale = entry
+                .metadata()
+                .and_then(|md| md.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if is_stale {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
@@ -96,7 +96,7 @@ impl RuntimeModuleImpl {
         }
     }
 
-    fn load_indexed_const(&self, idx: u16) -> Option<ConstantValue> {
+    fn load_indexed_const(&self, idx: u32) -> Option<ConstantValue> {
         self.compiled_module.load_indexed_const(idx)
     }
 
@@ -150,7 +150,7 @@ impl RuntimeModule {
         self.imp.store_typechecked_named_value(name, val, builtins)
     }
 
-    pub fn load_indexed_const(&self, idx: u16) -> Option<ConstantValue> {
+    pub fn load_indexed_const(&self, idx: u32) -> Option<ConstantValue> {
         self.imp.load_indexed_const(idx)
     }
 
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: Apache-2.0
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use super::{RuntimeValue, builtin_value::BuiltinValue, float::FloatValue, integer::IntegerValue};
+
+pub type BigIntValue = BuiltinValue<BigInt>;
+
+// a left shift allocates its whole result up front, so shifting by more
+// bits than this throws instead of taking the VM down with it
+const MAX_LEFT_SHIFT: usize = 1 << 24;
+
+impl BigIntValue {
+    // raw_value() would clone the digits, most operations only need to look at them
+    pub fn as_bigint(&self) -> &BigInt {
+        &self.imp.val
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.as_bigint().is_zero()
+    }
+
+    pub fn to_int(&self) -> Option<IntegerValue> {
+        self.as_bigint().to_i64().map(From::from)
+    }
+
+    pub fn to_fp(&self) -> FloatValue {
+        self.as_bigint().to_f64().unwrap_or(f64::NAN).into()
+    }
+
+    // an Int that fits in 64 bits hashes to itself, so BigInt hashes
+    // must agree with it for values that compare equal
+    pub fn hash_value(&self) -> i64 {
+        match self.as_bigint().to_i64() {
+            Some(n) => n,
+            None => self
+                .as_bigint()
+                .iter_u64_digits()
+                .fold(0_u64, |acc, d| acc.rotate_left(5) ^ d) as i64,
+        }
+    }
+
+    // if either operand is a BigInt and the other one is a BigInt or an Int,
+    // returns both operands as BigInt; mixed arithmetic always promotes Int
+    pub fn promote(lhs: &RuntimeValue, rhs: &RuntimeValue) -> Option<(BigIntValue, BigIntValue)> {
+        match (lhs, rhs) {
+            (RuntimeValue::BigInt(a), RuntimeValue::BigInt(b)) => Some((a.clone(), b.clone())),
+            (RuntimeValue::BigInt(a), RuntimeValue::Integer(b)) => Some((a.clone(), b.into())),
+            (RuntimeValue::Integer(a), RuntimeValue::BigInt(b)) => Some((a.into(), b.clone())),
+            _ => None,
+        }
+    }
+
+    // this value as the amount of a left shift
+    pub fn left_shift_amount(&self) -> Result<usize, &'static str> {
+        if self.as_bigint().is_negative() {
+            return Err("negative shift amount");
+        }
+        match self.as_bigint().to_usize() {
+            Some(by) if by <= MAX_LEFT_SHIFT => Ok(by),
+            _ => Err("shift amount too large"),
+        }
+    }
+
+    // this value as the amount of a right shift; shifting by at least as
+    // many bits as there are already gives 0 (or -1), however large the amount
+    pub fn right_shift_amount(&self) -> Result<usize, &'static str> {
+        if self.as_bigint().is_negative() {
+            return Err("negative shift amount");
+        }
+        Ok(self.as_bigint().to_usize().unwrap_or(usize::MAX))
+    }
+
+    // if one operand is a BigInt and the other one is a Float, returns both
+    // operands as Float, just like Int/Float arithmetic does
+    pub fn promote_fp(lhs: &RuntimeValue, rhs: &RuntimeValue) -> Option<(FloatValue, FloatValue)> {
+        match (lhs, rhs) {
+            (RuntimeValue::BigInt(a), RuntimeValue::Float(b)) => Some((a.to_fp(), b.clone())),
+            (RuntimeValue::Float(a), RuntimeValue::BigInt(b)) => Some((a.clone(), b.to_fp())),
+            _ => None,
+        }
+    }
+}
+
+impl From<&IntegerValue> for BigIntValue {
+    fn from(value: &IntegerValue) -> Self {
+        From::from(BigInt::from(value.raw_value()))
+    }
+}
+
+impl std::ops::Add<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn add(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() + rhs.as_bigint())
+    }
+}
+
+impl std::ops::Sub<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn sub(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() - rhs.as_bigint())
+    }
+}
+
+impl std::ops::Mul<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn mul(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() * rhs.as_bigint())
+    }
+}
+
+impl std::ops::Div<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn div(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() / rhs.as_bigint())
+    }
+}
+
+impl std::ops::Rem<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn rem(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() % rhs.as_bigint())
+    }
+}
+
+impl std::ops::Neg for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn neg(self) -> Self::Output {
+        From::from(-self.as_bigint())
+    }
+}
+
+impl std::ops::BitAnd<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn bitand(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() & rhs.as_bigint())
+    }
+}
+
+impl std::ops::BitOr<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn bitor(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() | rhs.as_bigint())
+    }
+}
+
+impl std::ops::BitXor<&BigIntValue> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn bitxor(self, rhs: &BigIntValue) -> Self::Output {
+        From::from(self.as_bigint() ^ rhs.as_bigint())
+    }
+}
+
+impl std::ops::Shl<usize> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        From::from(self.as_bigint() << rhs)
+    }
+}
+
+impl std::ops::Shr<usize> for &BigIntValue {
+    type Output = BigIntValue;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        From::from(self.as_bigint() >> rhs)
+    }
+}
+
+impl PartialEq<BigIntValue> for BigIntValue {
+    fn eq(&self, other: &BigIntValue) -> bool {
+        self.as_bigint() == other.as_bigint()
+    }
+}
+impl Eq for BigIntValue {}
+
+impl PartialOrd<BigIntValue> for BigIntValue {
+    fn partial_cmp(&self, other: &BigIntValue) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for BigIntValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_bigint().cmp(other.as_bigint())
+    }
+}
+
+impl PartialEq<IntegerValue> for BigIntValue {
+    fn eq(&self, other: &IntegerValue) -> bool {
+        *self.as_bigint() == BigInt::from(other.raw_value())
+    }
+}
+
+impl PartialEq<FloatValue> for BigIntValue {
+    fn eq(&self, other: &FloatValue) -> bool {
+        self.to_fp() == *other
+    }
+}
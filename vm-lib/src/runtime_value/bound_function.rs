@@ -36,7 +36,7 @@ impl BoundFunction {
 
     pub fn eval(
         &self,
-        argc: u8,
+        argc: u16,
         cur_frame: &mut Frame,
         vm: &mut VirtualMachine,
         discard_result: bool,
@@ -46,6 +46,26 @@ impl BoundFunction {
         self.func()
             .eval(argc, cur_frame, vm, &partial_application, discard_result)
     }
+
+    pub fn eval_named(
+        &self,
+        argc: u16,
+        names: &[String],
+        cur_frame: &mut Frame,
+        vm: &mut VirtualMachine,
+        discard_result: bool,
+    ) -> ExecutionResult<CallResult> {
+        let partial_application =
+            PartialFunctionApplication::default().with_suffix_arg(self.this().clone());
+        self.func().eval_named(
+            argc,
+            names,
+            cur_frame,
+            vm,
+            &partial_application,
+            discard_result,
+        )
+    }
 }
 
 impl PartialEq for BoundFunction {
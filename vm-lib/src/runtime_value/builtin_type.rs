@@ -15,6 +15,7 @@ use super::{
 pub enum BuiltinValueKind {
     Boolean,
     Integer,
+    BigInt,
     Float,
     List,
     String,
@@ -121,6 +122,7 @@ impl std::fmt::Debug for BuiltinType {
         match self.get_tag() {
             BuiltinValueKind::Boolean => write!(f, "Bool"),
             BuiltinValueKind::Integer => write!(f, "Int"),
+            BuiltinValueKind::BigInt => write!(f, "BigInt"),
             BuiltinValueKind::Float => write!(f, "Float"),
             BuiltinValueKind::List => write!(f, "List"),
             BuiltinValueKind::String => write!(f, "String"),
@@ -3,11 +3,14 @@ use std::{collections::HashMap, rc::Rc};
 
 use aria_compiler::line_table::LineTable;
 use aria_parser::ast::SourcePointer;
-use haxby_opcodes::function_attribs::{FUNC_ACCEPTS_VARARG, FUNC_IS_METHOD, METHOD_ATTRIBUTE_TYPE};
+use haxby_opcodes::function_attribs::{
+    FUNC_ACCEPTS_VARARG, FUNC_IS_GENERATOR, FUNC_IS_METHOD, METHOD_ATTRIBUTE_TYPE,
+};
 use rustc_data_structures::fx::FxHashSet;
 
 use crate::{
     arity::Arity,
+    builtins::generator::create_generator,
     frame::Frame,
     runtime_module::RuntimeModule,
     vm::{ExecutionResult, RunloopExit, VirtualMachine},
@@ -44,21 +47,22 @@ pub struct BytecodeFunction {
     pub name: String,
     pub body: Rc<[u8]>,
     pub arity: Arity,
-    pub frame_size: u8,
+    pub arg_names: Rc<[String]>,
+    pub frame_size: u16,
     pub line_table: Rc<LineTable>,
     pub loc: SourcePointer,
     pub attrib_byte: u8,
     pub module: RuntimeModule,
     pub(crate) boxx: ObjectBox,
-    uplevels: std::cell::RefCell<HashMap<u8, RuntimeValue>>,
+    uplevels: std::cell::RefCell<HashMap<u16, RuntimeValue>>,
 }
 
 impl BytecodeFunction {
-    pub(crate) fn store_uplevel(&self, idx: u8, val: RuntimeValue) {
+    pub(crate) fn store_uplevel(&self, idx: u16, val: RuntimeValue) {
         self.uplevels.borrow_mut().insert(idx, val);
     }
 
-    pub(crate) fn read_uplevel(&self, idx: u8) -> Option<RuntimeValue> {
+    pub(crate) fn read_uplevel(&self, idx: u16) -> Option<RuntimeValue> {
         self.uplevels.borrow().get(&idx).cloned()
     }
 }
@@ -96,7 +100,7 @@ impl FunctionImpl {
         }
     }
 
-    pub(crate) fn frame_size(&self) -> u8 {
+    pub(crate) fn frame_size(&self) -> u16 {
         match self {
             Self::BytecodeFunction(bc) => bc.frame_size,
             Self::BuiltinFunction(_) => 0,
@@ -131,7 +135,7 @@ impl Function {
         self.imp.arity()
     }
 
-    pub fn frame_size(&self) -> u8 {
+    pub fn frame_size(&self) -> u16 {
         self.imp.frame_size()
     }
 
@@ -167,6 +171,10 @@ impl FunctionAttribute {
         self.val & FUNC_ACCEPTS_VARARG != 0
     }
 
+    pub fn is_generator(&self) -> bool {
+        self.val & FUNC_IS_GENERATOR != 0
+    }
+
     pub fn is_method(&self) -> bool {
         self.val & FUNC_IS_METHOD == FUNC_IS_METHOD
     }
@@ -205,6 +213,7 @@ impl FunctionImpl {
                 required: co.required_argc,
                 optional: co.default_argc,
             },
+            arg_names: co.arg_names.clone(),
             frame_size: co.frame_size,
             line_table: lt,
             loc: co.loc.clone(),
@@ -240,9 +249,11 @@ impl FunctionImpl {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct PartialFunctionApplication {
     suffix_args: Vec<RuntimeValue>,
+    // arguments left out of a named call, which take their default value
+    skipped_args: Vec<u16>,
 }
 
 impl PartialFunctionApplication {
@@ -250,6 +261,11 @@ impl PartialFunctionApplication {
         self.suffix_args.push(arg);
         self
     }
+
+    pub fn with_skipped_args(mut self, args: Vec<u16>) -> Self {
+        self.skipped_args = args;
+        self
+    }
 }
 
 impl Function {
@@ -277,10 +293,10 @@ impl Function {
         }
     }
 
-    // DO NOT CALL unless you are Function or BoundFunction
-    pub(super) fn eval_in_frame(
+    // DO NOT CALL unless you are Function, BoundFunction or a Generator
+    pub(crate) fn eval_in_frame(
         &self,
-        argc: u8,
+        argc: u16,
         target_frame: &mut Frame,
         vm: &mut VirtualMachine,
     ) -> ExecutionResult<RunloopExit> {
@@ -295,15 +311,16 @@ impl Function {
 
     pub fn eval(
         &self,
-        argc: u8,
+        argc: u16,
         cur_frame: &mut Frame,
         vm: &mut VirtualMachine,
         other_args: &PartialFunctionApplication,
         discard_result: bool,
     ) -> ExecutionResult<CallResult> {
         let mut new_frame = Frame::new_with_function(self.clone());
+        new_frame.skipped_args = other_args.skipped_args.clone();
 
-        let other_argc = other_args.suffix_args.len() as u8;
+        let other_argc = other_args.suffix_args.len() as u16;
         let effective_argc = argc + other_argc;
         let fixed_arity = self.arity().required + self.arity().optional;
 
@@ -360,6 +377,14 @@ impl Function {
             new_frame.stack.push(arg.clone());
         }
 
+        if self.attribute().is_generator() {
+            let generator = create_generator(self.clone(), new_frame, effective_argc, vm)?;
+            if !discard_result {
+                cur_frame.stack.push(generator.clone());
+            }
+            return Ok(CallResult::Ok(generator));
+        }
+
         match self.eval_in_frame(effective_argc, &mut new_frame, vm)? {
             RunloopExit::Ok(_) => match new_frame.stack.try_pop() {
                 Some(ret) => {
@@ -374,6 +399,84 @@ impl Function {
         }
     }
 
+    // the positional arguments are on the stack as for eval, with the values of the
+    // named arguments below them; each value is matched to its slot in the argument
+    // list, and the arguments left out in between are marked as skipped, so that
+    // the callee computes their default values
+    pub fn eval_named(
+        &self,
+        argc: u16,
+        names: &[String],
+        cur_frame: &mut Frame,
+        vm: &mut VirtualMachine,
+        other_args: &PartialFunctionApplication,
+        discard_result: bool,
+    ) -> ExecutionResult<CallResult> {
+        use crate::error::vm_error::VmErrorReason;
+
+        let arg_names: &[String] = match self.imp.as_ref() {
+            FunctionImpl::BytecodeFunction(bcf) => &bcf.arg_names,
+            FunctionImpl::BuiltinFunction(_) => &[],
+        };
+        let bound = other_args.suffix_args.len();
+        let positional = cur_frame.stack.pop_count(argc as usize);
+        let named = cur_frame.stack.pop_count(names.len());
+
+        let fixed_arity = (self.arity().required + self.arity().optional) as usize;
+        if bound + positional.len() > fixed_arity {
+            return Err(VmErrorReason::MismatchedArgumentCount(
+                fixed_arity,
+                bound + positional.len() + named.len(),
+            )
+            .into());
+        }
+
+        let mut slots: Vec<Option<RuntimeValue>> = vec![None; fixed_arity];
+        for (idx, val) in positional.into_iter().enumerate() {
+            slots[bound + idx] = Some(val);
+        }
+        for (name, val) in names.iter().zip(named) {
+            let idx = match arg_names.iter().position(|arg| arg == name) {
+                Some(idx) if idx < fixed_arity => idx,
+                _ => return Err(VmErrorReason::UnknownArgumentName(name.clone()).into()),
+            };
+            if idx < bound || slots[idx].is_some() {
+                return Err(VmErrorReason::DuplicateArgumentName(name.clone()).into());
+            }
+            slots[idx] = Some(val);
+        }
+
+        let required = self.arity().required as usize;
+        if let Some(idx) = (bound..required).find(|idx| slots[*idx].is_none()) {
+            return Err(VmErrorReason::MissingArgument(arg_names[idx].clone()).into());
+        }
+
+        let supplied_end = slots
+            .iter()
+            .rposition(|s| s.is_some())
+            .map_or(bound, |i| i + 1);
+        let mut skipped = vec![];
+        let mut values = vec![];
+        for (idx, slot) in slots.into_iter().enumerate().take(supplied_end).skip(bound) {
+            match slot {
+                Some(val) => values.push(val),
+                None => skipped.push(idx as u16),
+            }
+        }
+
+        let argc = values.len() as u16;
+        for val in values.into_iter().rev() {
+            cur_frame.stack.push(val);
+        }
+        self.eval(
+            argc,
+            cur_frame,
+            vm,
+            &other_args.clone().with_skipped_args(skipped),
+            discard_result,
+        )
+    }
+
     pub fn write(&self, name: &str, val: RuntimeValue) {
         self.imp.write(name, val)
     }
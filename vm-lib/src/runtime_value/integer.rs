@@ -7,6 +7,54 @@ impl IntegerValue {
     pub fn to_fp(&self) -> FloatValue {
         (self.raw_value() as f64).into()
     }
+
+    // the checked operations return None on overflow (or a shift amount
+    // outside of 0..64), instead of wrapping around like the operators do
+
+    pub fn checked_add(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        self.raw_value()
+            .checked_add(rhs.raw_value())
+            .map(From::from)
+    }
+
+    pub fn checked_sub(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        self.raw_value()
+            .checked_sub(rhs.raw_value())
+            .map(From::from)
+    }
+
+    pub fn checked_mul(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        self.raw_value()
+            .checked_mul(rhs.raw_value())
+            .map(From::from)
+    }
+
+    pub fn checked_div(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        self.raw_value()
+            .checked_div(rhs.raw_value())
+            .map(From::from)
+    }
+
+    pub fn checked_neg(&self) -> Option<IntegerValue> {
+        self.raw_value().checked_neg().map(From::from)
+    }
+
+    // i64::checked_shl only rejects out-of-range amounts, a shift that drops
+    // significant bits (or flips the sign) overflows too
+    pub fn checked_shl(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        let n = self.raw_value();
+        u32::try_from(rhs.raw_value())
+            .ok()
+            .and_then(|by| n.checked_shl(by).filter(|r| r >> by == n))
+            .map(From::from)
+    }
+
+    pub fn checked_shr(&self, rhs: &IntegerValue) -> Option<IntegerValue> {
+        u32::try_from(rhs.raw_value())
+            .ok()
+            .and_then(|by| self.raw_value().checked_shr(by))
+            .map(From::from)
+    }
 }
 
 impl PartialEq<FloatValue> for IntegerValue {
@@ -59,7 +107,7 @@ impl std::ops::Neg for &IntegerValue {
     type Output = IntegerValue;
 
     fn neg(self) -> Self::Output {
-        From::from(-self.raw_value())
+        From::from(self.raw_value().wrapping_neg())
     }
 }
 
@@ -91,7 +139,7 @@ impl std::ops::Shl<&IntegerValue> for &IntegerValue {
     type Output = IntegerValue;
 
     fn shl(self, rhs: &IntegerValue) -> Self::Output {
-        From::from(self.raw_value() << rhs.raw_value())
+        From::from(self.raw_value().wrapping_shl(rhs.raw_value() as u32))
     }
 }
 
@@ -99,7 +147,7 @@ impl std::ops::Shr<&IntegerValue> for &IntegerValue {
     type Output = IntegerValue;
 
     fn shr(self, rhs: &IntegerValue) -> Self::Output {
-        From::from(self.raw_value() >> rhs.raw_value())
+        From::from(self.raw_value().wrapping_shr(rhs.raw_value() as u32))
     }
 }
 
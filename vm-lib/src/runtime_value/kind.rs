@@ -90,6 +90,7 @@ impl RuntimeValueType {
             RuntimeValue::Type(_) => builtins.get_builtin_type_by_name("Type"),
             RuntimeValue::Boolean(_) => builtins.get_builtin_type_by_name("Bool"),
             RuntimeValue::Integer(_) => builtins.get_builtin_type_by_name("Int"),
+            RuntimeValue::BigInt(_) => builtins.get_builtin_type_by_name("BigInt"),
             RuntimeValue::Float(_) => builtins.get_builtin_type_by_name("Float"),
             RuntimeValue::List(_) => builtins.get_builtin_type_by_name("List"),
             RuntimeValue::String(_) => builtins.get_builtin_type_by_name("String"),
@@ -3,6 +3,7 @@
 use std::rc::Rc;
 
 use aria_compiler::constant_value::ConstantValue;
+use bigint::BigIntValue;
 use boolean::BooleanValue;
 use bound_function::BoundFunction;
 use builtin_type::BuiltinType;
@@ -12,8 +13,8 @@ use enumeration::Enum;
 use float::FloatValue;
 use function::Function;
 use haxby_opcodes::builtin_type_ids::{
-    BUILTIN_TYPE_BOOL, BUILTIN_TYPE_FLOAT, BUILTIN_TYPE_INT, BUILTIN_TYPE_LIST,
-    BUILTIN_TYPE_STRING, BUILTIN_TYPE_UNIMPLEMENTED,
+    BUILTIN_TYPE_BIGINT, BUILTIN_TYPE_BOOL, BUILTIN_TYPE_FLOAT, BUILTIN_TYPE_INT,
+    BUILTIN_TYPE_LIST, BUILTIN_TYPE_STRING, BUILTIN_TYPE_UNIMPLEMENTED,
 };
 use integer::IntegerValue;
 use kind::RuntimeValueType;
@@ -34,6 +35,7 @@ use crate::{
     vm::{ExecutionResult, VirtualMachine},
 };
 
+pub mod bigint;
 pub mod boolean;
 pub mod bound_function;
 pub mod builtin_type;
@@ -56,6 +58,7 @@ pub mod structure;
 #[derive(EnumAsInner, Clone)]
 pub enum RuntimeValue {
     Integer(IntegerValue),
+    BigInt(BigIntValue),
     String(StringValue),
     Float(FloatValue),
     Boolean(BooleanValue),
@@ -84,6 +87,11 @@ impl RuntimeValue {
             (Self::Float(l0), Self::Float(r0)) => l0 == r0,
             (Self::Float(l0), Self::Integer(r0)) => l0 == r0,
             (Self::Integer(l0), Self::Float(r0)) => l0 == r0,
+            (Self::BigInt(l0), Self::BigInt(r0)) => l0 == r0,
+            (Self::BigInt(l0), Self::Integer(r0)) => l0 == r0,
+            (Self::Integer(l0), Self::BigInt(r0)) => r0 == l0,
+            (Self::BigInt(l0), Self::Float(r0)) => l0 == r0,
+            (Self::Float(l0), Self::BigInt(r0)) => r0 == l0,
             (Self::Boolean(l0), Self::Boolean(r0)) => l0 == r0,
             (Self::String(l0), Self::String(r0)) => l0 == r0,
             (Self::Object(l0), Self::Object(r0)) => l0 == r0,
@@ -402,6 +410,7 @@ impl std::fmt::Debug for RuntimeValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer(x) => write!(f, "{}", x.raw_value()),
+            Self::BigInt(x) => write!(f, "{}", x.as_bigint()),
             Self::Float(x) => write!(f, "{}", x.raw_value()),
             Self::Boolean(x) => write!(f, "{}", x.raw_value()),
             Self::String(s) => write!(f, "\"{}\"", s.raw_value()),
@@ -499,7 +508,7 @@ impl RuntimeValue {
 
     pub fn eval(
         &self,
-        argc: u8,
+        argc: u16,
         cur_frame: &mut Frame,
         vm: &mut VirtualMachine,
         discard_result: bool,
@@ -516,6 +525,33 @@ impl RuntimeValue {
         }
     }
 
+    pub fn eval_named(
+        &self,
+        argc: u16,
+        names: &[String],
+        cur_frame: &mut Frame,
+        vm: &mut VirtualMachine,
+        discard_result: bool,
+    ) -> ExecutionResult<CallResult> {
+        if let Some(f) = self.as_function() {
+            f.eval_named(
+                argc,
+                names,
+                cur_frame,
+                vm,
+                &Default::default(),
+                discard_result,
+            )
+        } else if let Some(bf) = self.as_bound_function() {
+            bf.eval_named(argc, names, cur_frame, vm, discard_result)
+        } else {
+            match self.read_attribute("_op_impl_call", &vm.builtins) {
+                Ok(op_call) => op_call.eval_named(argc, names, cur_frame, vm, discard_result),
+                _ => Err(crate::error::vm_error::VmErrorReason::UnexpectedType.into()),
+            }
+        }
+    }
+
     pub fn prettyprint(&self, cur_frame: &mut Frame, vm: &mut VirtualMachine) -> String {
         if let Ok(ppf) = self.read_attribute("prettyprint", &vm.builtins)
             && ppf.eval(0, cur_frame, vm, false).is_ok()
@@ -542,6 +578,9 @@ impl RuntimeValue {
         } else if let Some(i) = self.as_integer() {
             i.write(attr_name, val);
             Ok(())
+        } else if let Some(i) = self.as_big_int() {
+            i.write(attr_name, val);
+            Ok(())
         } else if let Some(i) = self.as_float() {
             i.write(attr_name, val);
             Ok(())
@@ -589,6 +628,13 @@ impl RuntimeValue {
             let bt = builtins.get_builtin_type_by_id(BUILTIN_TYPE_INT).unwrap();
             attrs.extend(bt.list_attributes());
             attrs.iter().cloned().collect()
+        } else if let Some(i) = self.as_big_int() {
+            let mut attrs = i.list_attributes();
+            let bt = builtins
+                .get_builtin_type_by_id(BUILTIN_TYPE_BIGINT)
+                .unwrap();
+            attrs.extend(bt.list_attributes());
+            attrs.iter().cloned().collect()
         } else if let Some(i) = self.as_float() {
             let mut attrs = i.list_attributes();
             let bt = builtins.get_builtin_type_by_id(BUILTIN_TYPE_FLOAT).unwrap();
@@ -660,6 +706,21 @@ impl RuntimeValue {
                     }
                 }
             }
+        } else if let Some(i) = self.as_big_int() {
+            match i.read(attrib_name) {
+                Some(val) => Ok(val),
+                _ => {
+                    let bt = builtins
+                        .get_builtin_type_by_id(BUILTIN_TYPE_BIGINT)
+                        .unwrap();
+                    match bt.read_attribute(attrib_name) {
+                        Ok(val) => {
+                            val_or_bound_func!(val, self)
+                        }
+                        _ => Err(AttributeError::NoSuchAttribute),
+                    }
+                }
+            }
         } else if let Some(i) = self.as_float() {
             match i.read(attrib_name) {
                 Some(val) => Ok(val),
@@ -751,7 +812,7 @@ impl RuntimeValue {
                 for idx in indices.iter().rev() {
                     cur_frame.stack.push(idx.clone());
                 }
-                read_index.eval(indices.len() as u8, cur_frame, vm, false)
+                read_index.eval(indices.len() as u16, cur_frame, vm, false)
             }
             _ => Err(VmErrorReason::UnexpectedType.into()),
         }
@@ -770,7 +831,7 @@ impl RuntimeValue {
                 for idx in indices.iter().rev() {
                     cur_frame.stack.push(idx.clone());
                 }
-                write_index.eval(1 + indices.len() as u8, cur_frame, vm, true)
+                write_index.eval(1 + indices.len() as u16, cur_frame, vm, true)
             }
             _ => Err(VmErrorReason::UnexpectedType.into()),
         }
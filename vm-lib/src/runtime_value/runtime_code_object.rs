@@ -8,9 +8,10 @@ use aria_parser::ast::SourcePointer;
 pub struct CodeObject {
     pub name: String,
     pub body: Rc<[u8]>,
-    pub required_argc: u8,
-    pub default_argc: u8,
-    pub frame_size: u8,
+    pub required_argc: u16,
+    pub default_argc: u16,
+    pub arg_names: Rc<[String]>,
+    pub frame_size: u16,
     pub loc: SourcePointer,
     pub line_table: Rc<LineTable>,
 }
@@ -28,6 +29,7 @@ impl From<&CompiledCodeObject> for CodeObject {
             body: Rc::from(value.body.as_slice()),
             required_argc: value.required_argc,
             default_argc: value.default_argc,
+            arg_names: Rc::from(value.arg_names.as_slice()),
             frame_size: value.frame_size,
             loc: value.loc.clone(),
             line_table: Rc::from(value.line_table.clone()),
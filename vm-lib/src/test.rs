@@ -1,22 +1,25 @@
 // SPDX-License-Identifier: Apache-2.0
-use aria_compiler::compile_from_source;
+use aria_compiler::{
+    CompilationOptions, compile_from_source,
+    constant_value::ConstantValue,
+    do_compile::{CompilationErrorReason, CompilationWarningReason},
+    module_file::{MODULE_FILE_MAGIC, ModuleFileError, read_module_file, write_module_file},
+};
 use aria_parser::ast::SourceBuffer;
 
 use crate::{
     HaxbyEvalResult,
     error::vm_error::VmErrorReason,
     haxby_eval,
+    module_cache::ModuleCache,
     vm::{ExecutionResult, VmOptions},
 };
 
-fn exec_code(src: &'static str) -> ExecutionResult<HaxbyEvalResult> {
+fn exec_code(src: &str) -> ExecutionResult<HaxbyEvalResult> {
     exec_code_with_vm_options(src, Default::default())
 }
 
-fn exec_code_with_vm_options(
-    src: &'static str,
-    vm_opts: VmOptions,
-) -> ExecutionResult<HaxbyEvalResult> {
+fn exec_code_with_vm_options(src: &str, vm_opts: VmOptions) -> ExecutionResult<HaxbyEvalResult> {
     let sb = SourceBuffer::stdin(src);
     let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
     haxby_eval(module, vm_opts)
@@ -270,3 +273,400 @@ func main(x,y,z,...) {
             .is_err_and(|err| err.reason == VmErrorReason::InvalidMainSignature)
     );
 }
+
+#[test]
+fn test_many_locals() {
+    let decls = (0..300)
+        .map(|i| format!("    val x{i} = {i};"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let sum = (0..300)
+        .map(|i| format!("x{i}"))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let input = format!(
+        "func main() {{\n{decls}\n    x299 = x299 + 1;\n    assert x299 == 300;\n    assert {sum} + 1 == 44852;\n    val f = |y| => x280 + y;\n    assert f(1) == 281;\n}}\n"
+    );
+
+    assert!(exec_code(&input).is_ok());
+}
+
+#[test]
+fn test_many_arguments() {
+    let params = (0..300)
+        .map(|i| format!("a{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let args = (0..300)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let input = format!(
+        "func many({params}, d=7) {{\n    assert a0 == 0;\n    assert a299 == 299;\n    return d;\n}}\nfunc main() {{\n    assert many({args}) == 7;\n    assert many({args}, 9) == 9;\n}}\n"
+    );
+
+    assert!(exec_code(&input).is_ok());
+}
+
+#[test]
+fn test_many_constants() {
+    let funcs = (0..1000)
+        .map(|f| {
+            let strings = (0..100)
+                .map(|s| format!("\"s_{f}_{s}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("func t{f}() {{\n    return [{strings}];\n}}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let input = format!(
+        "{funcs}\nfunc main() {{\n    assert t0()[0] == \"s_0_0\";\n    assert t999()[99] == \"s_999_99\";\n    assert t999().len() == 100;\n}}\n"
+    );
+
+    let sb = SourceBuffer::stdin(&input);
+    let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
+    assert!(module.constants.len() > 100000);
+    assert!(haxby_eval(module, Default::default()).is_ok());
+}
+
+#[test]
+fn test_constant_propagation_through_locals() {
+    let input = r##"
+func main() {
+    val minutes = 60;
+    val seconds = minutes * 60;
+    val day = seconds * 24;
+    val n = 5;
+    n = n * 7;
+    assert day == 86400;
+    assert n == 35;
+}
+"##;
+
+    let sb = SourceBuffer::stdin(input);
+    let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
+    let ints = module
+        .constants
+        .values()
+        .filter_map(|c| match c {
+            ConstantValue::Integer(n) => Some(*n),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    // day is folded all the way into the assertion, so none of the values leading
+    // up to it are in the constant table; n is written twice, so it is not propagated
+    assert!(ints.contains(&5));
+    assert!(!ints.contains(&60));
+    assert!(!ints.contains(&3600));
+    assert!(haxby_eval(module, Default::default()).is_ok());
+}
+
+#[test]
+fn test_unused_local_warnings() {
+    let input = r##"
+func main() {
+    val minutes = 60;
+    val typed: Int = 3;
+    val captured = 4;
+    val f = |x| => x + captured;
+    val dead = 5;
+    return minutes * 60;
+    println(dead);
+}
+"##;
+
+    let sb = SourceBuffer::stdin(input);
+    let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
+    let mut unused = module
+        .warnings
+        .iter()
+        .filter_map(|w| match &w.reason {
+            CompilationWarningReason::UnusedLocal(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    unused.sort();
+    // minutes is folded away, but it is still read by the source; dead is only
+    // read by unreachable code
+    assert_eq!(unused, ["dead", "f", "typed"]);
+}
+
+#[test]
+fn test_closure_captures_through_four_levels() {
+    let input = r##"
+func outer(a) {
+    return |b| => {
+        val c = b * 10;
+        return |d| => {
+            val e = d + c;
+            return || => a + b + c + d + e;
+        };
+    };
+}
+
+func main() {
+    val one = outer(1);
+    val two = outer(1000);
+    val inner = one(2)(3);
+    assert inner() == 1 + 2 + 20 + 3 + 23;
+    assert two(2)(3)() == 1000 + 2 + 20 + 3 + 23;
+    assert one(4)(5)() == 1 + 4 + 40 + 5 + 45;
+    assert inner() == 49;
+}
+"##;
+
+    assert!(exec_code(input).is_ok());
+}
+
+#[test]
+fn test_finally_cannot_be_left() {
+    for input in [
+        "func main() { try {} finally { return 1; } }",
+        "func main() { while true { try {} finally { break; } } }",
+        "func main() { for x in [1] { try {} finally { continue; } } }",
+        "func main() { try {} finally { val x = Maybe::Some(1)??; } }",
+    ] {
+        let sb = SourceBuffer::stdin(input);
+        let errors = compile_from_source(&sb, &Default::default())
+            .err()
+            .expect("module should not compile");
+        assert!(
+            errors
+                .iter()
+                .all(|err| matches!(err.reason, CompilationErrorReason::FlowControlInFinally))
+        );
+    }
+}
+
+#[test]
+fn test_capture_with_no_locals_left() {
+    let locals: String = (0..u16::MAX).map(|i| format!("val l{i} = 0;")).collect();
+    let input = format!("func main() {{ val x = 1; val f = || => {{ {locals} return x; }}; }}");
+    let sb = SourceBuffer::stdin(&input);
+    let errors = compile_from_source(&sb, &Default::default())
+        .err()
+        .expect("module should not compile");
+    assert!(matches!(
+        errors[0].reason,
+        CompilationErrorReason::TooManyLocals
+    ));
+}
+
+#[test]
+fn test_named_argument_repeated_in_call() {
+    let sb = SourceBuffer::stdin("func f(a, b) {} func main() { f(a: 1, b: 2, a: 3); }");
+    let errors = compile_from_source(&sb, &Default::default())
+        .err()
+        .expect("module should not compile");
+    assert!(matches!(
+        &errors[0].reason,
+        CompilationErrorReason::DuplicateNamedArgument(name) if name == "a"
+    ));
+}
+
+#[test]
+fn test_if_expression_requires_else() {
+    let sb = SourceBuffer::stdin("func main() { val x = if true { 1 } elsif false { 2 }; }");
+    let errors = compile_from_source(&sb, &Default::default())
+        .err()
+        .expect("module should not compile");
+    assert!(matches!(
+        errors[0].reason,
+        CompilationErrorReason::IfExpressionWithoutElse
+    ));
+}
+
+#[test]
+fn test_parser_reports_every_syntax_error() {
+    let input = r##"
+func foo(x) {
+    val y = x + ;
+    return y;
+}
+
+func main() {
+    val s = "not; the } end";
+    val z = foo(1 2);
+    println(s);
+}
+"##;
+
+    let sb = SourceBuffer::stdin(input);
+    let errors = compile_from_source(&sb, &Default::default())
+        .err()
+        .expect("module should not compile");
+    assert_eq!(2, errors.len());
+    assert!(
+        errors
+            .iter()
+            .all(|e| matches!(e.reason, CompilationErrorReason::ParserError(_)))
+    );
+    let lines: Vec<_> = errors
+        .iter()
+        .map(|e| sb.line_index_for_position(e.loc.location.start))
+        .collect();
+    assert_eq!(vec![2, 8], lines);
+}
+
+#[test]
+fn test_error_location_spans_postfix_chain() {
+    let input = r##"
+func main() {
+    val x = [[1, 2], [3, 4]];
+    val i = 1;
+    return x[i].len().foo + 1;
+}
+"##;
+
+    let e = match exec_code(input).expect("ok result expected").exit {
+        crate::vm::RunloopExit::Exception(e) => e,
+        _ => panic!("expected exception to be thrown"),
+    };
+    let loc = e
+        .backtrace
+        .first_entry()
+        .expect("backtrace expected")
+        .location;
+    assert_eq!("x[i].len().foo", &input[loc.start..loc.stop]);
+}
+
+#[test]
+fn test_error_location_spans_operator_chain() {
+    let input = r##"
+func main() {
+    val s = "a";
+    return 1 + 2 - s + 3;
+}
+"##;
+
+    let e = match exec_code(input).expect("ok result expected").exit {
+        crate::vm::RunloopExit::Exception(e) => e,
+        _ => panic!("expected exception to be thrown"),
+    };
+    let loc = e
+        .backtrace
+        .first_entry()
+        .expect("backtrace expected")
+        .location;
+    assert_eq!("1 + 2 - s", &input[loc.start..loc.stop]);
+}
+
+#[test]
+fn test_module_file_roundtrip() {
+    let input = r##"
+struct Pair {
+    type func new(a, b) = alloc(This) { .a = a, .b = b };
+    func sum() = this.a + this.b;
+}
+
+func main() {
+    val p = Pair.new(3, b: 4.5);
+    assert p.sum() == 7.5;
+    assert f"{p.a}-{"x"}" == "3-x";
+    assert p.a + 1 != 5;
+}
+"##;
+
+    let sb = SourceBuffer::stdin(input);
+    let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
+    let data = write_module_file(&module);
+    let module = read_module_file(&data).expect("module file did not load");
+    assert_eq!(data, write_module_file(&module));
+    assert!(haxby_eval(module, Default::default()).is_ok());
+}
+
+#[test]
+fn test_module_file_rejects_bad_input() {
+    let sb = SourceBuffer::stdin("func main() {}");
+    let module = compile_from_source(&sb, &Default::default()).expect("module did not compile");
+    let data = write_module_file(&module);
+
+    assert!(matches!(
+        read_module_file(b"func main() {}"),
+        Err(ModuleFileError::NotAModuleFile)
+    ));
+    assert!(matches!(
+        read_module_file(&data[..data.len() - 1]),
+        Err(ModuleFileError::Truncated)
+    ));
+
+    // the version string follows the magic and its own length
+    let mut other_version = data.clone();
+    other_version[MODULE_FILE_MAGIC.len() + 4] = b'X';
+    assert!(matches!(
+        read_module_file(&other_version),
+        Err(ModuleFileError::VersionMismatch(..))
+    ));
+}
+
+#[test]
+fn test_module_cache_is_keyed_on_source_and_options() {
+    let dir = std::env::temp_dir().join(format!("aria-module-cache-test-{}", std::process::id()));
+    let cache = ModuleCache::new(dir.clone());
+    let optimized = CompilationOptions {
+        optimize: true,
+        ..Default::default()
+    };
+    let unoptimized = CompilationOptions {
+        optimize: false,
+        ..Default::default()
+    };
+
+    let sb = SourceBuffer::stdin_with_name("func main() { assert 1 + 1 == 2; }", "cached.aria");
+    assert!(cache.load(&sb, &optimized).is_none());
+    let module = compile_from_source(&sb, &optimized).expect("module did not compile");
+    cache.store(&sb, &optimized, &module);
+
+    let cached = cache.load(&sb, &optimized).expect("module was not cached");
+    assert!(haxby_eval(cached, Default::default()).is_ok());
+    assert!(cache.load(&sb, &unoptimized).is_none());
+
+    let changed =
+        SourceBuffer::stdin_with_name("func main() { assert 1 + 1 == 3; }", "cached.aria");
+    assert!(cache.load(&changed, &optimized).is_none());
+
+    cache.prune(std::time::Duration::from_secs(3600));
+    assert!(cache.load(&sb, &optimized).is_some());
+    cache.prune(std::time::Duration::ZERO);
+    assert!(cache.load(&sb, &optimized).is_none());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_module_cache_prunes_at_most_once_a_day() {
+    let dir = std::env::temp_dir().join(format!("aria-module-prune-test-{}", std::process::id()));
+    let options = CompilationOptions::default();
+    let stale_entry = |name: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, []).unwrap();
+        let long_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(365 * 86400);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(long_ago)
+            .unwrap();
+        path
+    };
+    let store = |cache: &ModuleCache, src: &str| {
+        let sb = SourceBuffer::stdin_with_name(src, "pruned.aria");
+        let module = compile_from_source(&sb, &options).expect("module did not compile");
+        cache.store(&sb, &options, &module);
+    };
+
+    std::fs::create_dir_all(&dir).unwrap();
+    let first = stale_entry("first.ariac");
+    store(&ModuleCache::new(dir.clone()), "func main() {}");
+    assert!(!first.exists());
+
+    // the cache was pruned just now, so neither this cache nor a new one scans it again
+    let cache = ModuleCache::new(dir.clone());
+    let second = stale_entry("second.ariac");
+    store(&cache, "func main() { val x = 1; }");
+    store(&cache, "func main() { val x = 2; }");
+    assert!(second.exists());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
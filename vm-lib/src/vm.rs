@@ -6,12 +6,19 @@ use std::{
     rc::Rc,
 };
 
-use aria_compiler::{bc_reader::BytecodeReader, compile_from_source, module::CompiledModule};
+use aria_compiler::{
+    CompilationOptions,
+    bc_reader::BytecodeReader,
+    compile_from_source,
+    do_compile::{CompilationError, CompilationResult},
+    module::CompiledModule,
+};
 use aria_parser::ast::{SourceBuffer, prettyprint::printout_accumulator::PrintoutAccumulator};
 use haxby_opcodes::{
     Opcode,
     builtin_type_ids::{BUILTIN_TYPE_MAYBE, BUILTIN_TYPE_RESULT},
     enum_case_attribs::CASE_HAS_PAYLOAD,
+    range_flags::{RANGE_HAS_STEP, RANGE_INCLUSIVE},
     runtime_value_ids::RUNTIME_VALUE_THIS_MODULE,
 };
 use std::sync::OnceLock;
@@ -25,10 +32,12 @@ use crate::{
         vm_error::{VmError, VmErrorReason},
     },
     frame::Frame,
+    module_cache::ModuleCache,
     opcodes::prettyprint::opcode_prettyprint,
     runtime_module::RuntimeModule,
     runtime_value::{
         RuntimeValue,
+        bigint::BigIntValue,
         enumeration::{Enum, EnumCase},
         function::Function,
         isa::IsaCheckable,
@@ -50,6 +59,10 @@ pub struct VmOptions {
     pub dump_stack: bool,
     pub vm_args: Vec<String>,
     pub console: ConsoleHandle,
+    pub optimize_imports: bool,
+    // the directory to cache compiled imports in (see ModuleCache::default_dir);
+    // off by default, so that embedders and tests never write to the user's cache
+    pub module_cache: Option<PathBuf>,
 }
 
 impl Default for VmOptions {
@@ -59,6 +72,8 @@ impl Default for VmOptions {
             dump_stack: Default::default(),
             vm_args: Default::default(),
             console: Rc::new(RefCell::new(StdConsole {})),
+            optimize_imports: true,
+            module_cache: None,
         }
     }
 }
@@ -70,14 +85,17 @@ pub struct VirtualMachine {
     pub import_stack: Stack<String>,
     pub imported_modules: HashMap<String, ModuleLoadInfo>,
     pub loaded_dylibs: HashMap<String, libloading::Library>,
+    module_cache: Option<ModuleCache>,
 }
 
-const BUILTIN_VALUES_TO_INJECT: [(&str, &str); 5] = [
+const BUILTIN_VALUES_TO_INJECT: [(&str, &str); 7] = [
     ("Unit", include_str!("builtins/unit.aria")),
     ("Unimplemented", include_str!("builtins/unimplemented.aria")),
     ("Maybe", include_str!("builtins/maybe.aria")),
     ("Result", include_str!("builtins/result.aria")),
     ("RuntimeError", include_str!("builtins/runtime_error.aria")),
+    ("Generator", include_str!("builtins/generator.aria")),
+    ("Range", include_str!("builtins/range.aria")),
 ];
 
 impl VirtualMachine {
@@ -142,12 +160,14 @@ impl VirtualMachine {
             import_stack: Default::default(),
             imported_modules: Default::default(),
             loaded_dylibs: Default::default(),
+            module_cache: None,
         }
         .load_version_into_builtins();
         for (builtin_name, source) in BUILTIN_VALUES_TO_INJECT {
             let rmod = this.load_core_file_into_builtins(builtin_name, source);
             this = this.load_named_value_into_builtins(builtin_name, &rmod);
         }
+        this.module_cache = this.options.module_cache.clone().map(ModuleCache::new);
 
         this
     }
@@ -250,6 +270,23 @@ macro_rules! unaryop_eval {
     };
 }
 
+// pushes the result of an integer operation; the checked variants of the
+// arithmetic opcodes raise an overflow error instead of wrapping around
+macro_rules! push_int_result {
+    ($checked: expr, ($checked_val: expr), ($wrapping_val: expr), $next: expr, $frame: expr, $op_idx: expr) => {
+        if $checked {
+            match $checked_val {
+                Some(val) => $frame.stack.push(RuntimeValue::Integer(val)),
+                None => {
+                    return build_vm_error!(VmErrorReason::IntegerOverflow, $next, $frame, $op_idx);
+                }
+            }
+        } else {
+            $frame.stack.push(RuntimeValue::Integer($wrapping_val));
+        }
+    };
+}
+
 fn get_lib_path(lib_name: &str) -> PathBuf {
     let exe_path = std::env::current_exe().expect("failed to get current exe path");
     let exe_dir = exe_path.parent().expect("failed to get exe directory");
@@ -388,6 +425,28 @@ impl VirtualMachine {
         }
     }
 
+    fn compile_import(
+        &self,
+        sb: &SourceBuffer,
+    ) -> CompilationResult<CompiledModule, Vec<CompilationError>> {
+        let options = CompilationOptions {
+            optimize: self.options.optimize_imports,
+            ..Default::default()
+        };
+
+        if let Some(cache) = &self.module_cache
+            && let Some(cm) = cache.load(sb, &options)
+        {
+            return Ok(cm);
+        }
+
+        let cm = compile_from_source(sb, &options)?;
+        if let Some(cache) = &self.module_cache {
+            cache.store(sb, &options, &cm);
+        }
+        Ok(cm)
+    }
+
     fn resolve_import_path_to_path(
         ipath: &str,
         widget_root_path: Option<&PathBuf>,
@@ -565,7 +624,7 @@ impl VirtualMachine {
                 .rev()
                 .map(|arg| RuntimeValue::String(arg.as_str().into()))
                 .for_each(|arg| main_frame.stack.push(arg));
-            self.options.vm_args.len() as u8
+            self.options.vm_args.len() as u16
         } else {
             return Err(VmErrorReason::InvalidMainSignature.into());
         };
@@ -598,7 +657,227 @@ impl VirtualMachine {
         target_frame: &mut Frame,
     ) -> ExecutionResult<RunloopExit> {
         let mut bc_reader = BytecodeReader::from(bc);
-        self.runloop(&mut bc_reader, module, target_frame)
+        if let Some(resume_at) = target_frame.suspended_at.take() {
+            bc_reader.jump_to_index(resume_at);
+        }
+        let exit = self.runloop(&mut bc_reader, module, target_frame)?;
+        if target_frame.is_suspended() {
+            Ok(exit)
+        } else {
+            self.run_deferred(target_frame, exit)
+        }
+    }
+
+    // deferred calls run whether the function returned or threw; an exception
+    // thrown by one of them replaces the result, and the remaining ones still run
+    fn run_deferred(
+        &mut self,
+        frame: &mut Frame,
+        mut exit: RunloopExit,
+    ) -> ExecutionResult<RunloopExit> {
+        while let Some(deferred) = frame.deferred.pop() {
+            if let crate::runtime_value::CallResult::Exception(e) =
+                deferred.eval(0, frame, self, true)?
+            {
+                exit = RunloopExit::Exception(e);
+            }
+        }
+        Ok(exit)
+    }
+
+    fn read_uplevel(
+        &mut self,
+        n: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        if let Some(f) = &frame.func {
+            if let Some(bcf) = f.imp.as_bytecode_function() {
+                match bcf.read_uplevel(n) {
+                    Some(ulv) => {
+                        frame.stack.push(ulv);
+                    }
+                    _ => {
+                        return build_vm_error!(
+                            VmErrorReason::UplevelOutOfBounds(n as usize),
+                            next,
+                            frame,
+                            op_idx
+                        );
+                    }
+                }
+            } else {
+                return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+            }
+        } else {
+            return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+        }
+        Ok(OpcodeRunExit::Continue)
+    }
+
+    fn store_uplevel(
+        &mut self,
+        n: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let x = pop_or_err!(next, frame, op_idx);
+        if let Some(f) = x.as_function() {
+            if let Some(bcf) = f.imp.as_bytecode_function() {
+                let local = frame.locals[n as usize].val.clone();
+                bcf.store_uplevel(n, local);
+            } else {
+                return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+            }
+        } else {
+            return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+        }
+        frame.stack.push(x);
+        Ok(OpcodeRunExit::Continue)
+    }
+
+    fn write_local(
+        &mut self,
+        n: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let x = pop_or_err!(next, frame, op_idx);
+        let local = &mut frame.locals[n as usize];
+        if !local.ty.isa_check(&x, &self.builtins) {
+            return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+        } else {
+            local.val = x;
+        }
+        Ok(OpcodeRunExit::Continue)
+    }
+
+    fn typedef_local(
+        &mut self,
+        n: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let t = pop_or_err!(next, frame, op_idx);
+        if let Ok(isa_check) = IsaCheckable::try_from(&t) {
+            frame.locals[n as usize].ty = isa_check;
+        } else {
+            return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+        }
+        Ok(OpcodeRunExit::Continue)
+    }
+
+    fn call(
+        &mut self,
+        argc: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let x = pop_or_err!(next, frame, op_idx);
+        let result = x.eval(argc, frame, self, false);
+        self.complete_call(result, next, op_idx, frame)
+    }
+
+    fn call_named(
+        &mut self,
+        argc: u16,
+        named_argc: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let x = pop_or_err!(next, frame, op_idx);
+        let names = Self::pop_argument_names(named_argc, &next, op_idx, frame)?;
+        let result = x.eval_named(argc, &names, frame, self, false);
+        self.complete_call(result, next, op_idx, frame)
+    }
+
+    // the positional arguments arrive as a single list, below the names of
+    // any named arguments, and are unpacked onto the stack for the call
+    fn call_spread(
+        &mut self,
+        named_argc: u16,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        let x = pop_or_err!(next, frame, op_idx);
+        let names = Self::pop_argument_names(named_argc, &next, op_idx, frame)?;
+        let args = pop_or_err!(next, frame, op_idx);
+        let Some(args) = args.as_list() else {
+            return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+        };
+        let Ok(argc) = u16::try_from(args.len()) else {
+            return build_vm_error!(
+                VmErrorReason::MismatchedArgumentCount(u16::MAX as usize, args.len()),
+                next,
+                frame,
+                op_idx
+            );
+        };
+        for idx in (0..args.len()).rev() {
+            if let Some(arg) = args.get_at(idx) {
+                frame.stack.push(arg);
+            }
+        }
+        let result = if names.is_empty() {
+            x.eval(argc, frame, self, false)
+        } else {
+            x.eval_named(argc, &names, frame, self, false)
+        };
+        self.complete_call(result, next, op_idx, frame)
+    }
+
+    fn pop_argument_names(
+        named_argc: u16,
+        next: &Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<Vec<String>, VmError> {
+        let mut names = Vec::with_capacity(named_argc as usize);
+        for _ in 0..named_argc {
+            match pop_or_err!(next.clone(), frame, op_idx).as_string() {
+                Some(name) => names.push(name.raw_value().to_owned()),
+                None => {
+                    return build_vm_error!(
+                        VmErrorReason::UnexpectedType,
+                        next.clone(),
+                        frame,
+                        op_idx
+                    );
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    fn complete_call(
+        &mut self,
+        result: ExecutionResult<crate::runtime_value::CallResult>,
+        next: Opcode,
+        op_idx: usize,
+        frame: &mut Frame,
+    ) -> ExecutionResult<OpcodeRunExit, VmError> {
+        match result {
+            Ok(crate::runtime_value::CallResult::OkNoValue)
+            | Ok(crate::runtime_value::CallResult::Ok(_)) => {}
+            Ok(crate::runtime_value::CallResult::Exception(e)) => {
+                return Ok(OpcodeRunExit::Exception(e));
+            }
+            Err(err) => {
+                if err.loc.is_some() {
+                    return Err(err);
+                } else {
+                    return build_vm_error!(err.reason, next, frame, op_idx);
+                }
+            }
+        }
+        Ok(OpcodeRunExit::Continue)
     }
 
     fn run_opcode(
@@ -659,11 +938,18 @@ impl VirtualMachine {
                     frame.stack.push(val);
                 }
             }
-            Opcode::Add => {
+            Opcode::Add | Opcode::AddChecked => {
                 let x = pop_or_err!(next, frame, op_idx);
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
-                    frame.stack.push(RuntimeValue::Integer(b + a));
+                    push_int_result!(
+                        matches!(next, Opcode::AddChecked),
+                        (b.checked_add(a)),
+                        (b + a),
+                        next,
+                        frame,
+                        op_idx
+                    );
                 } else if let (RuntimeValue::Integer(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b + &a.to_fp()));
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Integer(b)) = (&x, &y) {
@@ -672,6 +958,10 @@ impl VirtualMachine {
                     frame.stack.push(RuntimeValue::String(b + a));
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b + a))
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b + &a));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Float(&b + &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::add(&y, &x, frame, self)),
@@ -681,17 +971,28 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::Sub => {
+            Opcode::Sub | Opcode::SubChecked => {
                 let x = pop_or_err!(next, frame, op_idx);
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
-                    frame.stack.push(RuntimeValue::Integer(b - a));
+                    push_int_result!(
+                        matches!(next, Opcode::SubChecked),
+                        (b.checked_sub(a)),
+                        (b - a),
+                        next,
+                        frame,
+                        op_idx
+                    );
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b - a))
                 } else if let (RuntimeValue::Integer(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b - &a.to_fp()));
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(&b.to_fp() - a));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b - &a));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Float(&b - &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::sub(&y, &x, frame, self)),
@@ -701,17 +1002,28 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::Mul => {
+            Opcode::Mul | Opcode::MulChecked => {
                 let x = pop_or_err!(next, frame, op_idx);
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
-                    frame.stack.push(RuntimeValue::Integer(b * a));
+                    push_int_result!(
+                        matches!(next, Opcode::MulChecked),
+                        (b.checked_mul(a)),
+                        (b * a),
+                        next,
+                        frame,
+                        op_idx
+                    );
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b * a))
                 } else if let (RuntimeValue::Integer(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b * &a.to_fp()));
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(&b.to_fp() * a));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b * &a));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Float(&b * &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::mul(&y, &x, frame, self)),
@@ -721,14 +1033,21 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::Div => {
+            Opcode::Div | Opcode::DivChecked => {
                 let x = pop_or_err!(next, frame, op_idx);
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     if a.raw_value() == 0 {
                         return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
                     }
-                    frame.stack.push(RuntimeValue::Integer(b / a));
+                    push_int_result!(
+                        matches!(next, Opcode::DivChecked),
+                        (b.checked_div(a)),
+                        (b / a),
+                        next,
+                        frame,
+                        op_idx
+                    );
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Float(b)) = (&x, &y) {
                     if a.raw_value() == 0.0 {
                         return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
@@ -744,6 +1063,16 @@ impl VirtualMachine {
                         return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
                     }
                     frame.stack.push(RuntimeValue::Float(&b.to_fp() / a));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    if a.is_zero() {
+                        return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
+                    }
+                    frame.stack.push(RuntimeValue::BigInt(&b / &a));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    if a.raw_value() == 0.0 {
+                        return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
+                    }
+                    frame.stack.push(RuntimeValue::Float(&b / &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::div(&y, &x, frame, self)),
@@ -753,10 +1082,14 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::Rem => {
+            // the remainder cannot overflow (i64::MIN % -1 is 0), so both variants agree
+            Opcode::Rem | Opcode::RemChecked => {
                 let x = pop_or_err!(next, frame, op_idx);
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
+                    if a.raw_value() == 0 {
+                        return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
+                    }
                     frame.stack.push(RuntimeValue::Integer(b % a));
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Float(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(b % a))
@@ -764,6 +1097,13 @@ impl VirtualMachine {
                     frame.stack.push(RuntimeValue::Float(b % &a.to_fp()))
                 } else if let (RuntimeValue::Float(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Float(&b.to_fp() % a))
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    if a.is_zero() {
+                        return build_vm_error!(VmErrorReason::DivisionByZero, next, frame, op_idx);
+                    }
+                    frame.stack.push(RuntimeValue::BigInt(&b % &a));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Float(&b % &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::rem(&y, &x, frame, self)),
@@ -773,21 +1113,50 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::Neg => {
+            Opcode::Neg | Opcode::NegChecked => {
                 let n = pop_or_err!(next, frame, op_idx);
                 if let RuntimeValue::Integer(i) = &n {
-                    frame.stack.push(RuntimeValue::Integer(-i));
+                    push_int_result!(
+                        matches!(next, Opcode::NegChecked),
+                        (i.checked_neg()),
+                        (-i),
+                        next,
+                        frame,
+                        op_idx
+                    );
                 } else if let RuntimeValue::Float(i) = &n {
                     frame.stack.push(RuntimeValue::Float(-i));
+                } else if let RuntimeValue::BigInt(i) = &n {
+                    frame.stack.push(RuntimeValue::BigInt(-i));
                 } else {
                     unaryop_eval!((RuntimeValue::neg(&n, frame, self)), next, frame, op_idx)
                 }
             }
-            Opcode::ShiftLeft => {
+            Opcode::ShiftLeft | Opcode::ShiftLeftChecked => {
                 let by = pop_or_err!(next, frame, op_idx);
                 let n = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(n), RuntimeValue::Integer(by)) = (&n, &by) {
-                    frame.stack.push(RuntimeValue::Integer(n << by));
+                    push_int_result!(
+                        matches!(next, Opcode::ShiftLeftChecked),
+                        (n.checked_shl(by)),
+                        (n << by),
+                        next,
+                        frame,
+                        op_idx
+                    );
+                } else if let Some((n, by)) = BigIntValue::promote(&n, &by) {
+                    let by = match by.left_shift_amount() {
+                        Ok(by) => by,
+                        Err(msg) => {
+                            return build_vm_error!(
+                                VmErrorReason::OperationFailed(msg.to_owned()),
+                                next,
+                                frame,
+                                op_idx
+                            );
+                        }
+                    };
+                    frame.stack.push(RuntimeValue::BigInt(&n << by));
                 } else {
                     binop_eval!(
                         (RuntimeValue::leftshift(&n, &by, frame, self)),
@@ -797,11 +1166,31 @@ impl VirtualMachine {
                     )
                 }
             }
-            Opcode::ShiftRight => {
+            Opcode::ShiftRight | Opcode::ShiftRightChecked => {
                 let by = pop_or_err!(next, frame, op_idx);
                 let n = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(n), RuntimeValue::Integer(by)) = (&n, &by) {
-                    frame.stack.push(RuntimeValue::Integer(n >> by));
+                    push_int_result!(
+                        matches!(next, Opcode::ShiftRightChecked),
+                        (n.checked_shr(by)),
+                        (n >> by),
+                        next,
+                        frame,
+                        op_idx
+                    );
+                } else if let Some((n, by)) = BigIntValue::promote(&n, &by) {
+                    let by = match by.right_shift_amount() {
+                        Ok(by) => by,
+                        Err(msg) => {
+                            return build_vm_error!(
+                                VmErrorReason::OperationFailed(msg.to_owned()),
+                                next,
+                                frame,
+                                op_idx
+                            );
+                        }
+                    };
+                    frame.stack.push(RuntimeValue::BigInt(&n >> by));
                 } else {
                     binop_eval!(
                         (RuntimeValue::rightshift(&n, &by, frame, self)),
@@ -841,6 +1230,10 @@ impl VirtualMachine {
                     frame
                         .stack
                         .push(RuntimeValue::Boolean(Into::into(b.to_fp() < *a)));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b < a)));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b < a)));
                 } else {
                     binop_eval!(
                         (RuntimeValue::less_than(&y, &x, frame, self)),
@@ -865,6 +1258,10 @@ impl VirtualMachine {
                     frame
                         .stack
                         .push(RuntimeValue::Boolean(Into::into(b.to_fp() <= *a)));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b <= a)));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b <= a)));
                 } else {
                     binop_eval!(
                         (RuntimeValue::less_than_equal(&y, &x, frame, self)),
@@ -889,6 +1286,10 @@ impl VirtualMachine {
                     frame
                         .stack
                         .push(RuntimeValue::Boolean(Into::into(b.to_fp() > *a)));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b > a)));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b > a)));
                 } else {
                     binop_eval!(
                         (RuntimeValue::greater_than(&y, &x, frame, self)),
@@ -913,6 +1314,10 @@ impl VirtualMachine {
                     frame
                         .stack
                         .push(RuntimeValue::Boolean(Into::into(b.to_fp() >= *a)));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b >= a)));
+                } else if let Some((b, a)) = BigIntValue::promote_fp(&y, &x) {
+                    frame.stack.push(RuntimeValue::Boolean(Into::into(b >= a)));
                 } else {
                     binop_eval!(
                         (RuntimeValue::greater_than_equal(&y, &x, frame, self)),
@@ -926,59 +1331,33 @@ impl VirtualMachine {
                 let local = frame.locals[n as usize].val.clone();
                 frame.stack.push(local);
             }
+            Opcode::ReadLocalWide(n) => {
+                let local = frame.locals[n as usize].val.clone();
+                frame.stack.push(local);
+            }
             Opcode::ReadUplevel(n) => {
-                if let Some(f) = &frame.func {
-                    if let Some(bcf) = f.imp.as_bytecode_function() {
-                        match bcf.read_uplevel(n) {
-                            Some(ulv) => {
-                                frame.stack.push(ulv);
-                            }
-                            _ => {
-                                return build_vm_error!(
-                                    VmErrorReason::UplevelOutOfBounds(n as usize),
-                                    next,
-                                    frame,
-                                    op_idx
-                                );
-                            }
-                        }
-                    } else {
-                        return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                    }
-                } else {
-                    return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                }
+                return self.read_uplevel(n.into(), next, op_idx, frame);
+            }
+            Opcode::ReadUplevelWide(n) => {
+                return self.read_uplevel(n, next, op_idx, frame);
             }
             Opcode::StoreUplevel(n) => {
-                let x = pop_or_err!(next, frame, op_idx);
-                if let Some(f) = x.as_function() {
-                    if let Some(bcf) = f.imp.as_bytecode_function() {
-                        let local = frame.locals[n as usize].val.clone();
-                        bcf.store_uplevel(n, local);
-                    } else {
-                        return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                    }
-                } else {
-                    return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                }
-                frame.stack.push(x);
+                return self.store_uplevel(n.into(), next, op_idx, frame);
+            }
+            Opcode::StoreUplevelWide(n) => {
+                return self.store_uplevel(n, next, op_idx, frame);
             }
             Opcode::WriteLocal(n) => {
-                let x = pop_or_err!(next, frame, op_idx);
-                let local = &mut frame.locals[n as usize];
-                if !local.ty.isa_check(&x, &self.builtins) {
-                    return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                } else {
-                    local.val = x;
-                }
+                return self.write_local(n.into(), next, op_idx, frame);
+            }
+            Opcode::WriteLocalWide(n) => {
+                return self.write_local(n, next, op_idx, frame);
             }
             Opcode::TypedefLocal(n) => {
-                let t = pop_or_err!(next, frame, op_idx);
-                if let Ok(isa_check) = IsaCheckable::try_from(&t) {
-                    frame.locals[n as usize].ty = isa_check;
-                } else {
-                    return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
-                }
+                return self.typedef_local(n.into(), next, op_idx, frame);
+            }
+            Opcode::TypedefLocalWide(n) => {
+                return self.typedef_local(n, next, op_idx, frame);
             }
             Opcode::ReadNamed(n) => {
                 if let Some(ct) = this_module.load_indexed_const(n)
@@ -1153,6 +1532,8 @@ impl VirtualMachine {
                     frame.stack.push(RuntimeValue::Boolean(a ^ b));
                 } else if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Integer(a ^ b));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b ^ &a));
                 } else {
                     binop_eval!(
                         (RuntimeValue::xor(&y, &x, frame, self)),
@@ -1167,6 +1548,8 @@ impl VirtualMachine {
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Integer(a & b));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b & &a));
                 } else if let Ok(x) = IsaCheckable::try_from(&x)
                     && let Ok(y) = IsaCheckable::try_from(&y)
                 {
@@ -1185,6 +1568,8 @@ impl VirtualMachine {
                 let y = pop_or_err!(next, frame, op_idx);
                 if let (RuntimeValue::Integer(a), RuntimeValue::Integer(b)) = (&x, &y) {
                     frame.stack.push(RuntimeValue::Integer(a | b));
+                } else if let Some((b, a)) = BigIntValue::promote(&y, &x) {
+                    frame.stack.push(RuntimeValue::BigInt(&b | &a));
                 } else if let Ok(x) = IsaCheckable::try_from(&x)
                     && let Ok(y) = IsaCheckable::try_from(&y)
                 {
@@ -1222,30 +1607,39 @@ impl VirtualMachine {
                 reader.jump_to_index(n as usize);
             }
             Opcode::JumpIfArgSupplied(arg, dest) => {
-                if frame.argc > arg {
+                if frame.is_arg_supplied(arg.into()) {
                     reader.jump_to_index(dest as usize);
                 }
             }
-            Opcode::Call(argc) => {
-                let x = pop_or_err!(next, frame, op_idx);
-                match x.eval(argc, frame, self, false) {
-                    Ok(crate::runtime_value::CallResult::OkNoValue)
-                    | Ok(crate::runtime_value::CallResult::Ok(_)) => {}
-                    Ok(crate::runtime_value::CallResult::Exception(e)) => {
-                        return Ok(OpcodeRunExit::Exception(e));
-                    }
-                    Err(err) => {
-                        if err.loc.is_some() {
-                            return Err(err);
-                        } else {
-                            return build_vm_error!(err.reason, next, frame, op_idx);
-                        }
-                    }
+            Opcode::JumpIfArgSuppliedWide(arg, dest) => {
+                if frame.is_arg_supplied(arg) {
+                    reader.jump_to_index(dest as usize);
                 }
             }
+            Opcode::Call(argc) => {
+                return self.call(argc.into(), next, op_idx, frame);
+            }
+            Opcode::CallWide(argc) => {
+                return self.call(argc, next, op_idx, frame);
+            }
+            Opcode::CallNamed(argc, named_argc) => {
+                return self.call_named(argc, named_argc, next, op_idx, frame);
+            }
+            Opcode::CallSpread(named_argc) => {
+                return self.call_spread(named_argc, next, op_idx, frame);
+            }
             Opcode::Return => {
                 return Ok(OpcodeRunExit::Return);
             }
+            Opcode::Yield => {
+                // leave the yielded value on the stack and remember where to pick up from
+                frame.suspended_at = Some(reader.get_index());
+                return Ok(OpcodeRunExit::Return);
+            }
+            Opcode::Defer => {
+                let deferred = pop_or_err!(next, frame, op_idx);
+                frame.deferred.push(deferred);
+            }
             Opcode::TryEnter(offset) => {
                 frame
                     .ctrl_blocks
@@ -1286,6 +1680,68 @@ impl VirtualMachine {
                 let list = RuntimeValue::List(list);
                 frame.stack.push(list);
             }
+            Opcode::BuildString(n) => {
+                let values = (0..n).map(|_| frame.stack.try_pop()).collect::<Vec<_>>();
+                let mut ret = String::new();
+                for value in values.iter().rev() {
+                    match value {
+                        Some(RuntimeValue::String(s)) => ret.push_str(&s.raw_value()),
+                        Some(x) => ret.push_str(&x.prettyprint(frame, self)),
+                        None => {
+                            return build_vm_error!(VmErrorReason::EmptyStack, next, frame, op_idx);
+                        }
+                    }
+                }
+                frame.stack.push(RuntimeValue::String(ret.into()));
+            }
+            Opcode::ExtendList => {
+                let src = pop_or_err!(next, frame, op_idx);
+                let dest = pop_or_err!(next, frame, op_idx);
+                match (src.as_list(), dest.as_list()) {
+                    (Some(src), Some(dest_list)) => {
+                        for idx in 0..src.len() {
+                            if let Some(val) = src.get_at(idx) {
+                                dest_list.append(val);
+                            }
+                        }
+                    }
+                    _ => {
+                        return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+                    }
+                }
+                frame.stack.push(dest);
+            }
+            Opcode::BuildRange(flags) => {
+                let from = pop_or_err!(next, frame, op_idx);
+                let to = pop_or_err!(next, frame, op_idx);
+                let step = if flags & RANGE_HAS_STEP != 0 {
+                    pop_or_err!(next, frame, op_idx)
+                } else {
+                    RuntimeValue::Integer(1.into())
+                };
+                let (from, to, step) = match (from.as_integer(), to.as_integer(), step.as_integer())
+                {
+                    (Some(from), Some(to), Some(step)) => {
+                        (from.raw_value(), to.raw_value(), step.raw_value())
+                    }
+                    _ => {
+                        return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+                    }
+                };
+                if step == 0 {
+                    return build_vm_error!(
+                        VmErrorReason::OperationFailed("range step cannot be zero".to_owned()),
+                        next,
+                        frame,
+                        op_idx
+                    );
+                }
+                let inclusive = flags & RANGE_INCLUSIVE != 0;
+                match crate::builtins::range::create_range(from, to, step, inclusive, self) {
+                    Ok(range) => frame.stack.push(range),
+                    Err(err) => return build_vm_error!(err.reason, next, frame, op_idx),
+                }
+            }
             Opcode::BuildFunction(a) => {
                 let val = pop_or_err!(next, frame, op_idx);
                 if let Some(co) = val.as_code_object() {
@@ -1494,7 +1950,9 @@ impl VirtualMachine {
                         .stack
                         .push(RuntimeValue::Boolean((ec.name == case_name).into()));
                 } else {
-                    return build_vm_error!(VmErrorReason::UnexpectedType, next, frame, op_idx);
+                    // a value that is not an enum can never match a case - this allows
+                    // nested patterns to test payloads of any type
+                    frame.stack.push(RuntimeValue::Boolean(false.into()));
                 }
             }
             Opcode::EnumTryExtractPayload => {
@@ -1559,10 +2017,17 @@ impl VirtualMachine {
                                 op_idx
                             );
                         }
+                        if mode == haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR {
+                            frame.stack.push(RuntimeValue::Boolean(true.into()));
+                        }
                     }
                     1 => {
                         // Err/None
                         match mode {
+                            haxby_opcodes::try_unwrap_protocol_mode::BRANCH_ON_ERROR => {
+                                frame.stack.push(val.clone());
+                                frame.stack.push(RuntimeValue::Boolean(false.into()));
+                            }
                             haxby_opcodes::try_unwrap_protocol_mode::PROPAGATE_ERROR => {
                                 frame.stack.push(val.clone());
                                 return Ok(OpcodeRunExit::Return); // implement a Return
@@ -1779,7 +2244,7 @@ impl VirtualMachine {
                         self.import_stack.push(ipath.clone());
                     }
 
-                    let c_module = match compile_from_source(&sb, &Default::default()) {
+                    let c_module = match self.compile_import(&sb) {
                         Ok(cm) => cm,
                         Err(ces) => {
                             let err_msg = ces